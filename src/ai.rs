@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+
+use crate::components::NpcBehaviorType;
+use crate::levels::TILE_SIZE;
+
+/// Everything an NPC's behavior tree is allowed to look at for one
+/// tick: where it stands, where safety and company are, and what the
+/// schedule says it should be doing.
+pub struct AiContext {
+    pub position: Vec2,
+    pub home: Vec2,
+    pub player: Option<Vec2>,
+    /// Nearest hazardous tile within smelling distance, if any.
+    pub hazard: Option<Vec2>,
+    pub storming: bool,
+    /// What the daily schedule (or party membership) asks for.
+    pub scheduled: NpcBehaviorType,
+    /// Standing wander destination, carried across frames so walks
+    /// look like walks instead of teleports.
+    pub wander_target: Option<Vec2>,
+}
+
+/// A leaf task the tree can settle on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Task {
+    Idle,
+    Wander,
+    FleeHazard,
+    SeekShelter,
+    FollowLeader,
+    ReturnHome,
+    Ascend,
+}
+
+/// A tiny behavior tree: selectors try children in order, gates run
+/// their child only while the predicate holds, leaves name a task.
+pub enum Behavior {
+    Select(Vec<Behavior>),
+    When(fn(&AiContext) -> bool, Box<Behavior>),
+    Do(Task),
+}
+
+impl Behavior {
+    /// Walk the tree and settle on a task; a selector with no willing
+    /// child yields nothing, which callers should read as idle.
+    pub fn evaluate(&self, ctx: &AiContext) -> Option<Task> {
+        match self {
+            Behavior::Select(children) => {
+                children.iter().find_map(|child| child.evaluate(ctx))
+            }
+            Behavior::When(predicate, child) => {
+                if predicate(ctx) {
+                    child.evaluate(ctx)
+                } else {
+                    None
+                }
+            }
+            Behavior::Do(task) => Some(*task),
+        }
+    }
+}
+
+/// The tree every NPC runs. Survival reflexes outrank the schedule:
+/// nobody wanders through rockfall or sits out a storm in the open.
+pub fn npc_tree() -> Behavior {
+    let gate = |predicate: fn(&AiContext) -> bool, task: Task| {
+        Behavior::When(predicate, Box::new(Behavior::Do(task)))
+    };
+    Behavior::Select(vec![
+        gate(|ctx| ctx.hazard.is_some(), Task::FleeHazard),
+        gate(
+            |ctx| ctx.storming && ctx.scheduled != NpcBehaviorType::Follow,
+            Task::SeekShelter,
+        ),
+        gate(
+            |ctx| ctx.scheduled == NpcBehaviorType::Follow,
+            Task::FollowLeader,
+        ),
+        gate(
+            |ctx| ctx.scheduled == NpcBehaviorType::ReturnHome,
+            Task::ReturnHome,
+        ),
+        gate(|ctx| ctx.scheduled == NpcBehaviorType::Ascend, Task::Ascend),
+        gate(
+            |ctx| {
+                matches!(
+                    ctx.scheduled,
+                    NpcBehaviorType::Wander | NpcBehaviorType::Patrol
+                )
+            },
+            Task::Wander,
+        ),
+        Behavior::Do(Task::Idle),
+    ])
+}
+
+/// Walking paces, world units per second.
+const FLEE_SPEED: f32 = 110.0;
+const SHELTER_SPEED: f32 = 80.0;
+const FOLLOW_SPEED: f32 = 90.0;
+const HOME_SPEED: f32 = 70.0;
+const WANDER_SPEED: f32 = 40.0;
+const ASCEND_SPEED: f32 = 12.0;
+
+/// How far from the player a follower keeps.
+const FOLLOW_DISTANCE: f32 = TILE_SIZE * 1.5;
+
+/// Turn the chosen task into this frame's velocity, or `None` to stand
+/// still. Wander destinations are handed back so the caller can store
+/// them on the NPC.
+pub fn task_velocity(task: Task, ctx: &mut AiContext, rng: &mut impl rand::Rng) -> Option<Vec2> {
+    let toward = |target: Vec2, speed: f32| {
+        let offset = target - ctx.position;
+        if offset.length() < 2.0 {
+            None
+        } else {
+            Some(offset.normalize() * speed)
+        }
+    };
+    match task {
+        Task::Idle => None,
+        Task::FleeHazard => {
+            let hazard = ctx.hazard?;
+            let away = ctx.position - hazard;
+            if away.length() < 0.1 {
+                return Some(Vec2::new(FLEE_SPEED, 0.0));
+            }
+            Some(away.normalize() * FLEE_SPEED)
+        }
+        Task::SeekShelter => toward(ctx.home, SHELTER_SPEED),
+        Task::FollowLeader => {
+            let player = ctx.player?;
+            if ctx.position.distance(player) <= FOLLOW_DISTANCE {
+                return None;
+            }
+            toward(player, FOLLOW_SPEED)
+        }
+        Task::ReturnHome => toward(ctx.home, HOME_SPEED),
+        Task::Ascend => Some(Vec2::new(rng.gen_range(-3.0..3.0), ASCEND_SPEED)),
+        Task::Wander => {
+            let reached = ctx
+                .wander_target
+                .is_none_or(|target| ctx.position.distance(target) < 4.0);
+            if reached {
+                ctx.wander_target = Some(
+                    ctx.home
+                        + Vec2::new(rng.gen_range(-60.0..60.0), rng.gen_range(-60.0..60.0)),
+                );
+            }
+            toward(ctx.wander_target?, WANDER_SPEED)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet_day(scheduled: NpcBehaviorType) -> AiContext {
+        AiContext {
+            position: Vec2::ZERO,
+            home: Vec2::ZERO,
+            player: None,
+            hazard: None,
+            storming: false,
+            scheduled,
+            wander_target: None,
+        }
+    }
+
+    #[test]
+    fn hazards_outrank_everything() {
+        let mut ctx = quiet_day(NpcBehaviorType::Follow);
+        ctx.hazard = Some(Vec2::new(10.0, 0.0));
+        assert_eq!(npc_tree().evaluate(&ctx), Some(Task::FleeHazard));
+    }
+
+    #[test]
+    fn storms_send_locals_home_but_not_followers() {
+        let mut ctx = quiet_day(NpcBehaviorType::Wander);
+        ctx.storming = true;
+        assert_eq!(npc_tree().evaluate(&ctx), Some(Task::SeekShelter));
+        ctx.scheduled = NpcBehaviorType::Follow;
+        assert_eq!(npc_tree().evaluate(&ctx), Some(Task::FollowLeader));
+    }
+
+    #[test]
+    fn stationary_schedule_means_idle() {
+        let ctx = quiet_day(NpcBehaviorType::Stationary);
+        assert_eq!(npc_tree().evaluate(&ctx), Some(Task::Idle));
+    }
+}
@@ -0,0 +1,239 @@
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::levels::{self, CurrentLevel};
+use crate::settings::{Action, Settings};
+use crate::systems::TerrainBrokenEvent;
+use crate::terrain::TerrainIndex;
+
+/// Handles for every sound the game plays, loaded once at startup.
+/// Missing files under `assets/audio/` just play nothing; the paths
+/// are the contract for whoever drops the recordings in.
+#[derive(Resource)]
+pub struct AudioAssets {
+    pub footstep_snow: Handle<AudioSource>,
+    pub footstep_rock: Handle<AudioSource>,
+    pub footstep_soil: Handle<AudioSource>,
+    pub wind_loop: Handle<AudioSource>,
+    pub ice_crack: Handle<AudioSource>,
+    pub rock_crack: Handle<AudioSource>,
+    pub ui_click: Handle<AudioSource>,
+    pub ambient_meadow: Handle<AudioSource>,
+    pub ambient_alpine: Handle<AudioSource>,
+    pub ambient_volcanic: Handle<AudioSource>,
+}
+
+pub fn load_audio_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AudioAssets {
+        footstep_snow: asset_server.load("audio/footstep_snow.ogg"),
+        footstep_rock: asset_server.load("audio/footstep_rock.ogg"),
+        footstep_soil: asset_server.load("audio/footstep_soil.ogg"),
+        wind_loop: asset_server.load("audio/wind_loop.ogg"),
+        ice_crack: asset_server.load("audio/ice_crack.ogg"),
+        rock_crack: asset_server.load("audio/rock_crack.ogg"),
+        ui_click: asset_server.load("audio/ui_click.ogg"),
+        ambient_meadow: asset_server.load("audio/ambient_meadow.ogg"),
+        ambient_alpine: asset_server.load("audio/ambient_alpine.ogg"),
+        ambient_volcanic: asset_server.load("audio/ambient_volcanic.ogg"),
+    });
+}
+
+/// Fire a one-shot at the effects volume; the entity despawns itself
+/// when the clip ends.
+fn play_effect(commands: &mut Commands, source: Handle<AudioSource>, volume: f32) {
+    commands.spawn(AudioBundle {
+        source,
+        settings: PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::new(volume),
+            ..default()
+        },
+    });
+}
+
+/// Seconds between footsteps at full walking pace.
+const FOOTSTEP_INTERVAL: f32 = 0.38;
+
+/// Play a step whenever the player is walking, picking the sound from
+/// the terrain underfoot.
+#[allow(clippy::too_many_arguments)]
+pub fn footstep_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    assets: Res<AudioAssets>,
+    current_level: Res<CurrentLevel>,
+    index: Res<TerrainIndex>,
+    tile_query: Query<&TerrainTile>,
+    player_query: Query<&Transform, With<Player>>,
+    mut until_next: Local<f32>,
+) {
+    let bindings = &settings.bindings;
+    let moving = bindings.pressed(&keyboard, Action::MoveUp)
+        || bindings.pressed(&keyboard, Action::MoveDown)
+        || bindings.pressed(&keyboard, Action::MoveLeft)
+        || bindings.pressed(&keyboard, Action::MoveRight);
+    if !moving {
+        *until_next = 0.0;
+        return;
+    }
+    *until_next -= time.delta_seconds();
+    if *until_next > 0.0 {
+        return;
+    }
+    *until_next = FOOTSTEP_INTERVAL;
+    let (Ok(transform), Some(level)) =
+        (player_query.get_single(), &current_level.definition)
+    else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(
+        transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let terrain = index
+        .get(grid_x, grid_y)
+        .and_then(|entity| tile_query.get(entity).ok())
+        .map(|tile| tile.terrain_type)
+        .unwrap_or(TerrainType::Rock);
+    let source = match terrain {
+        TerrainType::Snow | TerrainType::Ice | TerrainType::Glacier => {
+            assets.footstep_snow.clone()
+        }
+        TerrainType::Rock | TerrainType::Cliff => assets.footstep_rock.clone(),
+        _ => assets.footstep_soil.clone(),
+    };
+    play_effect(&mut commands, source, settings.effects_volume * 0.6);
+}
+
+/// The looping wind bed, volume tracking the simulated wind speed.
+#[derive(Component)]
+pub struct WindLoop;
+
+/// Calm air is near silence; a gale fills the mix.
+pub fn wind_audio_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    weather: Res<WeatherSystem>,
+    assets: Res<AudioAssets>,
+    sink_query: Query<&AudioSink, With<WindLoop>>,
+) {
+    let level = (weather.wind_speed / 25.0).clamp(0.0, 1.0) * settings.effects_volume;
+    match sink_query.get_single() {
+        Ok(sink) => sink.set_volume(level),
+        Err(_) => {
+            commands.spawn((
+                AudioBundle {
+                    source: assets.wind_loop.clone(),
+                    settings: PlaybackSettings {
+                        mode: PlaybackMode::Loop,
+                        volume: Volume::new(level),
+                        ..default()
+                    },
+                },
+                WindLoop,
+            ));
+        }
+    }
+}
+
+/// The slow background bed for the biome around the player.
+#[derive(Component)]
+pub struct AmbientBed {
+    biome: Biome,
+}
+
+/// Keep one ambient loop running, swapped out when the player crosses
+/// into ground with a different character.
+#[allow(clippy::too_many_arguments)]
+pub fn ambient_bed_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    assets: Res<AudioAssets>,
+    current_level: Res<CurrentLevel>,
+    index: Res<TerrainIndex>,
+    tile_query: Query<&TerrainTile>,
+    player_query: Query<&Transform, With<Player>>,
+    bed_query: Query<(Entity, &AudioSink, &AmbientBed)>,
+) {
+    let (Ok(transform), Some(level)) =
+        (player_query.get_single(), &current_level.definition)
+    else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(
+        transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let biome = index
+        .get(grid_x, grid_y)
+        .and_then(|entity| tile_query.get(entity).ok())
+        .map(|tile| tile.biome)
+        .unwrap_or_default();
+    if let Ok((entity, sink, bed)) = bed_query.get_single() {
+        if bed.biome == biome {
+            sink.set_volume(settings.music_volume * 0.5);
+            return;
+        }
+        commands.entity(entity).despawn();
+    }
+    let source = match biome {
+        Biome::Alpine | Biome::Glacier => assets.ambient_alpine.clone(),
+        Biome::Volcanic => assets.ambient_volcanic.clone(),
+        _ => assets.ambient_meadow.clone(),
+    };
+    commands.spawn((
+        AudioBundle {
+            source,
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Loop,
+                volume: Volume::new(settings.music_volume * 0.5),
+                ..default()
+            },
+        },
+        AmbientBed { biome },
+    ));
+}
+
+/// Crack sounds when terrain gives way, keyed off the same event the
+/// break handler consumes.
+pub fn terrain_break_audio_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    assets: Res<AudioAssets>,
+    mut events: EventReader<TerrainBrokenEvent>,
+) {
+    for event in events.read() {
+        let source = match event.terrain_type {
+            TerrainType::Ice | TerrainType::Glacier => assets.ice_crack.clone(),
+            _ => assets.rock_crack.clone(),
+        };
+        play_effect(&mut commands, source, settings.effects_volume);
+    }
+}
+
+/// A click for every UI row the player presses, on any screen.
+pub fn ui_click_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    assets: Option<Res<AudioAssets>>,
+    interaction_query: Query<&Interaction, Changed<Interaction>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        play_effect(
+            &mut commands,
+            assets.ui_click.clone(),
+            settings.effects_volume * 0.8,
+        );
+    }
+}
@@ -0,0 +1,1710 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// ============ Game states ============
+
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Menu,
+    CharacterSelection,
+    Loading,
+    LevelSelect,
+    Climbing,
+    Inventory,
+    Shop,
+    Barter,
+    Building,
+    Sleeping,
+    Magic,
+    Skills,
+    Map,
+    Settings,
+    GameOver,
+}
+
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum TimeOfDay {
+    Dawn,
+    #[default]
+    Day,
+    Dusk,
+    Night,
+}
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Cloudy,
+    Rain,
+    Snow,
+    Storm,
+    Fog,
+}
+
+// ============ Player ============
+
+#[derive(Component)]
+pub struct Player;
+
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(Component)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(Component)]
+pub struct Hunger {
+    pub current: f32,
+    pub max: f32,
+}
+
+#[derive(Component)]
+pub struct Thirst {
+    pub current: f32,
+    pub max: f32,
+}
+
+/// Spirit for the climb. Darkness, storms and injuries wear it down;
+/// summits, fires and hot food restore it. A worn-down climber
+/// recovers stamina slowly and has little to say.
+#[derive(Component)]
+pub struct Morale {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Default for Morale {
+    fn default() -> Self {
+        Self {
+            current: 80.0,
+            max: 100.0,
+        }
+    }
+}
+
+impl Morale {
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            self.current / self.max
+        } else {
+            0.0
+        }
+    }
+
+    /// Too worn down to muster much — conversation and recovery both
+    /// suffer below this line.
+    pub fn downhearted(&self) -> bool {
+        self.fraction() < 0.3
+    }
+
+    pub fn adjust(&mut self, delta: f32) {
+        self.current = (self.current + delta).clamp(0.0, self.max);
+    }
+}
+
+#[derive(Component)]
+pub struct MovementStats {
+    pub speed: f32,
+    pub climbing_skill: f32,
+}
+
+#[derive(Component, Default)]
+pub struct Velocity {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// The direction the player last moved in, for sprite flipping and
+/// anything that cares which way they're pointed.
+#[derive(Component)]
+pub struct Facing {
+    pub direction: Vec2,
+}
+
+impl Default for Facing {
+    fn default() -> Self {
+        Self { direction: Vec2::X }
+    }
+}
+
+/// Marker for a player standing on supporting terrain.
+/// Core body temperature in °C; 37 is healthy, hypothermia sets in
+/// below 35.
+#[derive(Component)]
+pub struct BodyTemperature {
+    pub current: f32,
+}
+
+impl Default for BodyTemperature {
+    fn default() -> Self {
+        Self { current: 37.0 }
+    }
+}
+
+/// How soaked the player is, 0 dry to 1 drenched. Wet clothes chill
+/// faster and weigh the climber down until they dry out.
+#[derive(Component, Default)]
+pub struct Wetness {
+    pub level: f32,
+}
+
+/// Cold injury accumulated in the extremities. Severity runs 0..1 and
+/// slows movement and numbs tool work; it heals slowly in the warm.
+#[derive(Component, Default)]
+pub struct Frostbite {
+    pub severity: f32,
+}
+
+/// A few seconds of forced slow-walking after stamina bottoms out;
+/// climbing is off the table until it passes.
+#[derive(Component)]
+pub struct Exhausted {
+    pub remaining: f32,
+}
+
+#[derive(Component)]
+pub struct Grounded;
+
+/// Attached while the player is falling; remembers where the fall began
+/// so landing damage can scale with distance.
+#[derive(Component)]
+pub struct Falling {
+    pub start_y: f32,
+}
+
+/// Attached while the player is roped to an anchor.
+#[derive(Component)]
+pub struct Climbing {
+    pub anchor_point: Vec2,
+    pub rope_length: f32,
+}
+
+/// Marker for the sprite showing a placed rope anchor.
+#[derive(Component)]
+pub struct RopeAnchor;
+
+/// Marker for the dots a guide lays out toward the goal.
+#[derive(Component)]
+pub struct RouteMarker;
+
+/// Marker over ground a bought hint has flagged as dangerous.
+#[derive(Component)]
+pub struct SecretMarker;
+
+#[derive(Component)]
+pub struct Sleeping {
+    pub wake_hour: f32,
+}
+
+#[derive(Component)]
+pub struct Money(pub f32);
+
+// ============ Items & inventory ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToolType {
+    IceAxe,
+    Pickaxe,
+    Hammer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipmentSlot {
+    Head,
+    Body,
+    Legs,
+    Feet,
+    Hands,
+    Backpack,
+    Tool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ItemType {
+    Tool(ToolType),
+    Clothing(EquipmentSlot),
+    Food,
+    Drink,
+    Gear,
+    Material,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ItemProperties {
+    pub weight: f32,
+    pub durability: f32,
+    pub max_durability: f32,
+    pub warmth: f32,
+    pub strength: f32,
+    pub protection: f32,
+    pub nutrition: f32,
+    pub water: f32,
+    /// Extra item slots granted while worn (backpacks).
+    #[serde(default)]
+    pub capacity_bonus: usize,
+    /// Extra carry weight granted while worn (backpacks).
+    #[serde(default)]
+    pub weight_limit_bonus: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub item_type: ItemType,
+    pub properties: ItemProperties,
+}
+
+impl Item {
+    /// The slot this item occupies when equipped, if it is equippable.
+    pub fn equipment_slot(&self) -> Option<EquipmentSlot> {
+        match self.item_type {
+            ItemType::Tool(_) => Some(EquipmentSlot::Tool),
+            ItemType::Clothing(slot) => Some(slot),
+            _ => None,
+        }
+    }
+}
+
+/// Every item id that spawns or shops may reference. Kept in one place
+/// so level validation can catch typos in authored files.
+pub const KNOWN_ITEM_IDS: &[&str] = &[
+    "ice_axe",
+    "pickaxe",
+    "hammer",
+    "crampons",
+    "rope",
+    "harness",
+    "heat_protection",
+    "rune_stone",
+    "ice_chunk",
+    "mineral",
+    "headlamp",
+    "torch",
+    "dried_fish",
+    "waterskin",
+    "driftwood",
+    "berries",
+    "water_flask",
+    "wood",
+    "carabiner",
+    "obsidian",
+    "tent",
+    "repair_kit",
+    "pelt",
+    "anchor_kit",
+    "day_pack",
+    "expedition_pack",
+    "energy_bar",
+    "warming_potion",
+    "first_aid_kit",
+    "raw_meat",
+    "raw_fish",
+];
+
+pub fn create_ice_axe() -> Item {
+    Item {
+        name: "Ice Axe".to_string(),
+        item_type: ItemType::Tool(ToolType::IceAxe),
+        properties: ItemProperties {
+            weight: 0.7,
+            durability: 100.0,
+            max_durability: 100.0,
+            strength: 8.0,
+            ..Default::default()
+        },
+    }
+}
+
+/// Head-mounted lamp; durability doubles as battery charge.
+pub fn create_headlamp() -> Item {
+    Item {
+        name: "Headlamp".to_string(),
+        item_type: ItemType::Clothing(EquipmentSlot::Head),
+        properties: ItemProperties {
+            weight: 0.2,
+            durability: 100.0,
+            max_durability: 100.0,
+            ..Default::default()
+        },
+    }
+}
+
+/// Travel rations; filling for their weight.
+pub fn create_dried_fish() -> Item {
+    Item {
+        name: "Dried Fish".to_string(),
+        item_type: ItemType::Food,
+        properties: ItemProperties {
+            weight: 0.3,
+            nutrition: 35.0,
+            ..Default::default()
+        },
+    }
+}
+
+/// A skin of water.
+pub fn create_waterskin() -> Item {
+    Item {
+        name: "Waterskin".to_string(),
+        item_type: ItemType::Drink,
+        properties: ItemProperties {
+            weight: 1.0,
+            water: 50.0,
+            ..Default::default()
+        },
+    }
+}
+
+/// A packable tent; pitch it to sleep sheltered.
+pub fn create_tent() -> Item {
+    Item {
+        name: "Tent".to_string(),
+        item_type: ItemType::Gear,
+        properties: ItemProperties {
+            weight: 3.5,
+            ..Default::default()
+        },
+    }
+}
+
+/// Build the item a spawn table or shop references by id. Ids must be
+/// listed in [`KNOWN_ITEM_IDS`] so level validation can vouch for them.
+pub fn item_from_id(id: &str) -> Option<Item> {
+    let simple = |name: &str, item_type: ItemType, weight: f32| Item {
+        name: name.to_string(),
+        item_type,
+        properties: ItemProperties {
+            weight,
+            ..Default::default()
+        },
+    };
+    let tool = |name: &str, tool_type: ToolType, weight: f32, strength: f32| Item {
+        name: name.to_string(),
+        item_type: ItemType::Tool(tool_type),
+        properties: ItemProperties {
+            weight,
+            durability: 100.0,
+            max_durability: 100.0,
+            strength,
+            ..Default::default()
+        },
+    };
+    Some(match id {
+        "ice_axe" => create_ice_axe(),
+        "headlamp" => create_headlamp(),
+        "torch" => create_torch(),
+        "dried_fish" => create_dried_fish(),
+        "waterskin" => create_waterskin(),
+        "tent" => create_tent(),
+        "wood" => create_wood(),
+        "repair_kit" => Item {
+            name: "Repair Kit".to_string(),
+            item_type: ItemType::Gear,
+            properties: ItemProperties {
+                weight: 0.8,
+                // Durability doubles as the number of repairs left
+                durability: 3.0,
+                max_durability: 3.0,
+                ..Default::default()
+            },
+        },
+        "pickaxe" => tool("Pickaxe", ToolType::Pickaxe, 1.5, 10.0),
+        "hammer" => tool("Hammer", ToolType::Hammer, 1.2, 6.0),
+        "crampons" => Item {
+            name: "Crampons".to_string(),
+            item_type: ItemType::Clothing(EquipmentSlot::Feet),
+            properties: ItemProperties {
+                weight: 0.9,
+                durability: 100.0,
+                max_durability: 100.0,
+                ..Default::default()
+            },
+        },
+        "harness" => Item {
+            name: "Harness".to_string(),
+            item_type: ItemType::Clothing(EquipmentSlot::Body),
+            properties: ItemProperties {
+                weight: 0.5,
+                durability: 100.0,
+                max_durability: 100.0,
+                protection: 2.0,
+                ..Default::default()
+            },
+        },
+        "heat_protection" => Item {
+            name: "Heat Suit".to_string(),
+            item_type: ItemType::Clothing(EquipmentSlot::Body),
+            properties: ItemProperties {
+                weight: 2.0,
+                durability: 100.0,
+                max_durability: 100.0,
+                protection: 6.0,
+                ..Default::default()
+            },
+        },
+        "rope" => simple("Rope", ItemType::Gear, 1.4),
+        "carabiner" => simple("Carabiner", ItemType::Gear, 0.1),
+        "rune_stone" => simple("Rune Stone", ItemType::Material, 0.4),
+        "ice_chunk" => simple("Ice Chunk", ItemType::Material, 0.8),
+        "mineral" => simple("Mineral", ItemType::Material, 0.6),
+        "driftwood" => simple("Driftwood", ItemType::Material, 0.9),
+        "obsidian" => simple("Obsidian", ItemType::Material, 0.7),
+        "pelt" => simple("Pelt", ItemType::Material, 0.5),
+        "anchor_kit" => simple("Anchor Kit", ItemType::Gear, 0.8),
+        "first_aid_kit" => simple("First Aid Kit", ItemType::Gear, 0.5),
+        "energy_bar" => Item {
+            name: "Energy Bar".to_string(),
+            item_type: ItemType::Food,
+            properties: ItemProperties {
+                weight: 0.1,
+                nutrition: 25.0,
+                ..Default::default()
+            },
+        },
+        "warming_potion" => Item {
+            name: "Warming Potion".to_string(),
+            item_type: ItemType::Drink,
+            properties: ItemProperties {
+                weight: 0.3,
+                water: 5.0,
+                warmth: 15.0,
+                ..Default::default()
+            },
+        },
+        "day_pack" => Item {
+            name: "Day Pack".to_string(),
+            item_type: ItemType::Clothing(EquipmentSlot::Backpack),
+            properties: ItemProperties {
+                weight: 0.8,
+                capacity_bonus: 5,
+                weight_limit_bonus: 8.0,
+                ..Default::default()
+            },
+        },
+        "expedition_pack" => Item {
+            name: "Expedition Pack".to_string(),
+            item_type: ItemType::Clothing(EquipmentSlot::Backpack),
+            properties: ItemProperties {
+                weight: 2.2,
+                capacity_bonus: 12,
+                weight_limit_bonus: 20.0,
+                ..Default::default()
+            },
+        },
+        "berries" => Item {
+            name: "Berries".to_string(),
+            item_type: ItemType::Food,
+            properties: ItemProperties {
+                weight: 0.2,
+                nutrition: 12.0,
+                ..Default::default()
+            },
+        },
+        "raw_meat" => Item {
+            name: "Raw Meat".to_string(),
+            item_type: ItemType::Food,
+            properties: ItemProperties {
+                weight: 1.0,
+                nutrition: 18.0,
+                ..Default::default()
+            },
+        },
+        "raw_fish" => Item {
+            name: "Raw Fish".to_string(),
+            item_type: ItemType::Food,
+            properties: ItemProperties {
+                weight: 0.5,
+                nutrition: 14.0,
+                ..Default::default()
+            },
+        },
+        "water_flask" => Item {
+            name: "Water Flask".to_string(),
+            item_type: ItemType::Drink,
+            properties: ItemProperties {
+                weight: 0.6,
+                water: 30.0,
+                ..Default::default()
+            },
+        },
+        _ => return None,
+    })
+}
+
+/// Firewood cut from shrubs and trees; fuel for a fire pit.
+pub fn create_wood() -> Item {
+    Item {
+        name: "Wood".to_string(),
+        item_type: ItemType::Material,
+        properties: ItemProperties {
+            weight: 1.0,
+            ..Default::default()
+        },
+    }
+}
+
+/// A hot meal cooked over a fire; worth more than what went into it.
+pub fn create_cooked_meal(nutrition: f32) -> Item {
+    Item {
+        name: "Cooked Meal".to_string(),
+        item_type: ItemType::Food,
+        properties: ItemProperties {
+            weight: 0.4,
+            nutrition,
+            ..Default::default()
+        },
+    }
+}
+
+/// Hand torch; burns fuel (durability) faster than a headlamp but
+/// throws a wider light.
+pub fn create_torch() -> Item {
+    Item {
+        name: "Torch".to_string(),
+        item_type: ItemType::Gear,
+        properties: ItemProperties {
+            weight: 0.5,
+            durability: 60.0,
+            max_durability: 60.0,
+            ..Default::default()
+        },
+    }
+}
+
+#[derive(Component)]
+pub struct Inventory {
+    pub items: Vec<Item>,
+    pub capacity: usize,
+    pub weight_limit: f32,
+}
+
+impl Inventory {
+    pub fn current_weight(&self) -> f32 {
+        self.items.iter().map(|i| i.properties.weight).sum()
+    }
+}
+
+/// Carrying limits before any backpack bonus.
+pub const BASE_CAPACITY: usize = 20;
+pub const BASE_WEIGHT_LIMIT: f32 = 25.0;
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            capacity: BASE_CAPACITY,
+            weight_limit: BASE_WEIGHT_LIMIT,
+        }
+    }
+}
+
+#[derive(Component, Default, Clone, Serialize, Deserialize)]
+pub struct EquippedItems {
+    pub head: Option<Item>,
+    pub body: Option<Item>,
+    pub legs: Option<Item>,
+    pub feet: Option<Item>,
+    pub hands: Option<Item>,
+    pub backpack: Option<Item>,
+    pub tool: Option<Item>,
+}
+
+impl EquippedItems {
+    pub fn get_total_warmth(&self) -> f32 {
+        [
+            &self.head,
+            &self.body,
+            &self.legs,
+            &self.feet,
+            &self.hands,
+        ]
+        .iter()
+        .filter_map(|slot| slot.as_ref())
+        .map(|item| item.properties.warmth)
+        .sum()
+    }
+
+    /// What currently sits in the given slot.
+    pub fn in_slot(&self, slot: EquipmentSlot) -> Option<&Item> {
+        match slot {
+            EquipmentSlot::Head => self.head.as_ref(),
+            EquipmentSlot::Body => self.body.as_ref(),
+            EquipmentSlot::Legs => self.legs.as_ref(),
+            EquipmentSlot::Feet => self.feet.as_ref(),
+            EquipmentSlot::Hands => self.hands.as_ref(),
+            EquipmentSlot::Backpack => self.backpack.as_ref(),
+            EquipmentSlot::Tool => self.tool.as_ref(),
+        }
+    }
+
+    /// The slot itself, for equipping and swapping.
+    pub fn slot_mut(&mut self, slot: EquipmentSlot) -> &mut Option<Item> {
+        match slot {
+            EquipmentSlot::Head => &mut self.head,
+            EquipmentSlot::Body => &mut self.body,
+            EquipmentSlot::Legs => &mut self.legs,
+            EquipmentSlot::Feet => &mut self.feet,
+            EquipmentSlot::Hands => &mut self.hands,
+            EquipmentSlot::Backpack => &mut self.backpack,
+            EquipmentSlot::Tool => &mut self.tool,
+        }
+    }
+
+    /// Every equipped item, mutably, for maintenance passes.
+    pub fn slots_mut(&mut self) -> Vec<&mut Item> {
+        [
+            &mut self.head,
+            &mut self.body,
+            &mut self.legs,
+            &mut self.feet,
+            &mut self.hands,
+            &mut self.backpack,
+            &mut self.tool,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Every equipped item, for display.
+    pub fn slots(&self) -> Vec<&Item> {
+        [
+            &self.head,
+            &self.body,
+            &self.legs,
+            &self.feet,
+            &self.hands,
+            &self.backpack,
+            &self.tool,
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Summed protection rating of everything worn, used to blunt
+    /// hazard damage.
+    pub fn total_protection(&self) -> f32 {
+        [
+            &self.head,
+            &self.body,
+            &self.legs,
+            &self.feet,
+            &self.hands,
+        ]
+        .iter()
+        .filter_map(|slot| slot.as_ref())
+        .map(|item| item.properties.protection)
+        .sum()
+    }
+
+    /// Climbing help from equipped gear: crampons, good boots, etc. all
+    /// contribute their strength rating.
+    pub fn climbing_bonus(&self) -> f32 {
+        [
+            &self.feet,
+            &self.hands,
+            &self.tool,
+        ]
+        .iter()
+        .filter_map(|slot| slot.as_ref())
+        .map(|item| item.properties.strength * 0.25)
+        .sum()
+    }
+
+    /// Whether any equipped item satisfies a `required_gear` id like
+    /// "crampons" or "heat_protection".
+    pub fn has_gear(&self, gear_id: &str) -> bool {
+        [
+            &self.head,
+            &self.body,
+            &self.legs,
+            &self.feet,
+            &self.hands,
+            &self.backpack,
+            &self.tool,
+        ]
+        .iter()
+        .filter_map(|slot| slot.as_ref())
+        .any(|item| item.name.to_lowercase().replace(' ', "_") == gear_id)
+    }
+
+    pub fn slot(&self, slot: EquipmentSlot) -> &Option<Item> {
+        match slot {
+            EquipmentSlot::Head => &self.head,
+            EquipmentSlot::Body => &self.body,
+            EquipmentSlot::Legs => &self.legs,
+            EquipmentSlot::Feet => &self.feet,
+            EquipmentSlot::Hands => &self.hands,
+            EquipmentSlot::Backpack => &self.backpack,
+            EquipmentSlot::Tool => &self.tool,
+        }
+    }
+}
+
+// ============ Terrain ============
+
+/// The single authoritative terrain enum, shared by level files,
+/// spawned tiles, and gameplay systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TerrainType {
+    Rock,
+    Ice,
+    Snow,
+    Grass,
+    Soil,
+    Glacier,
+    Lava,
+    Coast,
+    Cliff,
+    Water,
+    Shrub,
+    Tree,
+}
+
+impl TerrainType {
+    pub fn color(self) -> Color {
+        match self {
+            TerrainType::Rock => Color::srgb(0.45, 0.42, 0.40),
+            TerrainType::Ice => Color::srgb(0.68, 0.85, 0.95),
+            TerrainType::Snow => Color::srgb(0.92, 0.94, 0.97),
+            TerrainType::Grass => Color::srgb(0.35, 0.60, 0.30),
+            TerrainType::Soil => Color::srgb(0.45, 0.35, 0.25),
+            TerrainType::Glacier => Color::srgb(0.55, 0.78, 0.92),
+            TerrainType::Lava => Color::srgb(0.85, 0.25, 0.08),
+            TerrainType::Coast => Color::srgb(0.75, 0.70, 0.50),
+            TerrainType::Cliff => Color::srgb(0.30, 0.28, 0.27),
+            TerrainType::Water => Color::srgb(0.20, 0.40, 0.75),
+            TerrainType::Shrub => Color::srgb(0.28, 0.48, 0.24),
+            TerrainType::Tree => Color::srgb(0.16, 0.38, 0.20),
+        }
+    }
+
+    /// Multiplier on walking speed.
+    pub fn movement_modifier(self) -> f32 {
+        match self {
+            TerrainType::Grass | TerrainType::Soil => 1.0,
+            TerrainType::Coast => 0.9,
+            TerrainType::Shrub => 0.8,
+            TerrainType::Tree => 0.55,
+            TerrainType::Rock => 0.8,
+            TerrainType::Snow => 0.6,
+            TerrainType::Ice | TerrainType::Glacier => 0.7,
+            TerrainType::Lava | TerrainType::Cliff => 0.5,
+            TerrainType::Water => 0.35,
+        }
+    }
+
+    /// Damage per second from standing here, if the terrain is hazardous.
+    pub fn hazard_damage(self) -> Option<f32> {
+        match self {
+            TerrainType::Lava => Some(20.0),
+            _ => None,
+        }
+    }
+
+    /// Which tool breaks this terrain and how many hits it takes.
+    pub fn breakability(self) -> Option<(ToolType, u32)> {
+        match self {
+            TerrainType::Ice => Some((ToolType::IceAxe, 3)),
+            TerrainType::Glacier => Some((ToolType::IceAxe, 5)),
+            TerrainType::Rock => Some((ToolType::Pickaxe, 6)),
+            _ => None,
+        }
+    }
+
+    pub fn climbable(self) -> bool {
+        !matches!(self, TerrainType::Lava | TerrainType::Cliff)
+    }
+
+    pub fn solid(self) -> bool {
+        matches!(self, TerrainType::Cliff | TerrainType::Lava)
+    }
+}
+
+/// Broad region classification layered on top of terrain types, used by
+/// weather, wildlife, and spawning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Biome {
+    Coastal,
+    #[default]
+    Meadow,
+    Forest,
+    Alpine,
+    Glacier,
+    Volcanic,
+}
+
+impl Biome {
+    /// Species that roam this biome.
+    pub fn wildlife_table(self) -> &'static [WildlifeSpecies] {
+        match self {
+            Biome::Coastal => &[WildlifeSpecies::Sheep, WildlifeSpecies::Eagle],
+            Biome::Meadow => &[WildlifeSpecies::Sheep, WildlifeSpecies::Horse],
+            Biome::Forest => &[WildlifeSpecies::ArcticFox, WildlifeSpecies::Wolf],
+            Biome::Alpine => &[WildlifeSpecies::Eagle, WildlifeSpecies::ArcticFox],
+            Biome::Glacier => &[WildlifeSpecies::ArcticFox, WildlifeSpecies::Bear],
+            Biome::Volcanic => &[],
+        }
+    }
+
+    /// Item ids that can turn up lying around this biome.
+    pub fn item_table(self) -> &'static [&'static str] {
+        match self {
+            Biome::Coastal => &["driftwood", "rope"],
+            Biome::Meadow => &["berries", "water_flask"],
+            Biome::Forest => &["wood", "berries"],
+            Biome::Alpine => &["rope", "carabiner"],
+            Biome::Glacier => &["ice_chunk", "rune_stone"],
+            Biome::Volcanic => &["obsidian"],
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct TerrainTile {
+    pub terrain_type: TerrainType,
+    pub biome: Biome,
+    pub climbable: bool,
+    pub solid: bool,
+    pub stability: f32,
+    pub grid_x: i32,
+    pub grid_y: i32,
+}
+
+#[derive(Component)]
+pub struct Climbable {
+    pub difficulty: f32,
+    pub required_gear: Vec<String>,
+}
+
+#[derive(Component)]
+pub struct Breakable {
+    pub tool_required: ToolType,
+    pub hits_required: u32,
+    pub current_hits: u32,
+}
+
+#[derive(Component)]
+pub struct Hazardous {
+    pub damage_per_second: f32,
+}
+
+#[derive(Component)]
+pub struct Interactable {
+    pub prompt: String,
+}
+
+/// An item lying in the world, waiting to be picked up.
+#[derive(Component)]
+pub struct ItemPickup {
+    pub item: Item,
+}
+
+/// What kind of passage an [`Entrance`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntranceKind {
+    Cave,
+    Crevasse,
+    /// Leads back out of an interior sub-area.
+    Exit,
+}
+
+/// A passage between the overworld and an interior sub-area.
+#[derive(Component)]
+pub struct Entrance {
+    pub kind: EntranceKind,
+}
+
+// ============ Structures ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StructureType {
+    Tent,
+    FirePit,
+    Shelter,
+}
+
+impl StructureType {
+    /// Wood needed to build this in the field; `None` means it can't
+    /// be built by hand (a tent is carried and pitched, not built).
+    pub fn wood_cost(self) -> Option<usize> {
+        match self {
+            StructureType::Tent => None,
+            StructureType::FirePit => Some(3),
+            StructureType::Shelter => Some(6),
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            StructureType::Tent => Color::srgb(0.75, 0.45, 0.2),
+            StructureType::FirePit => Color::srgb(0.9, 0.5, 0.15),
+            StructureType::Shelter => Color::srgb(0.55, 0.45, 0.3),
+        }
+    }
+
+    pub fn sprite_size(self) -> Vec2 {
+        match self {
+            StructureType::Tent => Vec2::new(26.0, 20.0),
+            StructureType::FirePit => Vec2::new(16.0, 12.0),
+            StructureType::Shelter => Vec2::new(28.0, 24.0),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Structure {
+    pub structure_type: StructureType,
+}
+
+// ============ NPCs ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NPCType {
+    Guide,
+    Viking,
+    Mage,
+    Climber,
+    Trader,
+    Hermit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NpcBehaviorType {
+    Stationary,
+    Wander,
+    Patrol,
+    /// Trailing the player as a party member.
+    Follow,
+    /// Walking back to `home_position` after being dismissed.
+    ReturnHome,
+    /// Working slowly up the mountain, as climbers do by day.
+    Ascend,
+}
+
+#[derive(Component)]
+pub struct NPC {
+    pub npc_type: NPCType,
+    pub name: String,
+    pub behavior: NpcBehaviorType,
+    pub home_position: Vec2,
+    /// File in `assets/dialogue` holding this NPC's conversation.
+    pub dialogue_file: String,
+    /// Where a wandering NPC is currently headed.
+    pub wander_target: Option<Vec2>,
+}
+
+/// The face-to-face trade currently open: who it's with and which
+/// items each side has put forward.
+#[derive(Resource, Default)]
+pub struct ActiveBarter {
+    pub npc: Option<Entity>,
+    pub npc_pick: Option<usize>,
+    pub player_pick: Option<usize>,
+}
+
+/// A stranded climber waiting on help: first aid, then an escort back
+/// to the level start.
+#[derive(Component)]
+pub struct Injured {
+    pub treated: bool,
+}
+
+/// What an NPC of this type carries for barter.
+pub fn npc_pack(npc_type: NPCType) -> Vec<Item> {
+    let ids: &[&str] = match npc_type {
+        NPCType::Trader => &["rope", "dried_fish", "carabiner", "repair_kit"],
+        NPCType::Viking => &["pelt", "wood", "dried_fish"],
+        NPCType::Hermit | NPCType::Mage => &["berries", "torch"],
+        NPCType::Guide | NPCType::Climber => &["carabiner", "waterskin"],
+    };
+    ids.iter().filter_map(|id| item_from_id(id)).collect()
+}
+
+#[derive(Resource)]
+pub struct Party {
+    pub members: Vec<Entity>,
+    pub max_size: usize,
+}
+
+impl Default for Party {
+    fn default() -> Self {
+        Self {
+            members: Vec::new(),
+            max_size: 3,
+        }
+    }
+}
+
+// ============ Wildlife ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WildlifeSpecies {
+    Sheep,
+    Horse,
+    ArcticFox,
+    Eagle,
+    Wolf,
+    Bear,
+}
+
+impl WildlifeSpecies {
+    /// Sprite tint for this species.
+    pub fn color(&self) -> Color {
+        match self {
+            WildlifeSpecies::Sheep => Color::srgb(0.92, 0.9, 0.85),
+            WildlifeSpecies::Horse => Color::srgb(0.45, 0.3, 0.18),
+            WildlifeSpecies::ArcticFox => Color::srgb(0.8, 0.82, 0.85),
+            WildlifeSpecies::Eagle => Color::srgb(0.3, 0.25, 0.2),
+            WildlifeSpecies::Wolf => Color::srgb(0.5, 0.5, 0.52),
+            WildlifeSpecies::Bear => Color::srgb(0.3, 0.2, 0.12),
+        }
+    }
+
+    /// How much of a fight this species has in it.
+    pub fn max_health(&self) -> f32 {
+        match self {
+            WildlifeSpecies::Bear => 80.0,
+            WildlifeSpecies::Wolf => 40.0,
+            WildlifeSpecies::Horse => 50.0,
+            _ => 20.0,
+        }
+    }
+
+    /// Lowercase name for messages ("a wolf attack").
+    pub fn name(&self) -> &'static str {
+        match self {
+            WildlifeSpecies::Sheep => "sheep",
+            WildlifeSpecies::Horse => "horse",
+            WildlifeSpecies::ArcticFox => "arctic fox",
+            WildlifeSpecies::Eagle => "eagle",
+            WildlifeSpecies::Wolf => "wolf",
+            WildlifeSpecies::Bear => "bear",
+        }
+    }
+
+    /// Sprite footprint for this species.
+    pub fn sprite_size(&self) -> Vec2 {
+        match self {
+            WildlifeSpecies::Sheep => Vec2::new(18.0, 14.0),
+            WildlifeSpecies::Horse => Vec2::new(26.0, 20.0),
+            WildlifeSpecies::ArcticFox => Vec2::new(16.0, 10.0),
+            WildlifeSpecies::Eagle => Vec2::new(14.0, 10.0),
+            WildlifeSpecies::Wolf => Vec2::new(22.0, 14.0),
+            WildlifeSpecies::Bear => Vec2::new(30.0, 22.0),
+        }
+    }
+}
+
+/// How a species carries itself, one row per species: how shy it is,
+/// how it moves about, whether it sticks with its own kind, and any
+/// special habits.
+pub struct SpeciesProfile {
+    pub flee_distance: f32,
+    pub roam_speed: f32,
+    pub roam_radius: f32,
+    /// Pull toward nearby animals of the same species, 0 for loners.
+    pub cohesion: f32,
+    /// Circles its spawn point aloft instead of walking.
+    pub circles: bool,
+    /// Hunts only from dusk to dawn.
+    pub nocturnal: bool,
+}
+
+/// The config table the wildlife systems read.
+pub fn species_profile(species: WildlifeSpecies) -> SpeciesProfile {
+    match species {
+        WildlifeSpecies::Sheep => SpeciesProfile {
+            flee_distance: 120.0,
+            roam_speed: 20.0,
+            roam_radius: 40.0,
+            cohesion: 1.0,
+            circles: false,
+            nocturnal: false,
+        },
+        WildlifeSpecies::Horse => SpeciesProfile {
+            // Horses let you walk right up
+            flee_distance: 30.0,
+            roam_speed: 25.0,
+            roam_radius: 60.0,
+            cohesion: 0.6,
+            circles: false,
+            nocturnal: false,
+        },
+        WildlifeSpecies::ArcticFox => SpeciesProfile {
+            flee_distance: 140.0,
+            roam_speed: 35.0,
+            roam_radius: 70.0,
+            cohesion: 0.0,
+            circles: false,
+            nocturnal: false,
+        },
+        WildlifeSpecies::Eagle => SpeciesProfile {
+            flee_distance: 0.0,
+            roam_speed: 60.0,
+            roam_radius: 60.0,
+            cohesion: 0.0,
+            circles: true,
+            nocturnal: false,
+        },
+        WildlifeSpecies::Wolf => SpeciesProfile {
+            flee_distance: 0.0,
+            roam_speed: 30.0,
+            roam_radius: 80.0,
+            cohesion: 0.8,
+            circles: false,
+            nocturnal: true,
+        },
+        WildlifeSpecies::Bear => SpeciesProfile {
+            flee_distance: 0.0,
+            roam_speed: 20.0,
+            roam_radius: 50.0,
+            cohesion: 0.0,
+            circles: false,
+            nocturnal: false,
+        },
+    }
+}
+
+#[derive(Component)]
+pub struct Wildlife {
+    pub species: WildlifeSpecies,
+    pub attack_damage: f32,
+    pub aggression: f32,
+    pub flee_distance: f32,
+    pub spawn_point: Vec2,
+    /// Where this animal is currently drifting, while undisturbed.
+    pub roam_target: Option<Vec2>,
+    pub health: f32,
+    /// Seconds left of keeping well away after being scared off.
+    pub scared: f32,
+    /// Seconds until a predator can bite again.
+    pub attack_cooldown: f32,
+}
+
+// ============ Characters ============
+
+/// The climbers on offer at the start of an expedition. Each carries
+/// different stats and gear, and one passive knack the others lack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CharacterArchetype {
+    Mountaineer,
+    Viking,
+    Mage,
+    Scout,
+}
+
+impl CharacterArchetype {
+    pub const ALL: [CharacterArchetype; 4] = [
+        CharacterArchetype::Mountaineer,
+        CharacterArchetype::Viking,
+        CharacterArchetype::Mage,
+        CharacterArchetype::Scout,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CharacterArchetype::Mountaineer => "Mountaineer",
+            CharacterArchetype::Viking => "Viking",
+            CharacterArchetype::Mage => "Mage",
+            CharacterArchetype::Scout => "Scout",
+        }
+    }
+
+    pub fn blurb(self) -> &'static str {
+        match self {
+            CharacterArchetype::Mountaineer => {
+                "A steady hand on any face. Climbs above their grade."
+            }
+            CharacterArchetype::Viking => {
+                "Broad of back and hard to kill. Carries a heavier pack."
+            }
+            CharacterArchetype::Mage => {
+                "Thin blood, deep well. Mana returns twice as fast."
+            }
+            CharacterArchetype::Scout => {
+                "Light and quick. Climbing tires them noticeably less."
+            }
+        }
+    }
+
+    pub fn max_health(self) -> f32 {
+        match self {
+            CharacterArchetype::Viking => 120.0,
+            CharacterArchetype::Mage => 85.0,
+            _ => 100.0,
+        }
+    }
+
+    pub fn speed(self) -> f32 {
+        match self {
+            CharacterArchetype::Scout => 180.0,
+            CharacterArchetype::Viking => 135.0,
+            _ => 150.0,
+        }
+    }
+
+    pub fn climbing_skill(self) -> f32 {
+        match self {
+            CharacterArchetype::Mountaineer => 4.5,
+            CharacterArchetype::Mage => 2.5,
+            _ => 3.0,
+        }
+    }
+
+    pub fn starting_items(self) -> &'static [&'static str] {
+        match self {
+            CharacterArchetype::Mountaineer => &["tent", "dried_fish", "waterskin", "rope"],
+            CharacterArchetype::Viking => &["tent", "dried_fish", "dried_fish", "waterskin"],
+            CharacterArchetype::Mage => &["tent", "berries", "waterskin", "warming_potion"],
+            CharacterArchetype::Scout => &["tent", "energy_bar", "waterskin", "torch"],
+        }
+    }
+
+    pub fn max_mana(self) -> f32 {
+        match self {
+            CharacterArchetype::Mage => 70.0,
+            _ => 40.0,
+        }
+    }
+
+    pub fn known_spells(self) -> Vec<Spell> {
+        let mut spells = starter_spells();
+        if self == CharacterArchetype::Mage {
+            spells.push(Spell {
+                name: "Surefoot".to_string(),
+                mana_cost: 15.0,
+                effect: SpellEffect::BoostClimbing(2.0),
+                duration: 60.0,
+                cooldown: 45.0,
+            });
+        }
+        spells
+    }
+
+    /// Extra kilograms over the base weight limit.
+    pub fn carry_bonus(self) -> f32 {
+        match self {
+            CharacterArchetype::Viking => 10.0,
+            CharacterArchetype::Scout => -3.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Multiplier on mana regeneration.
+    pub fn mana_regen_multiplier(self) -> f32 {
+        match self {
+            CharacterArchetype::Mage => 2.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplier on the stamina cost of climbing.
+    pub fn climb_drain_multiplier(self) -> f32 {
+        match self {
+            CharacterArchetype::Scout => 0.7,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Knacks bought with training, consulted by whichever system each one
+/// bends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Perk {
+    /// The axe opens ice and glacier in a single swing.
+    IceSpecialist,
+    /// Another 10 kg on the back without complaint.
+    Sherpa,
+    /// Body heat drains at half the rate in the cold.
+    ColdBlood,
+}
+
+impl Perk {
+    pub const ALL: [Perk; 3] = [Perk::IceSpecialist, Perk::Sherpa, Perk::ColdBlood];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Perk::IceSpecialist => "Ice Specialist",
+            Perk::Sherpa => "Sherpa",
+            Perk::ColdBlood => "Cold Blood",
+        }
+    }
+
+    pub fn blurb(self) -> &'static str {
+        match self {
+            Perk::IceSpecialist => "the axe breaks ice in one hit",
+            Perk::Sherpa => "+10 kg carrying capacity",
+            Perk::ColdBlood => "lose body heat half as fast",
+        }
+    }
+}
+
+/// The perks this climber has taken.
+#[derive(Component, Default)]
+pub struct Perks {
+    pub owned: Vec<Perk>,
+}
+
+impl Perks {
+    pub fn has(&self, perk: Perk) -> bool {
+        self.owned.contains(&perk)
+    }
+}
+
+/// Experience gained on the mountain, and what's been made of it.
+/// Training in skill and stamina lands directly on those components;
+/// carry and warmth training is recorded here for the systems that
+/// recompute their values every frame.
+#[derive(Component, Default)]
+pub struct Experience {
+    pub xp: f32,
+    pub level: u32,
+    pub skill_points: u32,
+    /// Extra kilograms of trained carrying capacity.
+    pub trained_carry: f32,
+    /// Levels of cold tolerance bought with skill points.
+    pub trained_warmth: u32,
+}
+
+impl Experience {
+    /// XP needed to reach the next level from the current one.
+    pub fn xp_to_next(&self) -> f32 {
+        100.0 * (self.level + 1) as f32
+    }
+
+    /// Bank XP; returns true when it tips the climber over a level.
+    pub fn award(&mut self, amount: f32) -> bool {
+        self.xp += amount;
+        let mut leveled = false;
+        while self.xp >= self.xp_to_next() {
+            self.xp -= self.xp_to_next();
+            self.level += 1;
+            self.skill_points += 1;
+            leveled = true;
+        }
+        leveled
+    }
+}
+
+/// Who the player chose to be this expedition.
+#[derive(Resource)]
+pub struct SelectedCharacter {
+    pub archetype: CharacterArchetype,
+}
+
+impl Default for SelectedCharacter {
+    fn default() -> Self {
+        Self {
+            archetype: CharacterArchetype::Mountaineer,
+        }
+    }
+}
+
+// ============ Magic ============
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpellEffect {
+    Heal(f32),
+    BoostClimbing(f32),
+    Warmth(f32),
+    RockStability(f32),
+    WeatherControl,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spell {
+    pub name: String,
+    pub mana_cost: f32,
+    pub effect: SpellEffect,
+    /// How long the effect lingers; zero means it lands all at once.
+    #[serde(default)]
+    pub duration: f32,
+    /// Seconds before this spell can be cast again.
+    #[serde(default)]
+    pub cooldown: f32,
+}
+
+#[derive(Component)]
+pub struct MagicUser {
+    pub mana: f32,
+    pub max_mana: f32,
+    pub known_spells: Vec<Spell>,
+}
+
+/// The spells a fresh climber starts out knowing; the rest are out on
+/// the mountains waiting to be found.
+pub fn starter_spells() -> Vec<Spell> {
+    vec![
+        Spell {
+            name: "Mend".to_string(),
+            mana_cost: 20.0,
+            effect: SpellEffect::Heal(30.0),
+            duration: 0.0,
+            cooldown: 30.0,
+        },
+        Spell {
+            name: "Emberskin".to_string(),
+            mana_cost: 12.0,
+            effect: SpellEffect::Warmth(1.5),
+            duration: 45.0,
+            cooldown: 20.0,
+        },
+    ]
+}
+
+/// One spell effect still running, counting down.
+pub struct ActiveSpell {
+    pub effect: SpellEffect,
+    pub remaining: f32,
+}
+
+/// Every lingering spell effect on the player, plus per-spell
+/// cooldowns, ticked down by the spell systems.
+#[derive(Resource, Default)]
+pub struct ActiveSpells {
+    pub effects: Vec<ActiveSpell>,
+    pub cooldowns: std::collections::HashMap<String, f32>,
+}
+
+impl ActiveSpells {
+    /// Extra climbing skill granted by running spells.
+    pub fn climbing_bonus(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|active| match active.effect {
+                SpellEffect::BoostClimbing(bonus) => bonus,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Degrees per second of magical warmth.
+    pub fn warmth(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|active| match active.effect {
+                SpellEffect::Warmth(rate) => rate,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Whether the rock is being magically held in place.
+    pub fn rock_held(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|active| matches!(active.effect, SpellEffect::RockStability(_)))
+    }
+}
+
+/// What reading a rune grants.
+#[derive(Debug, Clone)]
+pub enum RuneReward {
+    Spell(Spell),
+    MaxMana(f32),
+}
+
+/// One rune as it exists in the world: where it waits and what it
+/// teaches.
+pub struct RuneDefinition {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub level: &'static str,
+    pub reward: RuneReward,
+}
+
+/// Every rune hidden across the mountains. Stones found on a level
+/// grant that level's runes in order.
+pub fn rune_catalog() -> Vec<RuneDefinition> {
+    vec![
+        RuneDefinition {
+            id: "isa",
+            name: "Ísa",
+            level: "Jökulheimar",
+            reward: RuneReward::MaxMana(10.0),
+        },
+        RuneDefinition {
+            id: "hagall",
+            name: "Hagall",
+            level: "Jökulheimar",
+            reward: RuneReward::Spell(Spell {
+                name: "Stormstill".to_string(),
+                mana_cost: 35.0,
+                effect: SpellEffect::WeatherControl,
+                duration: 0.0,
+                cooldown: 120.0,
+            }),
+        },
+        RuneDefinition {
+            id: "kenaz",
+            name: "Kenaz",
+            level: "Eldfjöll",
+            reward: RuneReward::Spell(Spell {
+                name: "Stonebind".to_string(),
+                mana_cost: 25.0,
+                effect: SpellEffect::RockStability(1.0),
+                duration: 30.0,
+                cooldown: 60.0,
+            }),
+        },
+        RuneDefinition {
+            id: "raidho",
+            name: "Raidho",
+            level: "Stóra Fjallið",
+            reward: RuneReward::Spell(Spell {
+                name: "Surefoot".to_string(),
+                mana_cost: 15.0,
+                effect: SpellEffect::BoostClimbing(2.0),
+                duration: 60.0,
+                cooldown: 45.0,
+            }),
+        },
+    ]
+}
+
+// ============ Time & weather resources ============
+
+#[derive(Resource)]
+pub struct GameTime {
+    pub hour: f32,
+    pub day: u32,
+    /// How many game hours pass per real second.
+    pub time_scale: f32,
+}
+
+impl Default for GameTime {
+    fn default() -> Self {
+        Self {
+            hour: 9.0,
+            day: 1,
+            time_scale: 0.1,
+        }
+    }
+}
+
+impl GameTime {
+    /// 0.0 at midnight, 1.0 at noon.
+    pub fn light_level(&self) -> f32 {
+        let angle = (self.hour - 6.0) / 12.0 * std::f32::consts::PI;
+        angle.sin().clamp(0.05, 1.0)
+    }
+}
+
+#[derive(Resource)]
+pub struct WeatherSystem {
+    pub current_weather: Weather,
+    pub temperature: f32,
+    pub wind_speed: f32,
+    /// Unit vector the wind blows toward.
+    pub wind_direction: Vec2,
+    pub visibility: f32,
+    pub change_timer: Timer,
+}
+
+impl Default for WeatherSystem {
+    fn default() -> Self {
+        Self {
+            current_weather: Weather::Clear,
+            temperature: 5.0,
+            wind_speed: 3.0,
+            wind_direction: Vec2::X,
+            visibility: 1.0,
+            change_timer: Timer::from_seconds(45.0, TimerMode::Repeating),
+        }
+    }
+}
+
+// ============ UI ============
+
+/// Quick-use slots for consumables, bound to 1-5 during climbing.
+/// Slots hold item names; the pack is searched on use, so the badge
+/// count and the slot stay honest as items come and go.
+pub const HOTBAR_SLOTS: usize = 5;
+
+#[derive(Resource, Default)]
+pub struct Hotbar {
+    pub slots: [Option<String>; HOTBAR_SLOTS],
+    pub cooldowns: [f32; HOTBAR_SLOTS],
+}
+
+/// A short-lived warning line shown in the HUD.
+#[derive(Resource, Default)]
+pub struct WarningMessage {
+    pub text: String,
+    pub remaining: f32,
+}
+
+impl WarningMessage {
+    pub fn show(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.remaining = 3.0;
+    }
+}
+
+/// The most recent acute hit the player took, so the death screen can
+/// name what finished them off rather than guessing.
+#[derive(Resource, Default)]
+pub struct LastDamage {
+    pub cause: Option<String>,
+    pub at: f64,
+}
+
+impl LastDamage {
+    pub fn note(&mut self, cause: impl Into<String>, at: f64) {
+        self.cause = Some(cause.into());
+        self.at = at;
+    }
+}
+
+/// Filled in at the moment of death and read by the game-over screen.
+#[derive(Resource)]
+pub struct DeathReport {
+    pub cause: String,
+    pub altitude: f32,
+    pub day: u32,
+    pub hour: f32,
+}
+
+/// A label the player has pinned to the map.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MapMarker {
+    pub label: String,
+    pub position: (f32, f32),
+}
+
+/// Player-placed map markers; carried through saves.
+#[derive(Resource, Default)]
+pub struct MapMarkers {
+    pub markers: Vec<MapMarker>,
+}
+
+/// Hazardous tiles the player has had on screen, so the map can pin
+/// dangers without revealing ground never visited.
+#[derive(Resource, Default)]
+pub struct SeenHazards {
+    pub positions: std::collections::HashSet<(i32, i32)>,
+}
+
+// ============ Shop ============
+
+#[derive(Clone)]
+pub struct ShopEntry {
+    pub item: Item,
+    pub price: f32,
+    pub stock: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct ShopInventory {
+    pub entries: Vec<ShopEntry>,
+}
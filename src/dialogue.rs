@@ -0,0 +1,656 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::components::{
+    ActiveBarter, GameState, Inventory, Money, Morale, Player, WarningMessage, NPC,
+};
+use crate::items::ItemDatabase;
+use crate::systems::PartyInvitationEvent;
+
+/// Something a dialogue choice does to the world when picked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DialogueEffect {
+    InviteToParty,
+    ChangeReputation(i32),
+    OpenShop,
+    OpenBarter,
+    GiveItem(String),
+    GiveQuest(String),
+    /// Sell knowledge about the current level for money: the topic
+    /// ("hazards" or "caches") and the asking price.
+    SellHint(String, f32),
+    EndConversation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueChoice {
+    pub text: String,
+    pub next_node: Option<String>,
+    pub effects: Vec<DialogueEffect>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueNode {
+    pub text: String,
+    pub choices: Vec<DialogueChoice>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DialogueTree {
+    pub nodes: HashMap<String, DialogueNode>,
+    pub entry: String,
+}
+
+const REPUTATION_PATH: &str = "saves/reputation.ron";
+
+/// How the locals feel about the player, nudged by dialogue choices
+/// and deeds. Warm scores mean friendlier greetings, better prices,
+/// and companions who actually say yes; cold ones the opposite. The
+/// score survives restarts in `saves/reputation.ron`.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct PlayerReputation {
+    pub score: i32,
+}
+
+impl PlayerReputation {
+    pub const MIN: i32 = -50;
+    pub const MAX: i32 = 50;
+
+    /// Read the saved score; a missing or unreadable file is a clean
+    /// slate.
+    pub fn load() -> Self {
+        match fs::read_to_string(REPUTATION_PATH) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(reputation) => reputation,
+                Err(e) => {
+                    error!("Failed to parse {REPUTATION_PATH}: {e}");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Shift the score and persist it.
+    pub fn adjust(&mut self, delta: i32) {
+        self.score = (self.score + delta).clamp(Self::MIN, Self::MAX);
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Some(parent) = Path::new(REPUTATION_PATH).parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(REPUTATION_PATH, contents) {
+                    error!("Failed to write {REPUTATION_PATH}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to serialize reputation: {e}"),
+        }
+    }
+
+    /// One word for how an NPC greets this score.
+    pub fn mood(&self) -> &'static str {
+        match self.score {
+            i32::MIN..=-20 => "hostile",
+            -19..=-5 => "wary",
+            -4..=4 => "neutral",
+            5..=19 => "friendly",
+            _ => "trusted",
+        }
+    }
+
+    /// What the trader multiplies asking prices by: up to 20% off for
+    /// a trusted regular, up to 20% over for a known troublemaker.
+    pub fn price_modifier(&self) -> f32 {
+        1.0 - self.score as f32 * 0.004
+    }
+
+    /// Probability an invited NPC actually joins the party.
+    pub fn join_chance(&self) -> f64 {
+        (0.5 + self.score as f64 * 0.01).clamp(0.05, 0.95)
+    }
+}
+
+const CONVERSATIONS_PATH: &str = "saves/conversations.ron";
+
+/// What one NPC remembers about talking to the player.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NpcMemory {
+    pub met: bool,
+    pub invitation_declined: bool,
+    /// Times the player picked a choice that cost reputation with them
+    /// listening.
+    pub rudeness: i32,
+    /// Nodes the conversation has already passed through.
+    pub exhausted_topics: Vec<String>,
+}
+
+/// Per-NPC conversation history, keyed by NPC name and persisted in
+/// `saves/conversations.ron` so nobody greets the player like a
+/// stranger twice.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct ConversationMemory {
+    pub by_npc: HashMap<String, NpcMemory>,
+}
+
+impl ConversationMemory {
+    pub fn load() -> Self {
+        match fs::read_to_string(CONVERSATIONS_PATH) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(memory) => memory,
+                Err(e) => {
+                    error!("Failed to parse {CONVERSATIONS_PATH}: {e}");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// The memory slot for this NPC, created blank on first contact.
+    pub fn remember(&mut self, npc_name: &str) -> &mut NpcMemory {
+        self.by_npc.entry(npc_name.to_string()).or_default()
+    }
+
+    pub fn save(&self) {
+        if let Some(parent) = Path::new(CONVERSATIONS_PATH).parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(CONVERSATIONS_PATH, contents) {
+                    error!("Failed to write {CONVERSATIONS_PATH}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to serialize conversation memory: {e}"),
+        }
+    }
+}
+
+/// Where a conversation should open given what this NPC remembers.
+/// Trees opt in by authoring the specially named nodes; ones that
+/// don't just start at their entry every time.
+pub fn entry_node_for(tree: &DialogueTree, memory: &NpcMemory) -> String {
+    if memory.invitation_declined && tree.nodes.contains_key("after_decline") {
+        return "after_decline".to_string();
+    }
+    if memory.met && tree.nodes.contains_key("met_before") {
+        return "met_before".to_string();
+    }
+    tree.entry.clone()
+}
+
+/// The conversation currently on screen, if any.
+#[derive(Resource, Default)]
+pub struct ActiveDialogue {
+    pub npc: Option<Entity>,
+    pub tree: Option<DialogueTree>,
+    pub current_node: String,
+}
+
+pub fn create_guide_dialogue() -> DialogueTree {
+    let mut nodes = HashMap::new();
+    nodes.insert(
+        "start".to_string(),
+        DialogueNode {
+            text: "Góðan daginn! The mountain is treacherous this time of year. You'll want crampons above the snow line.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "Would you guide me up?".to_string(),
+                    next_node: Some("guide_offer".to_string()),
+                    effects: vec![],
+                },
+                DialogueChoice {
+                    text: "Any work for a climber?".to_string(),
+                    next_node: Some("quest_offer".to_string()),
+                    effects: vec![],
+                },
+                DialogueChoice {
+                    text: "Thanks for the warning.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::ChangeReputation(1), DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    nodes.insert(
+        "guide_offer".to_string(),
+        DialogueNode {
+            text: "I could come along, if you seem capable.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "Join me.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::InviteToParty, DialogueEffect::EndConversation],
+                },
+                DialogueChoice {
+                    text: "Maybe later.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    nodes.insert(
+        "quest_offer".to_string(),
+        DialogueNode {
+            text: "There's always something. Old Völundur needs feeding, and the summit log is looking thin.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "I'll take him supplies.".to_string(),
+                    next_node: None,
+                    effects: vec![
+                        DialogueEffect::GiveQuest("supplies_for_volundur".to_string()),
+                        DialogueEffect::EndConversation,
+                    ],
+                },
+                DialogueChoice {
+                    text: "I'll sign the summit log.".to_string(),
+                    next_node: None,
+                    effects: vec![
+                        DialogueEffect::GiveQuest("summit_stora_fjallid".to_string()),
+                        DialogueEffect::EndConversation,
+                    ],
+                },
+                DialogueChoice {
+                    text: "Not today.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    nodes.insert(
+        "met_before".to_string(),
+        DialogueNode {
+            text: "Back again? The mountain hasn't moved, but the weather has.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "Would you guide me up?".to_string(),
+                    next_node: Some("guide_offer".to_string()),
+                    effects: vec![],
+                },
+                DialogueChoice {
+                    text: "Any work for a climber?".to_string(),
+                    next_node: Some("quest_offer".to_string()),
+                    effects: vec![],
+                },
+                DialogueChoice {
+                    text: "Just saying hello.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    DialogueTree {
+        nodes,
+        entry: "start".to_string(),
+    }
+}
+
+pub fn create_trader_dialogue() -> DialogueTree {
+    let mut nodes = HashMap::new();
+    nodes.insert(
+        "start".to_string(),
+        DialogueNode {
+            text: "Welcome, climber! Gear, food, rope — I have it all.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "Show me your wares.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::OpenShop, DialogueEffect::EndConversation],
+                },
+                DialogueChoice {
+                    text: "Got anything to swap?".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::OpenBarter, DialogueEffect::EndConversation],
+                },
+                DialogueChoice {
+                    text: "Just passing through.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    nodes.insert(
+        "met_before".to_string(),
+        DialogueNode {
+            text: "Good to see you in one piece. Need anything?".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "Show me your wares.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::OpenShop, DialogueEffect::EndConversation],
+                },
+                DialogueChoice {
+                    text: "Got anything to swap?".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::OpenBarter, DialogueEffect::EndConversation],
+                },
+                DialogueChoice {
+                    text: "Just passing through.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    DialogueTree {
+        nodes,
+        entry: "start".to_string(),
+    }
+}
+
+pub fn create_hermit_dialogue() -> DialogueTree {
+    let mut nodes = HashMap::new();
+    nodes.insert(
+        "start".to_string(),
+        DialogueNode {
+            text: "Few come this high. The mountain speaks to those who listen.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "What does it say?".to_string(),
+                    next_node: Some("hints".to_string()),
+                    effects: vec![],
+                },
+                DialogueChoice {
+                    text: "Farewell.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    nodes.insert(
+        "hints".to_string(),
+        DialogueNode {
+            text: "It speaks to me. To you it will speak for coin.".to_string(),
+            choices: vec![
+                DialogueChoice {
+                    text: "Where are the dangers here? (20 kr)".to_string(),
+                    next_node: None,
+                    effects: vec![
+                        DialogueEffect::SellHint("hazards".to_string(), 20.0),
+                        DialogueEffect::EndConversation,
+                    ],
+                },
+                DialogueChoice {
+                    text: "Did anyone leave supplies behind? (30 kr)".to_string(),
+                    next_node: None,
+                    effects: vec![
+                        DialogueEffect::SellHint("caches".to_string(), 30.0),
+                        DialogueEffect::EndConversation,
+                    ],
+                },
+                DialogueChoice {
+                    text: "Never mind.".to_string(),
+                    next_node: None,
+                    effects: vec![DialogueEffect::EndConversation],
+                },
+            ],
+        },
+    );
+    DialogueTree {
+        nodes,
+        entry: "start".to_string(),
+    }
+}
+
+/// Resolve a dialogue file name from a level definition to the tree
+/// built for it; `None` means the level references a conversation the
+/// game doesn't know.
+pub fn dialogue_for_file(dialogue_file: &str) -> Option<DialogueTree> {
+    match dialogue_file {
+        "erik_guide.ron" => Some(create_guide_dialogue()),
+        "sigrun_trader.ron" => Some(create_trader_dialogue()),
+        "volundur_hermit.ron" | "freyja_mage.ron" => Some(create_hermit_dialogue()),
+        _ => None,
+    }
+}
+
+const DIALOGUE_DIR: &str = "assets/dialogue";
+
+/// Every conversation the game knows, keyed by the file name the level
+/// definitions reference. Trees come from `assets/dialogue/*.ron`; the
+/// built-in trees are written out on first run so writers have
+/// something to start from.
+#[derive(Resource, Default)]
+pub struct DialogueLibrary {
+    pub trees: HashMap<String, DialogueTree>,
+}
+
+impl DialogueLibrary {
+    pub fn get(&self, dialogue_file: &str) -> Option<&DialogueTree> {
+        self.trees.get(dialogue_file)
+    }
+}
+
+/// Build the library from `assets/dialogue`, seeding the directory with
+/// the built-in trees when their files are missing.
+pub fn load_dialogue_library() -> DialogueLibrary {
+    let dir = Path::new(DIALOGUE_DIR);
+    let _ = fs::create_dir_all(dir);
+    let mut library = DialogueLibrary::default();
+    for file in [
+        "erik_guide.ron",
+        "sigrun_trader.ron",
+        "volundur_hermit.ron",
+        "freyja_mage.ron",
+    ] {
+        let Some(tree) = dialogue_for_file(file) else {
+            continue;
+        };
+        let path = dir.join(file);
+        if !path.exists() {
+            if let Ok(contents) =
+                ron::ser::to_string_pretty(&tree, ron::ser::PrettyConfig::default())
+            {
+                let _ = fs::write(&path, contents);
+            }
+        }
+        library.trees.insert(file.to_string(), tree);
+    }
+    // Anything else in the directory is a writer-added conversation
+    let Ok(entries) = fs::read_dir(dir) else {
+        return library;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        if path.extension().and_then(|e| e.to_str()) != Some("ron") {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match ron::from_str::<DialogueTree>(&contents) {
+            Ok(tree) => {
+                library.trees.insert(name, tree);
+            }
+            Err(e) => error!("Failed to parse {}: {e}", path.display()),
+        }
+    }
+    library
+}
+
+/// Advance the active dialogue according to a picked choice index,
+/// handing the choice's effects back for the caller to apply.
+pub fn process_dialogue_choice(
+    dialogue: &mut ActiveDialogue,
+    choice_index: usize,
+) -> Vec<DialogueEffect> {
+    let Some(tree) = dialogue.tree.as_ref() else {
+        return Vec::new();
+    };
+    let Some(node) = tree.nodes.get(&dialogue.current_node) else {
+        return Vec::new();
+    };
+    let Some(choice) = node.choices.get(choice_index) else {
+        return Vec::new();
+    };
+    let effects = choice.effects.clone();
+    match &choice.next_node {
+        Some(next) => dialogue.current_node = next.clone(),
+        None => {
+            dialogue.npc = None;
+            dialogue.tree = None;
+            dialogue.current_node.clear();
+        }
+    }
+    effects
+}
+
+/// Number-key input while a conversation is active. Picked choices are
+/// advanced through the tree and their effects applied to the world.
+#[allow(clippy::too_many_arguments)]
+pub fn dialogue_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    database: Res<ItemDatabase>,
+    npc_query: Query<&NPC>,
+    mut dialogue: ResMut<ActiveDialogue>,
+    mut memory: ResMut<ConversationMemory>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut barter: ResMut<ActiveBarter>,
+    catalog: Res<crate::quests::QuestCatalog>,
+    current_level: Res<crate::levels::CurrentLevel>,
+    mut knowledge: ResMut<crate::levels::LevelKnowledge>,
+    mut quest_log: ResMut<crate::quests::QuestLog>,
+    mut reputation: ResMut<PlayerReputation>,
+    mut invitations: EventWriter<PartyInvitationEvent>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&mut Inventory, &mut Money), With<Player>>,
+    morale_query: Query<&Morale, With<Player>>,
+) {
+    if dialogue.tree.is_none() {
+        return;
+    }
+    // A downhearted climber can only manage the first couple of replies
+    let spoken_choices = morale_query
+        .get_single()
+        .map(|morale| if morale.downhearted() { 2 } else { usize::MAX })
+        .unwrap_or(usize::MAX);
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+    ];
+    for (index, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        if index >= spoken_choices {
+            continue;
+        }
+        // Remember who we were talking to; ending the conversation
+        // clears the handle before the effects run.
+        let npc = dialogue.npc;
+        let npc_name = npc
+            .and_then(|npc| npc_query.get(npc).ok())
+            .map(|n| n.name.clone());
+        // The node we leave behind counts as a topic covered
+        if let Some(name) = &npc_name {
+            let topic = dialogue.current_node.clone();
+            let remembered = memory.remember(name);
+            if !remembered.exhausted_topics.contains(&topic) {
+                remembered.exhausted_topics.push(topic);
+                memory.save();
+            }
+        }
+        for effect in process_dialogue_choice(&mut dialogue, index) {
+            match effect {
+                DialogueEffect::OpenShop => next_state.set(GameState::Shop),
+                DialogueEffect::OpenBarter => {
+                    barter.npc = npc;
+                    barter.npc_pick = None;
+                    barter.player_pick = None;
+                    next_state.set(GameState::Barter);
+                }
+                DialogueEffect::ChangeReputation(delta) => {
+                    reputation.adjust(delta);
+                    // Insulting someone to their face sticks with them
+                    if delta < 0 {
+                        if let Some(name) = &npc_name {
+                            memory.remember(name).rudeness += 1;
+                            memory.save();
+                        }
+                    }
+                }
+                DialogueEffect::InviteToParty => {
+                    if let Some(npc) = npc {
+                        invitations.send(PartyInvitationEvent {
+                            npc,
+                            npc_name: npc_name.clone().unwrap_or_default(),
+                        });
+                    }
+                }
+                DialogueEffect::GiveItem(id) => {
+                    let Some(item) = database.get(&id) else {
+                        warn!("Dialogue gives unknown item id {id:?}");
+                        continue;
+                    };
+                    let Ok((mut inventory, _)) = player_query.get_single_mut() else {
+                        continue;
+                    };
+                    if inventory.items.len() >= inventory.capacity
+                        || inventory.current_weight() + item.properties.weight
+                            > inventory.weight_limit
+                    {
+                        warning.show(format!("You can't carry the {}", item.name));
+                        continue;
+                    }
+                    warning.show(format!("You receive the {}", item.name));
+                    inventory.items.push(item);
+                }
+                DialogueEffect::SellHint(topic, price) => {
+                    let Ok((_, mut money)) = player_query.get_single_mut() else {
+                        continue;
+                    };
+                    if money.0 < price {
+                        warning.show("You can't afford that knowledge");
+                        continue;
+                    }
+                    let secrets = knowledge.for_level(&current_level.name);
+                    let already = match topic.as_str() {
+                        "hazards" => std::mem::replace(&mut secrets.hazards, true),
+                        "caches" => std::mem::replace(&mut secrets.caches, true),
+                        _ => {
+                            warn!("Dialogue sells unknown hint topic {topic:?}");
+                            continue;
+                        }
+                    };
+                    if already {
+                        warning.show("You already know that");
+                        continue;
+                    }
+                    money.0 -= price;
+                    warning.show(match topic.as_str() {
+                        "hazards" => "The dangerous ground is marked in your mind",
+                        _ => "You learn where old supplies were left",
+                    });
+                }
+                DialogueEffect::GiveQuest(id) => {
+                    let Some(quest) = catalog.get(&id) else {
+                        warn!("Dialogue grants unknown quest id {id:?}");
+                        continue;
+                    };
+                    if quest_log.accept(&id) {
+                        warning.show(format!("Quest accepted: {}", quest.title));
+                    }
+                }
+                DialogueEffect::EndConversation => {}
+            }
+        }
+        break;
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::components::{item_from_id, Item, ItemType, ShopEntry, ShopInventory, KNOWN_ITEM_IDS};
+
+const ITEMS_PATH: &str = "assets/items.ron";
+
+/// Every item the game can hand out, keyed by id. Defaults come from
+/// the built-in factories; `assets/items.ron` overlays them so item
+/// stats can be tuned without a rebuild.
+#[derive(Resource)]
+pub struct ItemDatabase {
+    pub items: HashMap<String, Item>,
+}
+
+impl Default for ItemDatabase {
+    fn default() -> Self {
+        let items = KNOWN_ITEM_IDS
+            .iter()
+            .filter_map(|id| item_from_id(id).map(|item| (id.to_string(), item)))
+            .collect();
+        Self { items }
+    }
+}
+
+impl ItemDatabase {
+    /// A fresh copy of the item with this id, if the id is known.
+    pub fn get(&self, id: &str) -> Option<Item> {
+        self.items.get(id).cloned()
+    }
+}
+
+/// Build the database from `assets/items.ron`, overlaying the built-in
+/// defaults; a missing file just means defaults (and the file is
+/// written out so there's something to edit).
+pub fn load_item_database() -> ItemDatabase {
+    let mut database = ItemDatabase::default();
+    let path = Path::new(ITEMS_PATH);
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<HashMap<String, Item>>(&contents) {
+            Ok(items) => database.items.extend(items),
+            Err(e) => error!("Failed to parse {ITEMS_PATH}: {e}"),
+        },
+        Err(_) => {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(contents) =
+                ron::ser::to_string_pretty(&database.items, ron::ser::PrettyConfig::default())
+            {
+                let _ = fs::write(path, contents);
+            }
+        }
+    }
+    database
+}
+
+/// What the trading post carries and for how much.
+const SHOP_STOCK: &[(&str, f32, u32)] = &[
+    ("crampons", 35.0, 2),
+    ("rope", 20.0, 3),
+    ("pickaxe", 40.0, 1),
+    ("dried_fish", 8.0, 5),
+    ("heat_protection", 60.0, 1),
+    ("repair_kit", 25.0, 2),
+    ("expedition_pack", 55.0, 1),
+    ("first_aid_kit", 18.0, 3),
+];
+
+/// Stock the trading post from the database.
+pub fn stock_shop(database: &ItemDatabase) -> ShopInventory {
+    let entries = SHOP_STOCK
+        .iter()
+        .filter_map(|&(id, price, stock)| {
+            database.get(id).map(|item| ShopEntry { item, price, stock })
+        })
+        .collect();
+    ShopInventory { entries }
+}
+
+/// What the trader pays for an item: half the asking price for things
+/// it also sells, otherwise a flat rate by kind.
+pub fn sell_value(shop: &ShopInventory, item: &Item) -> f32 {
+    if let Some(entry) = shop.entries.iter().find(|entry| entry.item.name == item.name) {
+        return entry.price * 0.5;
+    }
+    match item.item_type {
+        ItemType::Tool(_) => 15.0,
+        ItemType::Clothing(_) => 12.0,
+        ItemType::Material => 10.0,
+        ItemType::Gear => 8.0,
+        ItemType::Food | ItemType::Drink => 4.0,
+    }
+}
+
+/// What a thing is worth in a face-to-face swap, judged on its
+/// properties alone: sturdier, warmer, better-fed wins. Worn gear
+/// trades at a discount proportional to its condition.
+pub fn barter_value(item: &Item) -> f32 {
+    let p = &item.properties;
+    let base = match item.item_type {
+        ItemType::Tool(_) => 12.0,
+        ItemType::Clothing(_) => 10.0,
+        ItemType::Gear => 8.0,
+        ItemType::Material => 6.0,
+        ItemType::Food | ItemType::Drink => 3.0,
+    };
+    let condition = if p.max_durability > 0.0 {
+        p.durability / p.max_durability
+    } else {
+        1.0
+    };
+    (base
+        + p.strength * 1.5
+        + p.warmth * 1.2
+        + p.protection * 1.5
+        + p.nutrition * 0.1
+        + p.water * 0.05
+        + p.weight_limit_bonus * 0.5
+        + p.capacity_bonus as f32 * 0.8)
+        * condition
+}
+
+const RECIPES_PATH: &str = "assets/recipes.ron";
+
+/// One crafting recipe: consume the inputs, gain the output. All item
+/// references are database ids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub output: String,
+    pub inputs: Vec<(String, u32)>,
+}
+
+/// The recipes the player knows, in the order the crafting list shows
+/// them.
+#[derive(Resource)]
+pub struct RecipeBook {
+    pub recipes: Vec<Recipe>,
+}
+
+impl Default for RecipeBook {
+    fn default() -> Self {
+        let recipe = |output: &str, inputs: &[(&str, u32)]| Recipe {
+            output: output.to_string(),
+            inputs: inputs
+                .iter()
+                .map(|&(id, count)| (id.to_string(), count))
+                .collect(),
+        };
+        Self {
+            recipes: vec![
+                recipe("torch", &[("wood", 2)]),
+                recipe("anchor_kit", &[("rope", 1), ("carabiner", 1)]),
+                recipe("repair_kit", &[("mineral", 2), ("wood", 1)]),
+            ],
+        }
+    }
+}
+
+/// Build the recipe book from `assets/recipes.ron`; a missing file just
+/// means the defaults (and the file is written out so there's something
+/// to edit).
+pub fn load_recipe_book() -> RecipeBook {
+    let mut book = RecipeBook::default();
+    let path = Path::new(RECIPES_PATH);
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<Vec<Recipe>>(&contents) {
+            Ok(recipes) => book.recipes = recipes,
+            Err(e) => error!("Failed to parse {RECIPES_PATH}: {e}"),
+        },
+        Err(_) => {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(contents) =
+                ron::ser::to_string_pretty(&book.recipes, ron::ser::PrettyConfig::default())
+            {
+                let _ = fs::write(path, contents);
+            }
+        }
+    }
+    book
+}
@@ -0,0 +1,1283 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use crate::components;
+use crate::components::{
+    Biome, Breakable, Climbable, EntranceKind, Hazardous, TerrainTile, TerrainType, Wildlife,
+    WildlifeSpecies, NPC,
+};
+use crate::pathfinding;
+use crate::terrain::TerrainRegistry;
+
+pub const TILE_SIZE: f32 = 32.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainData {
+    pub x: i32,
+    pub y: i32,
+    pub terrain_type: TerrainType,
+    #[serde(default)]
+    pub biome: Biome,
+    pub difficulty: f32,
+    pub required_gear: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemSpawn {
+    pub item_id: String,
+    pub position: (f32, f32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NPCSpawn {
+    pub npc_type: components::NPCType,
+    pub name: String,
+    pub position: (f32, f32),
+    pub dialogue_file: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntranceSpawn {
+    pub kind: EntranceKind,
+    /// Grid coordinates of the passage tile.
+    pub position: (i32, i32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WildlifeSpawn {
+    pub species: WildlifeSpecies,
+    pub position: (f32, f32),
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelDefinition {
+    pub name: String,
+    pub description: String,
+    pub difficulty: u32,
+    /// Seed used to generate this level; the same seed always produces
+    /// the same mountain.
+    pub seed: u64,
+    pub width: i32,
+    pub height: i32,
+    /// Grid coordinates; convert with [`calculate_tile_position`].
+    pub start_position: (i32, i32),
+    pub goal_position: (i32, i32),
+    pub terrain: Vec<TerrainData>,
+    pub items: Vec<ItemSpawn>,
+    pub npcs: Vec<NPCSpawn>,
+    pub wildlife: Vec<WildlifeSpawn>,
+    #[serde(default)]
+    pub entrances: Vec<EntranceSpawn>,
+    /// Money paid out for reaching the goal.
+    #[serde(default)]
+    pub reward: f32,
+}
+
+/// One elevation band of a heightmap import: pixels up to
+/// `max_elevation` (0..1) become `terrain_type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeightmapBand {
+    pub max_elevation: f32,
+    pub terrain_type: TerrainType,
+}
+
+/// Everything `from_heightmap` needs besides the image itself. Lives in
+/// RON next to the PNG so a painted mountain is two files, no code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeightmapConfig {
+    pub name: String,
+    pub description: String,
+    pub difficulty: u32,
+    /// Checked in ascending `max_elevation` order; pixels above every
+    /// band get the last band's terrain.
+    pub bands: Vec<HeightmapBand>,
+    pub start_position: (i32, i32),
+    pub goal_position: (i32, i32),
+}
+
+/// One problem found by [`LevelDefinition::validate`]. Structured so
+/// tests can assert on the exact failure rather than on log text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelValidationError {
+    StartOutOfBounds { position: (i32, i32) },
+    GoalOutOfBounds { position: (i32, i32) },
+    /// No walkable route from the start tile to the goal tile.
+    GoalUnreachable,
+    TileOutOfBounds { x: i32, y: i32 },
+    DuplicateTile { x: i32, y: i32 },
+    /// An item, NPC, wildlife or entrance spawn that isn't on any tile.
+    SpawnOffGrid {
+        what: &'static str,
+        grid: (i32, i32),
+    },
+    UnknownItemId { item_id: String },
+    UnknownDialogueFile { dialogue_file: String },
+}
+
+impl std::fmt::Display for LevelValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StartOutOfBounds { position } => {
+                write!(f, "start position {position:?} is out of bounds")
+            }
+            Self::GoalOutOfBounds { position } => {
+                write!(f, "goal position {position:?} is out of bounds")
+            }
+            Self::GoalUnreachable => write!(f, "no walkable route from start to goal"),
+            Self::TileOutOfBounds { x, y } => {
+                write!(f, "terrain tile ({x}, {y}) is outside the level dimensions")
+            }
+            Self::DuplicateTile { x, y } => write!(f, "terrain tile ({x}, {y}) appears twice"),
+            Self::SpawnOffGrid { what, grid } => {
+                write!(f, "{what} spawn at grid {grid:?} has no terrain tile")
+            }
+            Self::UnknownItemId { item_id } => write!(f, "unknown item id {item_id:?}"),
+            Self::UnknownDialogueFile { dialogue_file } => {
+                write!(f, "unknown dialogue file {dialogue_file:?}")
+            }
+        }
+    }
+}
+
+impl LevelDefinition {
+    /// Check the level for authoring mistakes: spawn points off the
+    /// grid, dimensions that don't match the tile list, dangling item
+    /// or dialogue references, and a goal the player can't walk to.
+    /// Returns every problem found, empty when the level is sound.
+    pub fn validate(&self) -> Vec<LevelValidationError> {
+        let mut errors = Vec::new();
+        let in_bounds =
+            |(x, y): (i32, i32)| x >= 0 && y >= 0 && x < self.width && y < self.height;
+
+        if !in_bounds(self.start_position) {
+            errors.push(LevelValidationError::StartOutOfBounds {
+                position: self.start_position,
+            });
+        }
+        if !in_bounds(self.goal_position) {
+            errors.push(LevelValidationError::GoalOutOfBounds {
+                position: self.goal_position,
+            });
+        }
+
+        let mut tiles: HashMap<(i32, i32), &TerrainData> = HashMap::new();
+        for tile in &self.terrain {
+            if !in_bounds((tile.x, tile.y)) {
+                errors.push(LevelValidationError::TileOutOfBounds {
+                    x: tile.x,
+                    y: tile.y,
+                });
+            } else if tiles.insert((tile.x, tile.y), tile).is_some() {
+                errors.push(LevelValidationError::DuplicateTile {
+                    x: tile.x,
+                    y: tile.y,
+                });
+            }
+        }
+
+        let world_spawns = self
+            .items
+            .iter()
+            .map(|item| ("item", item.position))
+            .chain(self.npcs.iter().map(|npc| ("npc", npc.position)))
+            .chain(self.wildlife.iter().map(|w| ("wildlife", w.position)));
+        for (what, position) in world_spawns {
+            let grid = world_to_grid(Vec2::new(position.0, position.1), self.width, self.height);
+            if !tiles.contains_key(&grid) {
+                errors.push(LevelValidationError::SpawnOffGrid { what, grid });
+            }
+        }
+        for entrance in &self.entrances {
+            if !tiles.contains_key(&entrance.position) {
+                errors.push(LevelValidationError::SpawnOffGrid {
+                    what: "entrance",
+                    grid: entrance.position,
+                });
+            }
+        }
+
+        for item in &self.items {
+            if !components::KNOWN_ITEM_IDS.contains(&item.item_id.as_str()) {
+                errors.push(LevelValidationError::UnknownItemId {
+                    item_id: item.item_id.clone(),
+                });
+            }
+        }
+        for npc in &self.npcs {
+            if crate::dialogue::dialogue_for_file(&npc.dialogue_file).is_none() {
+                errors.push(LevelValidationError::UnknownDialogueFile {
+                    dialogue_file: npc.dialogue_file.clone(),
+                });
+            }
+        }
+
+        if in_bounds(self.start_position)
+            && in_bounds(self.goal_position)
+            && !reachable(&tiles, self.start_position, self.goal_position)
+        {
+            errors.push(LevelValidationError::GoalUnreachable);
+        }
+        errors
+    }
+}
+
+/// Breadth-first flood fill over non-solid tiles.
+fn reachable(
+    tiles: &HashMap<(i32, i32), &TerrainData>,
+    start: (i32, i32),
+    goal: (i32, i32),
+) -> bool {
+    let passable = |coords: &(i32, i32)| {
+        tiles
+            .get(coords)
+            .is_some_and(|tile| !tile.terrain_type.solid())
+    };
+    if !passable(&start) {
+        return false;
+    }
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some((x, y)) = queue.pop_front() {
+        if (x, y) == goal {
+            return true;
+        }
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let next = (x + dx, y + dy);
+            if passable(&next) && visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+#[derive(Resource, Default)]
+pub struct CurrentLevel {
+    pub name: String,
+    pub definition: Option<LevelDefinition>,
+    /// Whether the goal payout for this visit has been collected.
+    pub completed: bool,
+    /// Chunks currently spawned by the chunk manager.
+    pub spawned_chunks: HashSet<(i32, i32)>,
+    /// Set when a level is (re)loaded so the spawn system repositions
+    /// the player; cleared once handled.
+    pub needs_spawn: bool,
+    /// Where to place the player instead of the start tile, used when
+    /// climbing back out of a sub-area.
+    pub return_position: Option<Vec2>,
+}
+
+/// What a hermit's hints have revealed about one level.
+#[derive(Debug, Default)]
+pub struct KnownSecrets {
+    pub hazards: bool,
+    pub caches: bool,
+    /// Set once the revealed caches have been placed in the world.
+    pub caches_spawned: bool,
+}
+
+/// Everything bought from the mountain's know-it-alls, keyed by level
+/// name.
+#[derive(Resource, Default)]
+pub struct LevelKnowledge {
+    pub by_level: HashMap<String, KnownSecrets>,
+}
+
+impl LevelKnowledge {
+    pub fn for_level(&mut self, name: &str) -> &mut KnownSecrets {
+        self.by_level.entry(name.to_string()).or_default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&KnownSecrets> {
+        self.by_level.get(name)
+    }
+}
+
+/// One suspended overworld (or outer area) while the player is inside a
+/// cave or crevasse; popped when they climb back out.
+pub struct LevelStackFrame {
+    pub name: String,
+    pub definition: LevelDefinition,
+    pub return_position: Vec2,
+}
+
+#[derive(Resource, Default)]
+pub struct LevelStack {
+    pub frames: Vec<LevelStackFrame>,
+}
+
+/// Levels found on disk, refreshed when the level-select screen opens.
+#[derive(Resource, Default)]
+pub struct AvailableLevels {
+    pub levels: Vec<(String, LevelDefinition)>,
+}
+
+/// Scan `levels/` for level files: RON definitions, PNG heightmaps
+/// with a `.heightmap.ron` config, and Tiled JSON exports with a
+/// `.tiled.ron` mapping alongside.
+pub fn list_levels() -> Vec<(String, LevelDefinition)> {
+    let mut found = Vec::new();
+    let Ok(entries) = fs::read_dir("levels") else {
+        return found;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let level = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ron") if !name.contains('.') => load_level(&path),
+            Some("png") => fs::read_to_string(path.with_extension("heightmap.ron"))
+                .ok()
+                .and_then(|contents| ron::from_str::<HeightmapConfig>(&contents).ok())
+                .and_then(|config| LevelDefinition::from_heightmap(&path, &config)),
+            Some("json") => fs::read_to_string(path.with_extension("tiled.ron"))
+                .ok()
+                .and_then(|contents| ron::from_str::<crate::tiled::TiledMapping>(&contents).ok())
+                .and_then(|mapping| crate::tiled::import_tiled(&path, &mapping)),
+            _ => None,
+        };
+        if let Some(level) = level {
+            found.push((name, level));
+        }
+    }
+    if found.is_empty() {
+        return LevelLibrary::embedded_levels();
+    }
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
+
+/// Convert grid coordinates to world coordinates, centered on the level.
+pub fn calculate_tile_position(x: i32, y: i32, width: i32, height: i32) -> Vec3 {
+    Vec3::new(
+        (x - width / 2) as f32 * TILE_SIZE,
+        (y - height / 2) as f32 * TILE_SIZE,
+        0.0,
+    )
+}
+
+/// Inverse of [`calculate_tile_position`]: world coordinates to grid coordinates.
+pub fn world_to_grid(position: Vec2, width: i32, height: i32) -> (i32, i32) {
+    (
+        (position.x / TILE_SIZE).round() as i32 + width / 2,
+        (position.y / TILE_SIZE).round() as i32 + height / 2,
+    )
+}
+
+/// Spawn the sprite + data entity for one tile, using the registry for
+/// per-type data.
+pub fn spawn_terrain_tile(
+    commands: &mut Commands,
+    tile: &TerrainData,
+    level: &LevelDefinition,
+    registry: &TerrainRegistry,
+) {
+    let def = registry.get(tile.terrain_type);
+    let position = calculate_tile_position(tile.x, tile.y, level.width, level.height);
+    // Data-only entity: rendering is handled by the chunk meshes
+    let mut entity = commands.spawn((
+        TransformBundle::from_transform(Transform::from_translation(position)),
+        TerrainTile {
+            terrain_type: tile.terrain_type,
+            biome: tile.biome,
+            climbable: def.climbable,
+            solid: def.solid,
+            stability: def.stability,
+            grid_x: tile.x,
+            grid_y: tile.y,
+        },
+    ));
+    if def.climbable {
+        entity.insert(Climbable {
+            difficulty: tile.difficulty,
+            required_gear: tile.required_gear.clone(),
+        });
+    }
+    if let Some(tool) = def.required_tool {
+        entity.insert(Breakable {
+            tool_required: tool,
+            hits_required: def.hits_to_break,
+            current_hits: 0,
+        });
+    }
+    if let Some(damage_per_second) = def.hazard_damage {
+        entity.insert(Hazardous { damage_per_second });
+    }
+}
+
+/// Spawn the non-terrain contents of a level (NPCs and wildlife).
+pub fn spawn_level(commands: &mut Commands, level: &LevelDefinition) {
+    for npc in &level.npcs {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.8, 0.6, 0.2),
+                    custom_size: Some(Vec2::new(24.0, 30.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(npc.position.0, npc.position.1, 1.0),
+                ..default()
+            },
+            NPC {
+                npc_type: npc.npc_type,
+                name: npc.name.clone(),
+                behavior: components::NpcBehaviorType::Wander,
+                home_position: Vec2::new(npc.position.0, npc.position.1),
+                dialogue_file: npc.dialogue_file.clone(),
+                wander_target: None,
+            },
+            components::Health {
+                current: 100.0,
+                max: 100.0,
+            },
+            components::Stamina {
+                current: 100.0,
+                max: 100.0,
+            },
+            components::Inventory {
+                items: components::npc_pack(npc.npc_type),
+                ..Default::default()
+            },
+        ));
+    }
+    let mut rng = rand::thread_rng();
+    for spawn in &level.wildlife {
+        for _ in 0..spawn.count {
+            spawn_wildlife_member(commands, spawn, &mut rng);
+        }
+    }
+}
+
+/// Spawn one animal of the group described by `spawn`, scattered a
+/// little around the authored point so herds don't stack.
+pub fn spawn_wildlife_member(
+    commands: &mut Commands,
+    spawn: &WildlifeSpawn,
+    rng: &mut impl Rng,
+) {
+    let position = Vec2::new(
+        spawn.position.0 + rng.gen_range(-40.0..40.0),
+        spawn.position.1 + rng.gen_range(-40.0..40.0),
+    );
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: spawn.species.color(),
+                custom_size: Some(spawn.species.sprite_size()),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.0),
+            ..default()
+        },
+        Wildlife {
+            species: spawn.species,
+            attack_damage: match spawn.species {
+                WildlifeSpecies::Wolf => 8.0,
+                WildlifeSpecies::Bear => 15.0,
+                WildlifeSpecies::Eagle => 4.0,
+                _ => 0.0,
+            },
+            aggression: match spawn.species {
+                WildlifeSpecies::Wolf | WildlifeSpecies::Bear => 0.7,
+                WildlifeSpecies::Eagle => 0.3,
+                _ => 0.0,
+            },
+            flee_distance: components::species_profile(spawn.species).flee_distance,
+            spawn_point: Vec2::new(spawn.position.0, spawn.position.1),
+            roam_target: None,
+            health: spawn.species.max_health(),
+            scared: 0.0,
+            attack_cooldown: 0.0,
+        },
+    ));
+}
+
+/// Hash a lattice point to a stable pseudo-random value in 0..1.
+fn lattice_value(x: i32, y: i32, seed: u64) -> f32 {
+    let mut h = seed
+        .wrapping_add((x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+        .wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h ^= (y as u64).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 31;
+    h = h.wrapping_mul(0xd6e8_feb8_6659_fd93);
+    h ^= h >> 32;
+    (h & 0xffff) as f32 / 65535.0
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Coherent value noise: bilinear interpolation between hashed lattice
+/// points, so neighbouring samples vary smoothly.
+fn value_noise(x: f32, y: f32, seed: u64) -> f32 {
+    let x0 = x.floor() as i32;
+    let y0 = y.floor() as i32;
+    let tx = smoothstep(x - x0 as f32);
+    let ty = smoothstep(y - y0 as f32);
+    let v00 = lattice_value(x0, y0, seed);
+    let v10 = lattice_value(x0 + 1, y0, seed);
+    let v01 = lattice_value(x0, y0 + 1, seed);
+    let v11 = lattice_value(x0 + 1, y0 + 1, seed);
+    let top = v00 + (v10 - v00) * tx;
+    let bottom = v01 + (v11 - v01) * tx;
+    top + (bottom - top) * ty
+}
+
+/// Fractal Brownian motion: several octaves of value noise.
+fn fbm(x: f32, y: f32, seed: u64, octaves: u32) -> f32 {
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    let mut total = 0.0;
+    let mut max = 0.0;
+    for octave in 0..octaves {
+        total += value_noise(x * frequency, y * frequency, seed.wrapping_add(octave as u64))
+            * amplitude;
+        max += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    total / max
+}
+
+/// Ridged noise: sharp crests where the underlying noise crosses 0.5.
+fn ridge_noise(x: f32, y: f32, seed: u64) -> f32 {
+    1.0 - (2.0 * fbm(x, y, seed, 4) - 1.0).abs()
+}
+
+const NOISE_SCALE: f32 = 0.04;
+
+/// Multi-octave elevation: a broad peak falloff shaped by fBm, with
+/// ridge lines carving spurs and valleys.
+fn elevation_map(width: i32, height: i32, seed: u64) -> Vec<Vec<f32>> {
+    let peak = (width as f32 / 2.0, height as f32 * 0.8);
+    let max_dist = (width as f32).hypot(height as f32) * 0.5;
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let dist = (x as f32 - peak.0).hypot(y as f32 - peak.1);
+                    let falloff = (1.0 - dist / max_dist).clamp(0.0, 1.0);
+                    let base = fbm(x as f32 * NOISE_SCALE, y as f32 * NOISE_SCALE, seed, 5);
+                    let ridges = ridge_noise(
+                        x as f32 * NOISE_SCALE * 2.0,
+                        y as f32 * NOISE_SCALE * 2.0,
+                        seed.wrapping_add(101),
+                    );
+                    (falloff * (0.55 + 0.3 * base + 0.15 * ridges)
+                        / 0.8)
+                        .clamp(0.0, 1.0)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Moisture field used alongside elevation for biome assignment.
+fn moisture_map(width: i32, height: i32, seed: u64) -> Vec<Vec<f32>> {
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    fbm(
+                        x as f32 * NOISE_SCALE * 0.7,
+                        y as f32 * NOISE_SCALE * 0.7,
+                        seed.wrapping_add(977),
+                        4,
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Pick a tile's terrain type from its elevation band, with moisture
+/// deciding the ties (wet lowlands grow grass, wet heights hold snow).
+pub fn apply_terrain_by_elevation(elevation: f32, moisture: f32) -> TerrainType {
+    match elevation {
+        e if e < 0.12 => TerrainType::Coast,
+        e if e < 0.3 => {
+            if moisture > 0.45 {
+                TerrainType::Grass
+            } else {
+                TerrainType::Soil
+            }
+        }
+        e if e < 0.55 => {
+            if moisture > 0.6 {
+                TerrainType::Grass
+            } else {
+                TerrainType::Rock
+            }
+        }
+        e if e < 0.7 => TerrainType::Rock,
+        e if e < 0.82 => {
+            if moisture > 0.5 {
+                TerrainType::Snow
+            } else {
+                TerrainType::Ice
+            }
+        }
+        _ => TerrainType::Glacier,
+    }
+}
+
+/// Assign a biome from the same elevation/moisture fields; volcanic
+/// biomes are only ever set explicitly by the level constructors.
+pub fn biome_for(elevation: f32, moisture: f32) -> Biome {
+    match elevation {
+        e if e < 0.12 => Biome::Coastal,
+        e if e < 0.35 => {
+            if moisture > 0.55 {
+                Biome::Forest
+            } else {
+                Biome::Meadow
+            }
+        }
+        e if e < 0.7 => Biome::Alpine,
+        _ => Biome::Glacier,
+    }
+}
+
+/// Gear a tile of this terrain demands before the player may enter.
+pub fn default_gear_for(terrain_type: TerrainType) -> Vec<String> {
+    match terrain_type {
+        TerrainType::Glacier => vec!["crampons".to_string(), "rope".to_string()],
+        TerrainType::Ice | TerrainType::Snow => vec!["crampons".to_string()],
+        TerrainType::Lava => vec!["heat_protection".to_string()],
+        TerrainType::Cliff => vec!["rope".to_string(), "harness".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+pub fn create_mountain_terrain(width: i32, height: i32, seed: u64) -> Vec<TerrainData> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let elevations = elevation_map(width, height, seed);
+    let moistures = moisture_map(width, height, seed);
+    let mut terrain = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let elevation = elevations[y as usize][x as usize];
+            let moisture = moistures[y as usize][x as usize];
+            let mut terrain_type = apply_terrain_by_elevation(elevation, moisture);
+            // Jitter the biome inputs so boundaries interleave rather
+            // than cut straight across the map
+            let jitter = (lattice_value(x, y, seed.wrapping_add(7)) - 0.5) * 0.06;
+            let biome = biome_for(elevation + jitter, moisture - jitter);
+            // Occasional impassable cliffs on steep rock
+            if terrain_type == TerrainType::Rock && rng.gen_bool(0.05) {
+                terrain_type = TerrainType::Cliff;
+            }
+            // Woody growth on the lower slopes, gatherable for firewood
+            if matches!(terrain_type, TerrainType::Grass | TerrainType::Soil) {
+                match biome {
+                    Biome::Forest => {
+                        if rng.gen_bool(0.25) {
+                            terrain_type = TerrainType::Tree;
+                        } else if rng.gen_bool(0.15) {
+                            terrain_type = TerrainType::Shrub;
+                        }
+                    }
+                    Biome::Meadow => {
+                        if rng.gen_bool(0.04) {
+                            terrain_type = TerrainType::Shrub;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            let required_gear = default_gear_for(terrain_type);
+            terrain.push(TerrainData {
+                x,
+                y,
+                terrain_type,
+                biome,
+                difficulty: elevation * 10.0,
+                required_gear,
+            });
+        }
+    }
+    carve_rivers(&mut terrain, &elevations, width, height, &mut rng);
+    terrain
+}
+
+fn large_mountain_level(seed: u64) -> LevelDefinition {
+    let width = 200;
+    let height = 150;
+    let terrain = create_mountain_terrain(width, height, seed);
+    let wildlife = populate_wildlife(&terrain, width, height, seed);
+    let entrances = scatter_entrances(&terrain, seed);
+    LevelDefinition {
+        name: "Stóra Fjallið".to_string(),
+        reward: 150.0,
+        description: "A huge mountain rising from the coast.".to_string(),
+        difficulty: 3,
+        seed,
+        width,
+        height,
+        start_position: (width / 2, 4),
+        goal_position: (width / 2, (height as f32 * 0.8) as i32),
+        terrain,
+        entrances,
+        items: vec![
+            ItemSpawn {
+                item_id: "crampons".to_string(),
+                position: (96.0, -2080.0),
+            },
+            ItemSpawn {
+                item_id: "rope".to_string(),
+                position: (-160.0, -1950.0),
+            },
+        ],
+        npcs: vec![
+            NPCSpawn {
+                npc_type: components::NPCType::Guide,
+                name: "Erik".to_string(),
+                position: (64.0, -2100.0),
+                dialogue_file: "erik_guide.ron".to_string(),
+            },
+            NPCSpawn {
+                npc_type: components::NPCType::Trader,
+                name: "Sigrún".to_string(),
+                position: (-128.0, -2100.0),
+                dialogue_file: "sigrun_trader.ron".to_string(),
+            },
+        ],
+        wildlife,
+    }
+}
+
+fn volcanic_peaks_level(seed: u64) -> LevelDefinition {
+    let width = 120;
+    let height = 100;
+    let mut terrain = create_mountain_terrain(width, height, seed);
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+    for tile in terrain.iter_mut() {
+        if tile.terrain_type == TerrainType::Rock && rng.gen_bool(0.08) {
+            tile.terrain_type = TerrainType::Lava;
+            tile.biome = Biome::Volcanic;
+            tile.required_gear = vec!["heat_protection".to_string()];
+        }
+    }
+    LevelDefinition {
+        name: "Eldfjöll".to_string(),
+        reward: 250.0,
+        description: "Volcanic peaks with active lava fields.".to_string(),
+        difficulty: 5,
+        seed,
+        width,
+        height,
+        start_position: (width / 2, 4),
+        goal_position: (width / 2, (height as f32 * 0.8) as i32),
+        terrain,
+        entrances: Vec::new(),
+        items: vec![ItemSpawn {
+            item_id: "heat_protection".to_string(),
+            position: (120.0, -1400.0),
+        }],
+        npcs: vec![NPCSpawn {
+            npc_type: components::NPCType::Hermit,
+            name: "Völundur".to_string(),
+            position: (-96.0, -1420.0),
+            dialogue_file: "volundur_hermit.ron".to_string(),
+        }],
+        wildlife: Vec::new(),
+    }
+}
+
+fn glacier_level(seed: u64) -> LevelDefinition {
+    let width = 140;
+    let height = 110;
+    let mut terrain = create_mountain_terrain(width, height, seed);
+    for tile in terrain.iter_mut() {
+        if tile.terrain_type == TerrainType::Ice {
+            tile.terrain_type = TerrainType::Glacier;
+        }
+    }
+    let entrances = scatter_entrances(&terrain, seed);
+    LevelDefinition {
+        name: "Jökulheimar".to_string(),
+        reward: 200.0,
+        description: "An endless glacier hiding ancient secrets.".to_string(),
+        difficulty: 4,
+        seed,
+        width,
+        height,
+        start_position: (width / 2, 4),
+        goal_position: (width / 2, (height as f32 * 0.8) as i32),
+        terrain,
+        entrances,
+        items: vec![ItemSpawn {
+            item_id: "rune_stone".to_string(),
+            position: (48.0, -1500.0),
+        }],
+        npcs: vec![NPCSpawn {
+            npc_type: components::NPCType::Mage,
+            name: "Freyja".to_string(),
+            position: (80.0, -1560.0),
+            dialogue_file: "freyja_mage.ron".to_string(),
+        }],
+        wildlife: vec![WildlifeSpawn {
+            species: WildlifeSpecies::ArcticFox,
+            position: (-200.0, -1400.0),
+            count: 2,
+        }],
+    }
+}
+
+/// Tiles at least this difficult to cross are deep water: impassable
+/// without a rope line.
+pub const DEEP_WATER_DIFFICULTY: f32 = 5.0;
+
+/// Carve rivers from high ground down to the coast by walking the
+/// steepest descent of the elevation map. Water deepens as it flows.
+fn carve_rivers(
+    terrain: &mut [TerrainData],
+    elevations: &[Vec<f32>],
+    width: i32,
+    height: i32,
+    rng: &mut StdRng,
+) {
+    let index = |x: i32, y: i32| (y * width + x) as usize;
+    for _ in 0..3 {
+        // Find a source somewhere high
+        let mut source = None;
+        for _ in 0..200 {
+            let x = rng.gen_range(1..width - 1);
+            let y = rng.gen_range(1..height - 1);
+            if elevations[y as usize][x as usize] > 0.7 {
+                source = Some((x, y));
+                break;
+            }
+        }
+        let Some((mut x, mut y)) = source else {
+            continue;
+        };
+        let mut depth = 1.0_f32;
+        loop {
+            let tile = &mut terrain[index(x, y)];
+            tile.terrain_type = TerrainType::Water;
+            tile.difficulty = depth.min(8.0);
+            tile.required_gear.clear();
+            if elevations[y as usize][x as usize] < 0.25 {
+                break; // reached the coast
+            }
+            // Steepest descent among the four neighbours
+            let mut next = None;
+            let mut lowest = elevations[y as usize][x as usize];
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= width || ny >= height {
+                    continue;
+                }
+                let e = elevations[ny as usize][nx as usize];
+                if e < lowest {
+                    lowest = e;
+                    next = Some((nx, ny));
+                }
+            }
+            let Some((nx, ny)) = next else {
+                break; // stuck in a hollow: the river ends in a tarn
+            };
+            // Big drops are waterfalls and scour a deeper channel
+            let drop = elevations[y as usize][x as usize] - lowest;
+            depth += if drop > 0.05 { 1.0 } else { 0.25 };
+            x = nx;
+            y = ny;
+        }
+    }
+}
+
+/// Sprinkle wildlife spawns across the map according to each tile's
+/// biome table.
+fn populate_wildlife(
+    terrain: &[TerrainData],
+    level_width: i32,
+    level_height: i32,
+    seed: u64,
+) -> Vec<WildlifeSpawn> {
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(4099));
+    let mut spawns = Vec::new();
+    for tile in terrain {
+        let table = tile.biome.wildlife_table();
+        if table.is_empty() || !rng.gen_bool(0.002) {
+            continue;
+        }
+        let species = table[rng.gen_range(0..table.len())];
+        let position = calculate_tile_position(tile.x, tile.y, level_width, level_height);
+        spawns.push(WildlifeSpawn {
+            species,
+            position: (position.x, position.y),
+            count: match species {
+                WildlifeSpecies::Sheep | WildlifeSpecies::Horse => rng.gen_range(2..5),
+                WildlifeSpecies::Wolf => rng.gen_range(2..4),
+                _ => 1,
+            },
+        });
+    }
+    spawns
+}
+
+/// Pick cave mouths on rock and crevasse lips on glacier tiles.
+fn scatter_entrances(terrain: &[TerrainData], seed: u64) -> Vec<EntranceSpawn> {
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(7001));
+    let mut entrances = Vec::new();
+    let rocks: Vec<_> = terrain
+        .iter()
+        .filter(|t| t.terrain_type == TerrainType::Rock)
+        .collect();
+    let glaciers: Vec<_> = terrain
+        .iter()
+        .filter(|t| t.terrain_type == TerrainType::Glacier)
+        .collect();
+    for _ in 0..2 {
+        if !rocks.is_empty() {
+            let tile = rocks[rng.gen_range(0..rocks.len())];
+            entrances.push(EntranceSpawn {
+                kind: EntranceKind::Cave,
+                position: (tile.x, tile.y),
+            });
+        }
+        if !glaciers.is_empty() {
+            let tile = glaciers[rng.gen_range(0..glaciers.len())];
+            entrances.push(EntranceSpawn {
+                kind: EntranceKind::Crevasse,
+                position: (tile.x, tile.y),
+            });
+        }
+    }
+    entrances
+}
+
+/// Build the small interior sub-level behind a cave or crevasse mouth:
+/// a walled chamber, cold and dark, with loot worth the detour.
+pub fn generate_interior(kind: EntranceKind, seed: u64) -> LevelDefinition {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let width = 16;
+    let height = 12;
+    let (floor, name) = match kind {
+        EntranceKind::Crevasse => (TerrainType::Ice, "Crevasse"),
+        _ => (TerrainType::Rock, "Cave"),
+    };
+    let mut terrain = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let wall = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            terrain.push(TerrainData {
+                x,
+                y,
+                terrain_type: if wall { TerrainType::Cliff } else { floor },
+                biome: Biome::Glacier,
+                difficulty: 2.0,
+                required_gear: Vec::new(),
+            });
+        }
+    }
+    let mut items = Vec::new();
+    for _ in 0..rng.gen_range(2..5) {
+        let x = rng.gen_range(2..width - 2);
+        let y = rng.gen_range(2..height - 2);
+        let position = calculate_tile_position(x, y, width, height);
+        let loot = match kind {
+            EntranceKind::Crevasse => "ice_chunk",
+            _ => "mineral",
+        };
+        items.push(ItemSpawn {
+            item_id: loot.to_string(),
+            position: (position.x, position.y),
+        });
+    }
+    LevelDefinition {
+        name: name.to_string(),
+        description: "A dark, cold hollow in the mountain.".to_string(),
+        difficulty: 2,
+        reward: 40.0,
+        seed,
+        width,
+        height,
+        start_position: (2, 2),
+        goal_position: (width - 2, height - 2),
+        terrain,
+        items,
+        npcs: Vec::new(),
+        wildlife: Vec::new(),
+        entrances: vec![EntranceSpawn {
+            kind: EntranceKind::Exit,
+            position: (2, 2),
+        }],
+    }
+}
+
+impl LevelDefinition {
+    /// Build a level from a grayscale PNG heightmap: luminance is
+    /// elevation, mapped through the config's bands. The bottom image
+    /// row becomes grid row 0 so the map reads the same way up in an
+    /// image editor and in game.
+    pub fn from_heightmap(path: &Path, config: &HeightmapConfig) -> Option<LevelDefinition> {
+        let image = match image::open(path) {
+            Ok(image) => image.into_luma8(),
+            Err(e) => {
+                error!("Failed to read heightmap {}: {e}", path.display());
+                return None;
+            }
+        };
+        if config.bands.is_empty() {
+            error!("Heightmap config {:?} has no elevation bands", config.name);
+            return None;
+        }
+        let width = image.width() as i32;
+        let height = image.height() as i32;
+        let mut terrain = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = image.get_pixel(x as u32, (height - 1 - y) as u32).0[0];
+                let elevation = pixel as f32 / 255.0;
+                let terrain_type = config
+                    .bands
+                    .iter()
+                    .find(|band| elevation <= band.max_elevation)
+                    .unwrap_or_else(|| config.bands.last().unwrap())
+                    .terrain_type;
+                terrain.push(TerrainData {
+                    x,
+                    y,
+                    terrain_type,
+                    biome: biome_for(elevation, 0.5),
+                    difficulty: elevation * 10.0,
+                    required_gear: default_gear_for(terrain_type),
+                });
+            }
+        }
+        let mut level = LevelDefinition {
+            name: config.name.clone(),
+            description: config.description.clone(),
+            difficulty: config.difficulty,
+            reward: config.difficulty as f32 * 50.0,
+            seed: 0,
+            width,
+            height,
+            start_position: config.start_position,
+            goal_position: config.goal_position,
+            terrain,
+            items: Vec::new(),
+            npcs: Vec::new(),
+            wildlife: Vec::new(),
+            entrances: Vec::new(),
+        };
+        ensure_route(&mut level);
+        Some(level)
+    }
+}
+
+/// The level archetypes the generator knows how to build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelKind {
+    LargeMountain,
+    VolcanicPeaks,
+    GlacierTraverse,
+}
+
+/// Generate a level deterministically from its kind and seed.
+pub fn generate_level(kind: LevelKind, seed: u64) -> LevelDefinition {
+    let mut level = match kind {
+        LevelKind::LargeMountain => large_mountain_level(seed),
+        LevelKind::VolcanicPeaks => volcanic_peaks_level(seed),
+        LevelKind::GlacierTraverse => glacier_level(seed),
+    };
+    ensure_route(&mut level);
+    level
+}
+
+/// Guarantee a fully equipped climber can reach the summit. When the
+/// noise happens to seal the goal off, carve the shortest blocking
+/// stretches into passable rock rather than rerolling the whole map.
+fn ensure_route(level: &mut LevelDefinition) {
+    if pathfinding::find_path(
+        &level.terrain,
+        level.start_position,
+        level.goal_position,
+        pathfinding::FULL_GEAR,
+    )
+    .is_some()
+    {
+        return;
+    }
+    let Some(route) = pathfinding::carving_route(
+        &level.terrain,
+        level.start_position,
+        level.goal_position,
+        pathfinding::FULL_GEAR,
+    ) else {
+        warn!("Level {}: no route to the goal could be carved", level.name);
+        return;
+    };
+    let index = |x: i32, y: i32| (y * level.width + x) as usize;
+    for (x, y) in route {
+        let tile = &mut level.terrain[index(x, y)];
+        let blocked = tile.terrain_type.solid()
+            || (tile.terrain_type == TerrainType::Water
+                && tile.difficulty >= DEEP_WATER_DIFFICULTY)
+            || tile.terrain_type == TerrainType::Lava;
+        if blocked {
+            if tile.terrain_type == TerrainType::Water {
+                // A ford: still water, but shallow enough to wade
+                tile.difficulty = 2.0;
+            } else {
+                tile.terrain_type = TerrainType::Rock;
+                tile.difficulty = 3.0;
+            }
+            tile.required_gear.clear();
+        }
+    }
+}
+
+/// The hand-authored starter level, embedded so a fresh install (or an
+/// unwritable levels directory) always has something to climb.
+const EMBEDDED_TRAINING_SLOPE: &str = include_str!("../levels/training_slope.ron");
+
+/// Knows which levels ship with the game and keeps the on-disk copies
+/// in order without clobbering hand edits.
+#[derive(Resource)]
+pub struct LevelLibrary {
+    /// Rewrite sample levels even when the files exist. Set with
+    /// `--regenerate-levels` or `KLIFURPLANTA_REGENERATE_LEVELS=1`.
+    pub force_regenerate: bool,
+}
+
+impl Default for LevelLibrary {
+    fn default() -> Self {
+        let force = std::env::args().any(|arg| arg == "--regenerate-levels")
+            || std::env::var("KLIFURPLANTA_REGENERATE_LEVELS").is_ok_and(|value| value == "1");
+        Self {
+            force_regenerate: force,
+        }
+    }
+}
+
+impl LevelLibrary {
+    /// Write any sample level that's missing from `levels/`, leaving
+    /// existing files alone unless regeneration is forced.
+    pub fn ensure_sample_levels(&self) {
+        let levels_dir = Path::new("levels");
+        if !levels_dir.exists() {
+            if let Err(e) = fs::create_dir_all(levels_dir) {
+                error!("Failed to create levels directory: {e}");
+                return;
+            }
+        }
+        let slope_path = levels_dir.join("training_slope.ron");
+        if self.force_regenerate || !slope_path.exists() {
+            if let Err(e) = fs::write(&slope_path, EMBEDDED_TRAINING_SLOPE) {
+                error!("Failed to write level training_slope.ron: {e}");
+            }
+        }
+        let generated = [
+            ("large_mountain_01.ron", LevelKind::LargeMountain, 1),
+            ("volcanic_peaks.ron", LevelKind::VolcanicPeaks, 2),
+            ("glacier_traverse.ron", LevelKind::GlacierTraverse, 3),
+        ];
+        for (file_name, kind, seed) in generated {
+            let path = levels_dir.join(file_name);
+            if !self.force_regenerate && path.exists() {
+                continue;
+            }
+            let level = generate_level(kind, seed);
+            match ron::ser::to_string_pretty(&level, ron::ser::PrettyConfig::default()) {
+                Ok(contents) => {
+                    if let Err(e) = fs::write(&path, contents) {
+                        error!("Failed to write level {file_name}: {e}");
+                    }
+                }
+                Err(e) => error!("Failed to serialize level {file_name}: {e}"),
+            }
+        }
+    }
+
+    /// The embedded fallback levels, parsed fresh.
+    pub fn embedded_levels() -> Vec<(String, LevelDefinition)> {
+        ron::from_str(EMBEDDED_TRAINING_SLOPE)
+            .map(|level| vec![("training_slope".to_string(), level)])
+            .unwrap_or_default()
+    }
+}
+
+pub fn load_level(path: &Path) -> Option<LevelDefinition> {
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<LevelDefinition>(&contents) {
+            Ok(level) => {
+                for error in level.validate() {
+                    warn!("Level {}: {error}", path.display());
+                }
+                Some(level)
+            }
+            Err(e) => {
+                error!("Failed to parse level {}: {e}", path.display());
+                None
+            }
+        },
+        Err(e) => {
+            error!("Failed to read level {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_level() -> LevelDefinition {
+        generate_interior(EntranceKind::Cave, 1)
+    }
+
+    #[test]
+    fn interior_levels_validate_cleanly() {
+        assert_eq!(valid_level().validate(), Vec::new());
+    }
+
+    #[test]
+    fn goal_out_of_bounds_is_reported() {
+        let mut level = valid_level();
+        level.goal_position = (level.width, 0);
+        assert!(level
+            .validate()
+            .contains(&LevelValidationError::GoalOutOfBounds {
+                position: (level.width, 0)
+            }));
+    }
+
+    #[test]
+    fn unknown_item_id_is_reported() {
+        let mut level = valid_level();
+        level.items.push(ItemSpawn {
+            item_id: "definitely_not_an_item".to_string(),
+            position: (0.0, 0.0),
+        });
+        let errors = level.validate();
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            LevelValidationError::UnknownItemId { item_id } if item_id == "definitely_not_an_item"
+        )));
+    }
+
+    #[test]
+    fn walled_off_goal_is_unreachable() {
+        let mut level = valid_level();
+        let (gx, gy) = level.goal_position;
+        for tile in level.terrain.iter_mut() {
+            if (tile.x - gx).abs() <= 1 && (tile.y - gy).abs() <= 1 && (tile.x, tile.y) != (gx, gy)
+            {
+                tile.terrain_type = TerrainType::Cliff;
+            }
+        }
+        assert!(level.validate().contains(&LevelValidationError::GoalUnreachable));
+    }
+}
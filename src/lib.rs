@@ -0,0 +1,22 @@
+pub mod ai;
+pub mod audio;
+pub mod components;
+pub mod dialogue;
+pub mod items;
+pub mod levels;
+pub mod localization;
+pub mod particles;
+pub mod pathfinding;
+pub mod plugins;
+pub mod quests;
+pub mod saves;
+pub mod settings;
+pub mod sprites;
+pub mod systems;
+pub mod terrain;
+pub mod tiled;
+pub mod ui;
+pub mod volcano;
+pub mod weather;
+
+pub use plugins::KlifurplantaPlugin;
@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// One RON table per locale, keyed by string identifiers (UI labels)
+/// or by the exact English line (free-form gameplay and dialogue
+/// text).
+pub const LOCALES_DIR: &str = "assets/locales";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Icelandic,
+}
+
+impl Language {
+    /// How the language names itself, for the settings row.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Icelandic => "Íslenska",
+        }
+    }
+
+    /// The locale file stem under [`LOCALES_DIR`].
+    fn file_stem(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Icelandic => "is",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Language::English => Language::Icelandic,
+            Language::Icelandic => Language::English,
+        }
+    }
+}
+
+/// The built-in table for a language: English is the source text, the
+/// others override whatever they have translated. Shipping these in
+/// code keeps a missing or stale locale file from breaking the UI.
+fn builtin(language: Language) -> &'static [(&'static str, &'static str)] {
+    match language {
+        Language::English => &[
+            ("hud-health", "Health"),
+            ("hud-stamina", "Stamina"),
+            ("hud-weight", "Weight"),
+            ("hud-purse", "Purse"),
+            ("menu-continue", "Continue the last expedition"),
+            ("menu-continue-none", "Continue (no expedition recorded)"),
+            ("menu-new", "New expedition"),
+            ("menu-levels", "Choose a mountain"),
+            ("menu-settings", "Settings"),
+            ("menu-quit", "Quit"),
+            ("settings-language", "Language"),
+        ],
+        Language::Icelandic => &[
+            ("hud-health", "Heilsa"),
+            ("hud-stamina", "Þol"),
+            ("hud-weight", "Þyngd"),
+            ("hud-purse", "Pyngja"),
+            ("menu-continue", "Halda síðasta leiðangri áfram"),
+            ("menu-continue-none", "Halda áfram (enginn leiðangur skráður)"),
+            ("menu-new", "Nýr leiðangur"),
+            ("menu-levels", "Velja fjall"),
+            ("menu-settings", "Stillingar"),
+            ("menu-quit", "Hætta"),
+            ("settings-language", "Tungumál"),
+            // Free-form gameplay lines, keyed by the English text
+            ("No tool equipped!", "Ekkert verkfæri í hendi!"),
+            ("No rope in your pack", "Enginn kaðall í pokanum"),
+            ("Anchor placed — roped in", "Akkeri fest — bundinn í línu"),
+            ("Untied from the anchor", "Leystur frá akkerinu"),
+            ("The rope catches you!", "Kaðallinn grípur þig!"),
+            ("You slip!", "Þú rennur til!"),
+            ("Lost your footing!", "Misstir fótfestuna!"),
+            ("Struck by falling rock!", "Grjóthrun lendir á þér!"),
+            ("The heat is searing!", "Hitinn er sviðandi!"),
+            ("Catching your breath...", "Nærð andanum..."),
+            (
+                "You're spent — legs shaking, lungs burning",
+                "Þú ert úrvinda — fæturnir skjálfa, lungun brenna",
+            ),
+            (
+                "You climb back out into the daylight",
+                "Þú klifrar aftur út í dagsbirtuna",
+            ),
+            (
+                "You squeeze into the dark...",
+                "Þú smeygir þér inn í myrkrið...",
+            ),
+        ],
+    }
+}
+
+/// The active locale's lookup table. UI labels ask [`Strings::get`]
+/// with an identifier; free-form lines pass through [`Strings::tr`].
+#[derive(Resource)]
+pub struct Strings {
+    pub language: Language,
+    table: HashMap<String, String>,
+}
+
+impl Strings {
+    /// Build the table for a language: built-in entries overlaid with
+    /// `assets/locales/<stem>.ron`; a missing file just means the
+    /// built-ins (and the file is written out so there's something for
+    /// translators to edit).
+    pub fn load(language: Language) -> Self {
+        let mut table: HashMap<String, String> = builtin(language)
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let path = Path::new(LOCALES_DIR).join(format!("{}.ron", language.file_stem()));
+        match fs::read_to_string(&path) {
+            Ok(contents) => match ron::from_str::<HashMap<String, String>>(&contents) {
+                Ok(entries) => table.extend(entries),
+                Err(e) => error!("Failed to parse {}: {e}", path.display()),
+            },
+            Err(_) => {
+                let _ = fs::create_dir_all(LOCALES_DIR);
+                if let Ok(contents) =
+                    ron::ser::to_string_pretty(&table, ron::ser::PrettyConfig::default())
+                {
+                    let _ = fs::write(&path, contents);
+                }
+            }
+        }
+        Self { language, table }
+    }
+
+    /// Look an identifier up; an untranslated identifier comes back
+    /// as-is so the gap is visible instead of silent.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.table.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// Translate a free-form English line if the table has it, else
+    /// show it as written. This is how dialogue and gameplay messages
+    /// route without every call site needing an identifier.
+    pub fn tr<'a>(&'a self, text: &'a str) -> &'a str {
+        self.table.get(text).map(String::as_str).unwrap_or(text)
+    }
+}
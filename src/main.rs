@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+use klifurplanta::KlifurplantaPlugin;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Klifurplanta".to_string(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(KlifurplantaPlugin)
+        .run();
+}
@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::*;
+use crate::levels::{self, CurrentLevel, TILE_SIZE};
+use crate::settings::{Action, Settings};
+use crate::systems::TerrainBrokenEvent;
+use crate::terrain::{TerrainIndex, TerrainRegistry};
+
+/// One short-lived square flying under simple ballistics, fading out
+/// over its lifetime.
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    /// Downward acceleration; negative values rise (steam).
+    pub gravity: f32,
+    pub remaining: f32,
+    pub lifetime: f32,
+}
+
+/// A burst of particles from one point. Callers fill in what differs
+/// from the defaults.
+pub struct Burst {
+    pub position: Vec2,
+    pub color: Color,
+    pub count: usize,
+    pub speed: f32,
+    pub gravity: f32,
+    pub lifetime: f32,
+    pub size: f32,
+}
+
+impl Default for Burst {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            color: Color::WHITE,
+            count: 8,
+            speed: 60.0,
+            gravity: 240.0,
+            lifetime: 0.7,
+            size: 3.0,
+        }
+    }
+}
+
+/// Scatter a burst's particles in a fan around its point.
+pub fn spawn_burst(commands: &mut Commands, burst: Burst) {
+    let mut rng = rand::thread_rng();
+    for _ in 0..burst.count {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let speed = burst.speed * rng.gen_range(0.4..1.0);
+        let lifetime = burst.lifetime * rng.gen_range(0.6..1.0);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: burst.color,
+                    custom_size: Some(Vec2::splat(burst.size)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(burst.position.x, burst.position.y, 3.5),
+                ..default()
+            },
+            Particle {
+                velocity: Vec2::from_angle(angle) * speed,
+                gravity: burst.gravity,
+                remaining: lifetime,
+                lifetime,
+            },
+        ));
+    }
+}
+
+/// Fly, fall, fade, despawn.
+pub fn particle_motion_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Transform, &mut Sprite, &mut Particle)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut transform, mut sprite, mut particle) in query.iter_mut() {
+        particle.remaining -= dt;
+        if particle.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        particle.velocity.y -= particle.gravity * dt;
+        transform.translation.x += particle.velocity.x * dt;
+        transform.translation.y += particle.velocity.y * dt;
+        let alpha = particle.remaining / particle.lifetime;
+        sprite.color = sprite.color.with_alpha(alpha);
+    }
+}
+
+/// Shards the colour of the broken ground, thrown from the break.
+pub fn terrain_break_particles_system(
+    mut commands: Commands,
+    registry: Res<TerrainRegistry>,
+    mut events: EventReader<TerrainBrokenEvent>,
+) {
+    for event in events.read() {
+        spawn_burst(
+            &mut commands,
+            Burst {
+                position: event.position,
+                color: registry.get(event.terrain_type).color(),
+                count: 12,
+                speed: 90.0,
+                ..default()
+            },
+        );
+    }
+}
+
+/// Seconds between footstep puffs while walking on soft ground.
+const PUFF_INTERVAL: f32 = 0.3;
+
+/// Snow kicks up white puffs, soil a little dust; hard rock leaves
+/// nothing behind.
+#[allow(clippy::too_many_arguments)]
+pub fn footstep_particles_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    current_level: Res<CurrentLevel>,
+    index: Res<TerrainIndex>,
+    tile_query: Query<&TerrainTile>,
+    player_query: Query<&Transform, With<Player>>,
+    mut until_next: Local<f32>,
+) {
+    let bindings = &settings.bindings;
+    let moving = bindings.pressed(&keyboard, Action::MoveUp)
+        || bindings.pressed(&keyboard, Action::MoveDown)
+        || bindings.pressed(&keyboard, Action::MoveLeft)
+        || bindings.pressed(&keyboard, Action::MoveRight);
+    if !moving {
+        return;
+    }
+    *until_next -= time.delta_seconds();
+    if *until_next > 0.0 {
+        return;
+    }
+    *until_next = PUFF_INTERVAL;
+    let (Ok(transform), Some(level)) =
+        (player_query.get_single(), &current_level.definition)
+    else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    let terrain = index
+        .get(grid_x, grid_y)
+        .and_then(|entity| tile_query.get(entity).ok())
+        .map(|tile| tile.terrain_type);
+    let color = match terrain {
+        Some(TerrainType::Snow) => Color::srgba(0.95, 0.96, 1.0, 0.8),
+        Some(TerrainType::Soil) | Some(TerrainType::Grass) => {
+            Color::srgba(0.5, 0.42, 0.3, 0.7)
+        }
+        _ => return,
+    };
+    spawn_burst(
+        &mut commands,
+        Burst {
+            position: position - Vec2::new(0.0, 10.0),
+            color,
+            count: 3,
+            speed: 22.0,
+            gravity: 40.0,
+            lifetime: 0.45,
+            size: 2.0,
+        },
+    );
+}
+
+/// How often the lava check runs; steam is occasional, not a jet.
+const STEAM_INTERVAL: f32 = 0.4;
+
+/// Lava within a tile of the player wafts steam upward.
+pub fn lava_steam_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    index: Res<TerrainIndex>,
+    tile_query: Query<(&Transform, &TerrainTile)>,
+    player_query: Query<&Transform, (With<Player>, Without<TerrainTile>)>,
+    mut until_next: Local<f32>,
+) {
+    *until_next -= time.delta_seconds();
+    if *until_next > 0.0 {
+        return;
+    }
+    *until_next = STEAM_INTERVAL;
+    let (Ok(player_transform), Some(level)) =
+        (player_query.get_single(), &current_level.definition)
+    else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(
+        player_transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let Some((transform, tile)) = index
+                .get(grid_x + dx, grid_y + dy)
+                .and_then(|entity| tile_query.get(entity).ok())
+            else {
+                continue;
+            };
+            if tile.terrain_type != TerrainType::Lava {
+                continue;
+            }
+            spawn_burst(
+                &mut commands,
+                Burst {
+                    position: transform.translation.truncate()
+                        + Vec2::new(0.0, TILE_SIZE * 0.3),
+                    color: Color::srgba(0.85, 0.85, 0.88, 0.5),
+                    count: 1,
+                    speed: 12.0,
+                    gravity: -30.0,
+                    lifetime: 1.1,
+                    size: 4.0,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::components::TerrainType;
+use crate::levels::{TerrainData, DEEP_WATER_DIFFICULTY};
+
+/// Gear set the generator assumes a fully equipped climber carries when
+/// checking that a summit can be reached at all.
+pub const FULL_GEAR: &[&str] = &["crampons", "rope", "harness", "heat_protection"];
+
+/// Step costs are tracked in hundredths so they can live in a
+/// `BinaryHeap` without floating-point ordering headaches.
+const COST_SCALE: f32 = 100.0;
+
+fn tile_map(terrain: &[TerrainData]) -> HashMap<(i32, i32), &TerrainData> {
+    terrain.iter().map(|tile| ((tile.x, tile.y), tile)).collect()
+}
+
+/// Whether a climber carrying `gear` can cross this tile.
+fn passable(tile: &TerrainData, gear: &[&str]) -> bool {
+    if tile.terrain_type.solid() {
+        return false;
+    }
+    if tile.terrain_type == TerrainType::Water && tile.difficulty >= DEEP_WATER_DIFFICULTY {
+        return gear.contains(&"rope");
+    }
+    tile.required_gear
+        .iter()
+        .all(|required| gear.contains(&required.as_str()))
+}
+
+/// Cost of stepping onto a tile: slow terrain and hard climbs are
+/// dearer, so routes prefer gentle ground where it exists.
+fn step_cost(tile: &TerrainData) -> u32 {
+    let movement = tile.terrain_type.movement_modifier().max(0.1);
+    ((1.0 / movement + tile.difficulty * 0.1) * COST_SCALE) as u32
+}
+
+fn manhattan(a: (i32, i32), b: (i32, i32)) -> u32 {
+    ((a.0 - b.0).abs() + (a.1 - b.1).abs()) as u32 * COST_SCALE as u32
+}
+
+fn reconstruct(
+    came_from: &HashMap<(i32, i32), (i32, i32)>,
+    goal: (i32, i32),
+) -> Vec<(i32, i32)> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(&previous) = came_from.get(&current) {
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+/// A* over the tile grid. `blocked_cost` of `None` treats impassable
+/// tiles as walls; `Some(cost)` lets the search cross them at a steep
+/// price, which is how route carving finds where to dig.
+fn astar(
+    tiles: &HashMap<(i32, i32), &TerrainData>,
+    start: (i32, i32),
+    goal: (i32, i32),
+    gear: &[&str],
+    blocked_cost: Option<u32>,
+) -> Option<Vec<(i32, i32)>> {
+    let mut open = BinaryHeap::new();
+    let mut best: HashMap<(i32, i32), u32> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    open.push(Reverse((manhattan(start, goal), start)));
+    while let Some(Reverse((_, current))) = open.pop() {
+        if current == goal {
+            return Some(reconstruct(&came_from, goal));
+        }
+        let current_cost = best[&current];
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let next = (current.0 + dx, current.1 + dy);
+            let Some(tile) = tiles.get(&next) else {
+                continue;
+            };
+            let cost = if passable(tile, gear) {
+                step_cost(tile)
+            } else {
+                match blocked_cost {
+                    Some(cost) => cost,
+                    None => continue,
+                }
+            };
+            let tentative = current_cost.saturating_add(cost);
+            if best.get(&next).is_none_or(|&known| tentative < known) {
+                best.insert(next, tentative);
+                came_from.insert(next, current);
+                open.push(Reverse((tentative.saturating_add(manhattan(next, goal)), next)));
+            }
+        }
+    }
+    None
+}
+
+/// Find a route across the grid a climber carrying `gear` could walk,
+/// or `None` when start and goal are in different pockets of the map.
+pub fn find_path(
+    terrain: &[TerrainData],
+    start: (i32, i32),
+    goal: (i32, i32),
+    gear: &[&str],
+) -> Option<Vec<(i32, i32)>> {
+    astar(&tile_map(terrain), start, goal, gear, None)
+}
+
+/// Route used to fix an unreachable level: impassable tiles may be
+/// crossed at a heavy cost, so the result hugs walkable ground and
+/// marks the shortest stretches that need carving.
+pub fn carving_route(
+    terrain: &[TerrainData],
+    start: (i32, i32),
+    goal: (i32, i32),
+    gear: &[&str],
+) -> Option<Vec<(i32, i32)>> {
+    astar(&tile_map(terrain), start, goal, gear, Some(5000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Biome;
+
+    fn open_grid(width: i32, height: i32) -> Vec<TerrainData> {
+        let mut terrain = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                terrain.push(TerrainData {
+                    x,
+                    y,
+                    terrain_type: TerrainType::Grass,
+                    biome: Biome::Meadow,
+                    difficulty: 1.0,
+                    required_gear: Vec::new(),
+                });
+            }
+        }
+        terrain
+    }
+
+    #[test]
+    fn path_goes_around_walls() {
+        let mut terrain = open_grid(5, 5);
+        // A cliff wall across the middle with a gap at x = 4
+        for tile in terrain.iter_mut() {
+            if tile.y == 2 && tile.x < 4 {
+                tile.terrain_type = TerrainType::Cliff;
+            }
+        }
+        let path = find_path(&terrain, (0, 0), (0, 4), FULL_GEAR).expect("gap exists");
+        assert!(path.contains(&(4, 2)));
+    }
+
+    #[test]
+    fn sealed_goal_has_no_path() {
+        let mut terrain = open_grid(5, 5);
+        for tile in terrain.iter_mut() {
+            if tile.y == 2 {
+                tile.terrain_type = TerrainType::Cliff;
+            }
+        }
+        assert!(find_path(&terrain, (0, 0), (0, 4), FULL_GEAR).is_none());
+        // but the carving route crosses the wall
+        assert!(carving_route(&terrain, (0, 0), (0, 4), FULL_GEAR).is_some());
+    }
+
+    #[test]
+    fn missing_gear_blocks_ice() {
+        let mut terrain = open_grid(3, 3);
+        for tile in terrain.iter_mut() {
+            if tile.y == 1 {
+                tile.terrain_type = TerrainType::Ice;
+                tile.required_gear = vec!["crampons".to_string()];
+            }
+        }
+        assert!(find_path(&terrain, (0, 0), (0, 2), &[]).is_none());
+        assert!(find_path(&terrain, (0, 0), (0, 2), &["crampons"]).is_some());
+    }
+}
@@ -0,0 +1,489 @@
+//! The app composed as one plugin per domain, so the binary and any
+//! test can stand the whole game (or a slice of it) up with a single
+//! `add_plugins` call.
+//!
+//! Every system, event, and resource is registered by the plugin that
+//! owns its domain — nothing is wired up anywhere else, so a definition
+//! without a registration here is dead and a grep away from being
+//! noticed.
+//!
+//! Simulation — movement, survival drains, weather, hazards, NPC
+//! routines — runs on [`FixedUpdate`] so behaviour doesn't drift with
+//! frame rate; Bevy's fixed schedule accumulates real time and runs the
+//! step as many times as needed. Anything that reads input edges
+//! (`just_pressed`) or only presents state stays on [`Update`], since a
+//! fixed tick can skip a frame and would miss the edge.
+
+use bevy::prelude::*;
+
+use crate::components::{
+    GameState, GameTime, Hotbar, LastDamage, MapMarkers, Party, SeenHazards, ShopInventory,
+    TimeOfDay, WarningMessage, Weather, WeatherSystem,
+};
+use crate::dialogue::ActiveDialogue;
+use crate::levels::{AvailableLevels, CurrentLevel, LevelLibrary, LevelStack};
+use crate::{
+    audio, components, dialogue, items, levels, localization, particles, quests, saves, settings,
+    sprites, systems, terrain, ui, volcano, weather,
+};
+
+/// The player's body and hands: movement, climbing gear, tools,
+/// survival, magic, and the camera that follows it all.
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<systems::ClimbingRules>()
+            .init_resource::<LastDamage>()
+            .init_resource::<components::ActiveSpells>()
+            .insert_resource(systems::RuneCollection::load())
+            .add_event::<systems::FallStartEvent>()
+            .add_event::<systems::PlayerLandedEvent>()
+            .add_event::<systems::PlayerSlippedEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    systems::player_movement_system,
+                    systems::water_crossing_system,
+                    systems::gravity_system,
+                    systems::rope_tether_system,
+                    systems::fall_damage_system,
+                    systems::slip_damage_system,
+                    systems::terrain_interaction_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::anchor_placement_system,
+                    systems::camera_follow_system,
+                    systems::tool_use_system,
+                    systems::check_player_death,
+                    systems::open_level_select_system,
+                    systems::open_building_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            // Survival: the body against the mountain
+            .add_systems(
+                FixedUpdate,
+                (
+                    systems::wetness_system,
+                    systems::body_temperature_system,
+                    systems::wind_push_system,
+                    systems::hunger_thirst_system,
+                    systems::morale_system,
+                    systems::backpack_capacity_system,
+                    systems::mage_warmth_system,
+                    systems::climber_belay_system,
+                    systems::spell_tick_system,
+                    systems::health_system,
+                    systems::light_source_system,
+                    systems::exhaustion_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            // Camp actions, triggered by key presses
+            .add_systems(
+                Update,
+                (
+                    systems::pitch_tent_system,
+                    systems::start_sleep_system,
+                    systems::gather_wood_system,
+                    systems::cook_food_system,
+                    systems::repair_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::secret_knowledge_system,
+                    systems::open_magic_system,
+                    systems::open_skills_system,
+                    saves::save_game_system,
+                    saves::party_restore_system,
+                    systems::return_to_menu_system,
+                    systems::aim_highlight_system,
+                    systems::player_facing_system,
+                    systems::camera_zoom_system,
+                    systems::camera_pan_system,
+                    systems::open_map_system,
+                    systems::floating_number_system,
+                    systems::floating_text_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                Update,
+                systems::sleeping_system.run_if(in_state(GameState::Sleeping)),
+            )
+            .add_systems(
+                Update,
+                systems::building_mode_system.run_if(in_state(GameState::Building)),
+            );
+    }
+}
+
+/// The mountain itself: levels, chunked tile rendering, breakage,
+/// rockfall, eruptions, and the hazards the ground deals out.
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(terrain::load_terrain_registry())
+            .insert_resource(systems::BuiltStructures::load())
+            .init_resource::<CurrentLevel>()
+            .init_resource::<LevelLibrary>()
+            .init_resource::<AvailableLevels>()
+            .init_resource::<LevelStack>()
+            .init_resource::<levels::LevelKnowledge>()
+            .init_resource::<terrain::TerrainIndex>()
+            .init_resource::<terrain::DirtyChunks>()
+            .init_resource::<systems::LevelWatcher>()
+            .init_resource::<systems::PendingLevelLoad>()
+            .init_resource::<systems::LevelLoadProgress>()
+            .init_resource::<systems::RockfallAgitation>()
+            .init_resource::<saves::BrokenTiles>()
+            .init_resource::<volcano::VolcanoActivity>()
+            .add_event::<systems::TerrainBrokenEvent>()
+            // World events: terrain wear, rockfall, eruptions, sub-areas
+            .add_systems(
+                Update,
+                (
+                    systems::spawn_entrances_system,
+                    systems::interactable_prompt_system,
+                    systems::enter_area_system,
+                    systems::level_hot_reload_system,
+                    systems::spawn_built_structures_system,
+                    systems::level_complete_system,
+                    systems::terrain_broken_handler_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    systems::tile_stability_system,
+                    systems::rockfall_spawn_system,
+                    systems::falling_rock_system,
+                    volcano::volcano_scheduler_system,
+                    systems::hazard_damage_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    systems::place_player_at_start,
+                    terrain::terrain_chunk_system,
+                    terrain::rebuild_dirty_chunks,
+                    terrain::update_terrain_index,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, saves::restore_breaks_system)
+            .add_systems(Update, systems::hazard_sighting_system)
+            .add_systems(
+                Update,
+                systems::level_loading_system.run_if(in_state(GameState::Loading)),
+            );
+    }
+}
+
+/// Weather fronts, their presentation, and the day/night cycle.
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameTime>()
+            .init_resource::<WeatherSystem>()
+            .init_resource::<weather::FrontSpawner>()
+            .init_resource::<weather::WeatherCalm>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    systems::update_game_time,
+                    systems::time_of_day_system,
+                    weather::front_spawn_system,
+                    weather::front_drift_system,
+                    weather::calm_dissipation_system,
+                    weather::local_weather_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    weather::sync_weather_state,
+                    weather::weather_particle_spawn_system.run_if(
+                        in_state(Weather::Snow)
+                            .or_else(in_state(Weather::Rain).or_else(in_state(Weather::Storm))),
+                    ),
+                    weather::weather_particle_move_system,
+                    weather::fog_overlay_system,
+                    systems::day_night_overlay_system,
+                    systems::low_health_vignette_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(OnEnter(TimeOfDay::Night), systems::on_night_falls)
+            .add_systems(OnEnter(TimeOfDay::Dawn), systems::on_dawn_breaks);
+    }
+}
+
+/// Everyone else on the mountain: NPCs and their routines, dialogue,
+/// quests, the party, rescues, and wildlife.
+pub struct NpcPlugin;
+
+impl Plugin for NpcPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Party>()
+            .init_resource::<ActiveDialogue>()
+            .insert_resource(dialogue::load_dialogue_library())
+            .insert_resource(dialogue::PlayerReputation::load())
+            .insert_resource(dialogue::ConversationMemory::load())
+            .insert_resource(quests::load_quest_catalog())
+            .insert_resource(quests::QuestLog::load())
+            .init_resource::<systems::RescueState>()
+            .add_event::<systems::PartyInvitationEvent>()
+            .add_systems(
+                Update,
+                (
+                    systems::spawn_npcs_system,
+                    systems::npc_proximity_system,
+                    dialogue::dialogue_input_system,
+                    quests::quest_progress_system,
+                    systems::rescue_spawn_system,
+                    systems::rescue_system,
+                    systems::party_invitation_system,
+                    systems::party_dismiss_system,
+                    systems::guide_route_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (systems::npc_schedule_system, systems::execute_npc_behavior)
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            // Wildlife
+            .add_systems(
+                Update,
+                (
+                    systems::spawn_wildlife_system,
+                    systems::predator_attack_system,
+                    systems::hunt_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                systems::wildlife_system.run_if(in_state(GameState::Climbing)),
+            );
+    }
+}
+
+/// Items in the world and in the pack: pickups, the hotbar, crafting
+/// ingredients, and the shop stock they trade against.
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(items::load_item_database())
+            .insert_resource(items::load_recipe_book())
+            .init_resource::<Hotbar>()
+            .init_resource::<ShopInventory>()
+            .init_resource::<components::ActiveBarter>()
+            .add_systems(
+                Update,
+                (
+                    systems::spawn_item_pickups_system,
+                    systems::item_pickup_system,
+                    systems::hotbar_use_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            );
+    }
+}
+
+/// Every screen and overlay, plus the input loops that drive the
+/// non-climbing states they belong to.
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WarningMessage>()
+            .init_resource::<components::SelectedCharacter>()
+            .add_event::<systems::GameMessageEvent>()
+            .add_systems(Startup, ui::setup_ui)
+            .add_systems(OnEnter(GameState::Menu), ui::setup_menu_ui)
+            .add_systems(OnExit(GameState::Menu), ui::cleanup_menu_ui)
+            .add_systems(
+                Update,
+                systems::main_menu_system.run_if(in_state(GameState::Menu)),
+            )
+            .add_systems(OnEnter(GameState::Settings), ui::setup_settings_ui)
+            .add_systems(OnExit(GameState::Settings), ui::cleanup_settings_ui)
+            .add_systems(
+                Update,
+                (settings::settings_input_system, ui::update_settings_ui)
+                    .run_if(in_state(GameState::Settings)),
+            )
+            .add_systems(
+                OnEnter(GameState::CharacterSelection),
+                ui::setup_character_select_ui,
+            )
+            .add_systems(
+                OnExit(GameState::CharacterSelection),
+                ui::cleanup_character_select_ui,
+            )
+            .add_systems(
+                Update,
+                systems::character_select_system
+                    .run_if(in_state(GameState::CharacterSelection)),
+            )
+            .add_systems(OnEnter(GameState::Loading), ui::setup_loading_ui)
+            .add_systems(OnExit(GameState::Loading), ui::cleanup_loading_ui)
+            .add_systems(
+                Update,
+                ui::update_loading_bar.run_if(in_state(GameState::Loading)),
+            )
+            .add_systems(
+                OnEnter(GameState::LevelSelect),
+                (systems::refresh_available_levels, ui::setup_level_select_ui).chain(),
+            )
+            .add_systems(OnExit(GameState::LevelSelect), ui::cleanup_level_select_ui)
+            .add_systems(
+                Update,
+                systems::level_select_system.run_if(in_state(GameState::LevelSelect)),
+            )
+            .add_systems(OnEnter(GameState::Skills), ui::setup_skills_ui)
+            .add_systems(OnExit(GameState::Skills), ui::cleanup_skills_ui)
+            .add_systems(
+                Update,
+                (systems::skill_spend_system, ui::update_skills_ui)
+                    .run_if(in_state(GameState::Skills)),
+            )
+            .add_systems(OnEnter(GameState::Magic), ui::setup_magic_ui)
+            .add_systems(OnExit(GameState::Magic), ui::cleanup_magic_ui)
+            .add_systems(
+                Update,
+                (systems::spellcasting_system, ui::update_magic_ui)
+                    .run_if(in_state(GameState::Magic)),
+            )
+            .add_systems(OnEnter(GameState::Barter), ui::setup_barter_ui)
+            .add_systems(OnExit(GameState::Barter), ui::cleanup_barter_ui)
+            .add_systems(
+                Update,
+                (systems::barter_system, ui::update_barter_ui)
+                    .run_if(in_state(GameState::Barter)),
+            )
+            .add_systems(OnEnter(GameState::Shop), ui::setup_shop_ui)
+            .add_systems(OnExit(GameState::Shop), ui::cleanup_shop_ui)
+            .add_systems(
+                Update,
+                (systems::shop_system, ui::update_shop_ui).run_if(in_state(GameState::Shop)),
+            )
+            .add_systems(OnEnter(GameState::Inventory), ui::setup_inventory_ui)
+            .add_systems(OnExit(GameState::Inventory), ui::cleanup_inventory_ui)
+            .add_systems(
+                Update,
+                (
+                    systems::consume_item_system,
+                    systems::craft_system,
+                    ui::item_tooltip_system,
+                    ui::hotbar_assign_system,
+                )
+                    .run_if(in_state(GameState::Inventory)),
+            )
+            .add_systems(OnEnter(GameState::Map), systems::setup_map_view)
+            .add_systems(OnExit(GameState::Map), systems::cleanup_map_view)
+            .add_systems(
+                Update,
+                systems::map_input_system.run_if(in_state(GameState::Map)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), ui::setup_game_over_ui)
+            .add_systems(OnExit(GameState::GameOver), ui::cleanup_game_over_ui)
+            .add_systems(
+                Update,
+                saves::game_over_continue_system.run_if(in_state(GameState::GameOver)),
+            )
+            .add_systems(
+                Update,
+                (
+                    ui::update_health_stamina_ui,
+                    ui::update_weight_display,
+                    ui::update_wallet_display,
+                    ui::update_environment_display,
+                    ui::update_hotbar_ui,
+                    ui::update_party_ui,
+                    ui::journal_ui_system,
+                    ui::dialogue_ui_system,
+                    ui::update_warning_text,
+                    ui::message_feed_system,
+                    ui::inventory_toggle_system,
+                ),
+            );
+    }
+}
+
+/// The whole game: shared states and resources, settings and
+/// localization, presentation extras, and every domain plugin.
+pub struct KlifurplantaPlugin;
+
+impl Plugin for KlifurplantaPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = settings::Settings::load();
+        app.init_state::<GameState>()
+            .init_state::<TimeOfDay>()
+            .init_state::<Weather>()
+            .init_resource::<MapMarkers>()
+            .init_resource::<SeenHazards>()
+            .init_resource::<saves::AutosaveState>()
+            .insert_resource(localization::Strings::load(settings.language))
+            .insert_resource(settings)
+            .init_resource::<settings::Rebinding>()
+            .add_systems(
+                Startup,
+                (
+                    systems::setup,
+                    audio::load_audio_assets,
+                    sprites::load_sprite_sheets,
+                ),
+            )
+            .add_systems(Update, settings::apply_settings_system)
+            .add_systems(Update, audio::ui_click_system)
+            .add_systems(
+                Update,
+                (
+                    sprites::attach_sprites_system,
+                    sprites::player_animation_row_system,
+                    sprites::animate_sprites_system,
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    audio::footstep_system,
+                    audio::wind_audio_system,
+                    audio::ambient_bed_system,
+                    audio::terrain_break_audio_system,
+                    particles::particle_motion_system,
+                    particles::terrain_break_particles_system,
+                    particles::footstep_particles_system,
+                    particles::lava_steam_system,
+                )
+                    .run_if(in_state(GameState::Climbing)),
+            )
+            .add_plugins((
+                PlayerPlugin,
+                TerrainPlugin,
+                WeatherPlugin,
+                NpcPlugin,
+                InventoryPlugin,
+                UiPlugin,
+            ));
+    }
+}
@@ -0,0 +1,249 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::components::{GameTime, Money, Player, WarningMessage, NPC};
+use crate::dialogue::PlayerReputation;
+use crate::levels::{CurrentLevel, TILE_SIZE};
+
+const QUESTS_PATH: &str = "assets/quests.ron";
+const QUEST_LOG_PATH: &str = "saves/quests.ron";
+
+/// What finishes a quest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum QuestObjective {
+    /// Reach the goal tile of the named level.
+    ReachGoal { level: String },
+    /// Reach the goal of the named level before the given hour.
+    ReachGoalBefore { level: String, hour: f32 },
+    /// Hand the named NPC one of the given item.
+    DeliverItem { item_id: String, npc_name: String },
+}
+
+/// One quest as authored: what to do and what it pays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestDefinition {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub objective: QuestObjective,
+    #[serde(default)]
+    pub reward_money: f32,
+    #[serde(default)]
+    pub reward_reputation: i32,
+}
+
+/// Every quest the game can hand out. Defaults are built in;
+/// `assets/quests.ron` overlays them so new ones can be authored
+/// without a rebuild.
+#[derive(Resource)]
+pub struct QuestCatalog {
+    pub quests: Vec<QuestDefinition>,
+}
+
+impl QuestCatalog {
+    pub fn get(&self, id: &str) -> Option<&QuestDefinition> {
+        self.quests.iter().find(|quest| quest.id == id)
+    }
+}
+
+impl Default for QuestCatalog {
+    fn default() -> Self {
+        Self {
+            quests: vec![
+                QuestDefinition {
+                    id: "supplies_for_volundur".to_string(),
+                    title: "Supplies for the hermit".to_string(),
+                    description: "Bring Völundur on Eldfjöll some dried fish — he won't come \
+                                  down for it himself."
+                        .to_string(),
+                    objective: QuestObjective::DeliverItem {
+                        item_id: "dried_fish".to_string(),
+                        npc_name: "Völundur".to_string(),
+                    },
+                    reward_money: 60.0,
+                    reward_reputation: 3,
+                },
+                QuestDefinition {
+                    id: "summit_stora_fjallid".to_string(),
+                    title: "The long way up".to_string(),
+                    description: "Reach the summit of Stóra Fjallið.".to_string(),
+                    objective: QuestObjective::ReachGoal {
+                        level: "Stóra Fjallið".to_string(),
+                    },
+                    reward_money: 100.0,
+                    reward_reputation: 2,
+                },
+                QuestDefinition {
+                    id: "jokulheimar_by_dusk".to_string(),
+                    title: "Before the light goes".to_string(),
+                    description: "Cross Jökulheimar and reach its far side before 18:00."
+                        .to_string(),
+                    objective: QuestObjective::ReachGoalBefore {
+                        level: "Jökulheimar".to_string(),
+                        hour: 18.0,
+                    },
+                    reward_money: 150.0,
+                    reward_reputation: 4,
+                },
+            ],
+        }
+    }
+}
+
+/// Build the catalog from `assets/quests.ron`, writing the defaults
+/// out when the file is missing so there's something to edit.
+pub fn load_quest_catalog() -> QuestCatalog {
+    let mut catalog = QuestCatalog::default();
+    let path = Path::new(QUESTS_PATH);
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<Vec<QuestDefinition>>(&contents) {
+            Ok(quests) => catalog.quests = quests,
+            Err(e) => error!("Failed to parse {QUESTS_PATH}: {e}"),
+        },
+        Err(_) => {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(contents) =
+                ron::ser::to_string_pretty(&catalog.quests, ron::ser::PrettyConfig::default())
+            {
+                let _ = fs::write(path, contents);
+            }
+        }
+    }
+    catalog
+}
+
+/// One quest as taken: which one and whether it's done.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestEntry {
+    pub id: String,
+    pub completed: bool,
+}
+
+/// The quests the player has accepted, in the order the journal lists
+/// them. Persisted in `saves/quests.ron`.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct QuestLog {
+    pub entries: Vec<QuestEntry>,
+}
+
+impl QuestLog {
+    pub fn load() -> Self {
+        match fs::read_to_string(QUEST_LOG_PATH) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(log) => log,
+                Err(e) => {
+                    error!("Failed to parse {QUEST_LOG_PATH}: {e}");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Take a quest on; already knowing it is a no-op.
+    pub fn accept(&mut self, id: &str) -> bool {
+        if self.entries.iter().any(|entry| entry.id == id) {
+            return false;
+        }
+        self.entries.push(QuestEntry {
+            id: id.to_string(),
+            completed: false,
+        });
+        self.save();
+        true
+    }
+
+    pub fn save(&self) {
+        if let Some(parent) = Path::new(QUEST_LOG_PATH).parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(QUEST_LOG_PATH, contents) {
+                    error!("Failed to write {QUEST_LOG_PATH}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to serialize quest log: {e}"),
+        }
+    }
+}
+
+/// Check every open quest against the world and pay out the ones that
+/// are done.
+#[allow(clippy::too_many_arguments)]
+pub fn quest_progress_system(
+    catalog: Res<QuestCatalog>,
+    current_level: Res<CurrentLevel>,
+    game_time: Res<GameTime>,
+    npc_query: Query<(&Transform, &NPC), Without<Player>>,
+    mut log: ResMut<QuestLog>,
+    mut autosave: ResMut<crate::saves::AutosaveState>,
+    mut reputation: ResMut<PlayerReputation>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<
+        (&Transform, &mut Money, &mut crate::components::Inventory),
+        With<Player>,
+    >,
+) {
+    let Ok((player_transform, mut money, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let level_name = current_level
+        .definition
+        .as_ref()
+        .map(|level| level.name.clone())
+        .unwrap_or_default();
+    let mut completed_now: Vec<String> = Vec::new();
+    for entry in log.entries.iter_mut().filter(|entry| !entry.completed) {
+        let Some(quest) = catalog.get(&entry.id) else {
+            continue;
+        };
+        let done = match &quest.objective {
+            QuestObjective::ReachGoal { level } => {
+                *level == level_name && current_level.completed
+            }
+            QuestObjective::ReachGoalBefore { level, hour } => {
+                *level == level_name && current_level.completed && game_time.hour < *hour
+            }
+            QuestObjective::DeliverItem { item_id, npc_name } => {
+                let near = npc_query.iter().any(|(transform, npc)| {
+                    npc.name == *npc_name
+                        && transform
+                            .translation
+                            .truncate()
+                            .distance(player_transform.translation.truncate())
+                            < TILE_SIZE * 2.0
+                });
+                let carried = inventory.items.iter().position(|item| {
+                    crate::components::item_from_id(item_id)
+                        .is_some_and(|wanted| wanted.name == item.name)
+                });
+                match (near, carried) {
+                    (true, Some(index)) => {
+                        inventory.items.remove(index);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+        };
+        if done {
+            entry.completed = true;
+            money.0 += quest.reward_money;
+            reputation.adjust(quest.reward_reputation);
+            warning.show(format!(
+                "Quest complete: {} (+{:.0} kr)",
+                quest.title, quest.reward_money
+            ));
+            completed_now.push(quest.id.clone());
+        }
+    }
+    if !completed_now.is_empty() {
+        log.save();
+        autosave.request("quest complete");
+    }
+}
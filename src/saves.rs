@@ -0,0 +1,522 @@
+use bevy::prelude::*;
+use bevy::tasks::AsyncComputeTaskPool;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::components::*;
+use crate::levels::{self, CurrentLevel};
+use crate::systems::PendingLevelLoad;
+
+/// Where the single save slot lives. Reputation, structures, quests,
+/// runes and conversations already persist in files of their own; the
+/// slot covers everything else.
+const SLOT_PATH: &str = "saves/slot_1.ron";
+
+/// Tiles broken open this playthrough, by level name, so the damage
+/// survives chunk respawns and reloads.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct BrokenTiles {
+    pub by_level: HashMap<String, Vec<(i32, i32)>>,
+}
+
+impl BrokenTiles {
+    pub fn record(&mut self, level: &str, position: (i32, i32)) {
+        let entries = self.by_level.entry(level.to_string()).or_default();
+        if !entries.contains(&position) {
+            entries.push(position);
+        }
+    }
+}
+
+/// Everything about the climber worth writing down.
+#[derive(Serialize, Deserialize)]
+pub struct PlayerSave {
+    pub position: (f32, f32),
+    pub archetype: CharacterArchetype,
+    pub health: (f32, f32),
+    pub stamina: (f32, f32),
+    pub morale: (f32, f32),
+    pub hunger: f32,
+    pub thirst: f32,
+    pub body_temperature: f32,
+    pub frostbite: f32,
+    pub wetness: f32,
+    pub money: f32,
+    pub speed: f32,
+    pub climbing_skill: f32,
+    pub xp: f32,
+    pub level: u32,
+    pub skill_points: u32,
+    pub trained_carry: f32,
+    pub trained_warmth: u32,
+    pub perks: Vec<Perk>,
+    pub mana: f32,
+    pub max_mana: f32,
+    pub known_spells: Vec<Spell>,
+    pub inventory: Vec<Item>,
+    pub equipped: EquippedItems,
+}
+
+/// One whole expedition, frozen mid-climb.
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub level_name: String,
+    pub hour: f32,
+    pub day: u32,
+    /// Names of the companions on the rope, re-linked after loading.
+    pub party: Vec<String>,
+    pub broken: HashMap<String, Vec<(i32, i32)>>,
+    /// Cairns the player pinned on the map.
+    #[serde(default)]
+    pub markers: Vec<MapMarker>,
+    pub player: PlayerSave,
+}
+
+/// How many autosave files rotate underneath the manual slot.
+const AUTOSAVE_SLOTS: usize = 3;
+/// World units of climbing between altitude checkpoints.
+const ALTITUDE_STEP: f32 = 1500.0;
+
+fn autosave_path(index: usize) -> String {
+    format!("saves/autosave_{}.ron", index + 1)
+}
+
+/// Autosave bookkeeping: one-shot checkpoint requests from other
+/// systems, the next altitude worth a checkpoint, and which rotating
+/// slot gets written next.
+#[derive(Resource)]
+pub struct AutosaveState {
+    pub requested: Option<String>,
+    pub next_altitude: f32,
+    cursor: usize,
+}
+
+impl Default for AutosaveState {
+    fn default() -> Self {
+        Self {
+            requested: None,
+            next_altitude: ALTITUDE_STEP,
+            cursor: 0,
+        }
+    }
+}
+
+impl AutosaveState {
+    /// Ask for a checkpoint at the next opportunity, e.g. on waking
+    /// rested or finishing a quest.
+    pub fn request(&mut self, reason: impl Into<String>) {
+        self.requested = Some(reason.into());
+    }
+}
+
+pub fn slot_exists() -> bool {
+    Path::new(SLOT_PATH).exists()
+}
+
+pub fn read_slot() -> Option<SaveGame> {
+    let contents = fs::read_to_string(SLOT_PATH).ok()?;
+    match ron::from_str(&contents) {
+        Ok(save) => Some(save),
+        Err(e) => {
+            warn!("Failed to parse {SLOT_PATH}: {e}");
+            None
+        }
+    }
+}
+
+/// Companions named in the slot, waiting for their NPCs to spawn so
+/// the rope team can be re-tied.
+#[derive(Resource)]
+pub struct PendingPartyRestore {
+    pub names: Vec<String>,
+}
+
+/// Write the manual slot on F5, and rotating autosaves when another
+/// system requests a checkpoint or the climb crosses an altitude
+/// milestone. The player's components are split over two queries only
+/// because one tuple can't hold them all.
+#[allow(clippy::type_complexity)]
+pub fn save_game_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    markers: Res<MapMarkers>,
+    mut autosave: ResMut<AutosaveState>,
+    current_level: Res<CurrentLevel>,
+    game_time: Res<GameTime>,
+    party: Res<Party>,
+    broken: Res<BrokenTiles>,
+    selected: Res<SelectedCharacter>,
+    mut warning: ResMut<WarningMessage>,
+    npc_query: Query<&NPC, Without<Player>>,
+    body_query: Query<
+        (
+            &Transform,
+            &Health,
+            &Stamina,
+            &Morale,
+            &Hunger,
+            &Thirst,
+            &BodyTemperature,
+            &Frostbite,
+            &Wetness,
+            &Money,
+            &MovementStats,
+        ),
+        With<Player>,
+    >,
+    pack_query: Query<(&Inventory, &EquippedItems, &Experience, &Perks, &MagicUser), With<Player>>,
+) {
+    let manual = settings
+        .bindings
+        .just_pressed(&keyboard, crate::settings::Action::ManualSave);
+    let mut reason = autosave.requested.take();
+    if let Ok((transform, ..)) = body_query.get_single() {
+        if transform.translation.y >= autosave.next_altitude {
+            autosave.next_altitude += ALTITUDE_STEP;
+            reason.get_or_insert_with(|| "altitude gained".to_string());
+        }
+    }
+    if !manual && reason.is_none() {
+        return;
+    }
+    let Ok((
+        transform,
+        health,
+        stamina,
+        morale,
+        hunger,
+        thirst,
+        body,
+        frostbite,
+        wetness,
+        money,
+        stats,
+    )) = body_query.get_single()
+    else {
+        return;
+    };
+    let Ok((inventory, equipped, experience, perks, caster)) = pack_query.get_single() else {
+        return;
+    };
+    let save = SaveGame {
+        level_name: current_level.name.clone(),
+        hour: game_time.hour,
+        day: game_time.day,
+        party: party
+            .members
+            .iter()
+            .filter_map(|&member| npc_query.get(member).ok())
+            .map(|npc| npc.name.clone())
+            .collect(),
+        broken: broken.by_level.clone(),
+        markers: markers.markers.clone(),
+        player: PlayerSave {
+            position: (transform.translation.x, transform.translation.y),
+            archetype: selected.archetype,
+            health: (health.current, health.max),
+            stamina: (stamina.current, stamina.max),
+            morale: (morale.current, morale.max),
+            hunger: hunger.current,
+            thirst: thirst.current,
+            body_temperature: body.current,
+            frostbite: frostbite.severity,
+            wetness: wetness.level,
+            money: money.0,
+            speed: stats.speed,
+            climbing_skill: stats.climbing_skill,
+            xp: experience.xp,
+            level: experience.level,
+            skill_points: experience.skill_points,
+            trained_carry: experience.trained_carry,
+            trained_warmth: experience.trained_warmth,
+            perks: perks.owned.clone(),
+            mana: caster.mana,
+            max_mana: caster.max_mana,
+            known_spells: caster.known_spells.clone(),
+            inventory: inventory.items.clone(),
+            equipped: equipped.clone(),
+        },
+    };
+    if manual {
+        if write_save(&save, SLOT_PATH) {
+            warning.show("Expedition saved");
+        }
+    } else if let Some(reason) = reason {
+        let path = autosave_path(autosave.cursor);
+        autosave.cursor = (autosave.cursor + 1) % AUTOSAVE_SLOTS;
+        if write_save(&save, &path) {
+            warning.show(format!("Checkpoint ({reason})"));
+        }
+    }
+}
+
+fn write_save(save: &SaveGame, path: &str) -> bool {
+    if let Err(e) = fs::create_dir_all("saves") {
+        error!("Failed to create saves directory: {e}");
+        return false;
+    }
+    match ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => match fs::write(path, contents) {
+            Ok(()) => true,
+            Err(e) => {
+                error!("Failed to write {path}: {e}");
+                false
+            }
+        },
+        Err(e) => {
+            error!("Failed to serialize save: {e}");
+            false
+        }
+    }
+}
+
+/// The freshest checkpoint on disk: the newest autosave, or the manual
+/// slot if no autosave beats it.
+pub fn latest_checkpoint() -> Option<SaveGame> {
+    let mut best: Option<(std::time::SystemTime, String)> = None;
+    let mut candidates: Vec<String> = (0..AUTOSAVE_SLOTS).map(autosave_path).collect();
+    candidates.push(SLOT_PATH.to_string());
+    for path in candidates {
+        let Ok(modified) = fs::metadata(&path).and_then(|meta| meta.modified()) else {
+            continue;
+        };
+        if best.as_ref().is_none_or(|(time, _)| modified > *time) {
+            best = Some((modified, path));
+        }
+    }
+    let (_, path) = best?;
+    let contents = fs::read_to_string(&path).ok()?;
+    match ron::from_str(&contents) {
+        Ok(save) => Some(save),
+        Err(e) => {
+            warn!("Failed to parse {path}: {e}");
+            None
+        }
+    }
+}
+
+/// Rebuild the world side of a slot: resources, the level load task,
+/// and the player entity with every saved component.
+pub fn restore(commands: &mut Commands, current_level: &mut CurrentLevel, save: SaveGame) {
+    let player = &save.player;
+    let mut spawned = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.9, 0.3, 0.3),
+                custom_size: Some(Vec2::new(22.0, 28.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(player.position.0, player.position.1, 2.0),
+            ..default()
+        },
+        Player,
+        Health {
+            current: player.health.0,
+            max: player.health.1,
+        },
+        Stamina {
+            current: player.stamina.0,
+            max: player.stamina.1,
+        },
+        MovementStats {
+            speed: player.speed,
+            climbing_skill: player.climbing_skill,
+        },
+        Velocity::default(),
+        Inventory {
+            items: player.inventory.clone(),
+            ..default()
+        },
+        player.equipped.clone(),
+        Money(player.money),
+        Hunger {
+            current: player.hunger,
+            max: 100.0,
+        },
+        Thirst {
+            current: player.thirst,
+            max: 100.0,
+        },
+        BodyTemperature {
+            current: player.body_temperature,
+        },
+        Frostbite {
+            severity: player.frostbite,
+        },
+        Wetness {
+            level: player.wetness,
+        },
+    ));
+    // A bundle holds at most fifteen components; the rest go on with a
+    // second insert on the same entity.
+    spawned.insert((
+        MagicUser {
+            mana: player.mana,
+            max_mana: player.max_mana,
+            known_spells: player.known_spells.clone(),
+        },
+        Experience {
+            xp: player.xp,
+            level: player.level,
+            skill_points: player.skill_points,
+            trained_carry: player.trained_carry,
+            trained_warmth: player.trained_warmth,
+        },
+        Perks {
+            owned: player.perks.clone(),
+        },
+        Morale {
+            current: player.morale.0,
+            max: player.morale.1,
+        },
+        Facing::default(),
+    ));
+    commands.insert_resource(SelectedCharacter {
+        archetype: player.archetype,
+    });
+    commands.insert_resource(MapMarkers {
+        markers: save.markers.clone(),
+    });
+    commands.insert_resource(GameTime {
+        hour: save.hour,
+        day: save.day,
+        ..default()
+    });
+    commands.insert_resource(BrokenTiles {
+        by_level: save.broken,
+    });
+    if !save.party.is_empty() {
+        commands.insert_resource(PendingPartyRestore { names: save.party });
+    }
+
+    // Altitude checkpoints resume counting from where the climb left off
+    commands.insert_resource(AutosaveState {
+        next_altitude: player.position.1 + ALTITUDE_STEP,
+        ..default()
+    });
+
+    current_level.name = save.level_name.clone();
+    // Resume in place rather than back at the level's start tile
+    current_level.return_position = Some(Vec2::new(player.position.0, player.position.1));
+    let level_name = save.level_name;
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        levels::load_level(Path::new(&format!("levels/{level_name}.ron"))).or_else(|| {
+            levels::list_levels()
+                .into_iter()
+                .find(|(name, _)| *name == level_name)
+                .map(|(_, level)| level)
+        })
+    });
+    commands.insert_resource(PendingLevelLoad { task: Some(task) });
+}
+
+/// On the game-over screen, Enter clears the fallen expedition and
+/// picks the climb back up from the freshest checkpoint; Escape
+/// abandons it and returns to the main menu.
+#[allow(clippy::type_complexity)]
+pub fn game_over_continue_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut party: ResMut<Party>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<Entity, With<Player>>,
+    level_entity_query: Query<
+        Entity,
+        Or<(
+            With<TerrainTile>,
+            With<crate::terrain::TerrainChunkMesh>,
+            With<NPC>,
+            With<RopeAnchor>,
+            With<Entrance>,
+            With<Structure>,
+            With<ItemPickup>,
+            With<RouteMarker>,
+            With<SecretMarker>,
+            With<Wildlife>,
+        )>,
+    >,
+) {
+    let retry = keyboard.just_pressed(KeyCode::Enter);
+    let to_menu = keyboard.just_pressed(KeyCode::Escape);
+    if !retry && !to_menu {
+        return;
+    }
+    let save = if retry {
+        let Some(save) = latest_checkpoint() else {
+            return;
+        };
+        Some(save)
+    } else {
+        None
+    };
+    for entity in player_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    crate::systems::despawn_level_entities(&mut commands, &level_entity_query);
+    party.members.clear();
+    current_level.spawned_chunks.clear();
+    commands.remove_resource::<DeathReport>();
+    match save {
+        Some(save) => {
+            restore(&mut commands, &mut current_level, save);
+            next_state.set(GameState::Loading);
+        }
+        None => next_state.set(GameState::Menu),
+    }
+}
+
+/// Re-tie the rope team once the saved level has spawned its NPCs.
+pub fn party_restore_system(
+    mut commands: Commands,
+    pending: Option<ResMut<PendingPartyRestore>>,
+    mut party: ResMut<Party>,
+    mut npc_query: Query<(Entity, &mut NPC), Without<Player>>,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    pending.names.retain(|name| {
+        for (entity, mut npc) in npc_query.iter_mut() {
+            if npc.name == *name {
+                npc.behavior = NpcBehaviorType::Follow;
+                party.members.push(entity);
+                return false;
+            }
+        }
+        true
+    });
+    if pending.names.is_empty() {
+        commands.remove_resource::<PendingPartyRestore>();
+    }
+}
+
+/// Stamp recorded breaks back into the level definition whenever a
+/// level comes up, before its chunks spawn from it.
+pub fn restore_breaks_system(
+    mut applied_for: Local<Option<String>>,
+    broken: Res<BrokenTiles>,
+    mut current_level: ResMut<CurrentLevel>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    if applied_for.as_deref() == Some(level.name.as_str()) {
+        return;
+    }
+    *applied_for = Some(level.name.clone());
+    let Some(positions) = broken.by_level.get(&level.name) else {
+        return;
+    };
+    let positions = positions.clone();
+    let Some(level) = current_level.definition.as_mut() else {
+        return;
+    };
+    for tile in level.terrain.iter_mut() {
+        if positions.contains(&(tile.x, tile.y)) {
+            tile.terrain_type = TerrainType::Soil;
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::components::GameState;
+use crate::levels::CurrentLevel;
+use crate::terrain::DirtyChunks;
+
+/// Options live next to the save slots so one directory carries
+/// everything a player would back up.
+pub const SETTINGS_PATH: &str = "saves/settings.ron";
+
+/// Scales incoming damage; the mountain itself doesn't change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Gentle,
+    #[default]
+    Standard,
+    Alpine,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Gentle => "Gentle",
+            Difficulty::Standard => "Standard",
+            Difficulty::Alpine => "Alpine",
+        }
+    }
+
+    /// Multiplier on damage the player takes.
+    pub fn damage_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Gentle => 0.6,
+            Difficulty::Standard => 1.0,
+            Difficulty::Alpine => 1.5,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Difficulty::Gentle => Difficulty::Standard,
+            Difficulty::Standard => Difficulty::Alpine,
+            Difficulty::Alpine => Difficulty::Gentle,
+        }
+    }
+}
+
+/// Terrain tint scheme. The alternatives trade the natural look for
+/// separations that survive red-green colour blindness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorPalette {
+    #[default]
+    Natural,
+    HighContrast,
+    Deuteranopia,
+}
+
+impl ColorPalette {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorPalette::Natural => "Natural",
+            ColorPalette::HighContrast => "High contrast",
+            ColorPalette::Deuteranopia => "Deuteranopia",
+        }
+    }
+
+    /// Remap a terrain colour for this palette.
+    pub fn adjust(&self, color: Color) -> Color {
+        let c = color.to_srgba();
+        match self {
+            ColorPalette::Natural => color,
+            ColorPalette::HighContrast => {
+                let stretch = |v: f32| ((v - 0.5) * 1.6 + 0.5).clamp(0.0, 1.0);
+                Color::srgb(stretch(c.red), stretch(c.green), stretch(c.blue))
+            }
+            // Fold the green channel toward blue so red/green pairs
+            // read as orange/blue instead.
+            ColorPalette::Deuteranopia => Color::srgb(
+                c.red,
+                (c.green * 0.6 + c.red * 0.2).clamp(0.0, 1.0),
+                (c.blue * 0.7 + c.green * 0.5).clamp(0.0, 1.0),
+            ),
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            ColorPalette::Natural => ColorPalette::HighContrast,
+            ColorPalette::HighContrast => ColorPalette::Deuteranopia,
+            ColorPalette::Deuteranopia => ColorPalette::Natural,
+        }
+    }
+}
+
+/// Everything a key can be bound to. Systems ask the input map for an
+/// action instead of naming a `KeyCode` directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    UseTool,
+    Interact,
+    Sleep,
+    ToggleInventory,
+    ToggleJournal,
+    ToggleBuilding,
+    OpenMagic,
+    OpenSkills,
+    OpenLevelSelect,
+    ToggleMap,
+    ManualSave,
+}
+
+impl Action {
+    pub const ALL: [Action; 15] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::UseTool,
+        Action::Interact,
+        Action::Sleep,
+        Action::ToggleInventory,
+        Action::ToggleJournal,
+        Action::ToggleBuilding,
+        Action::OpenMagic,
+        Action::OpenSkills,
+        Action::OpenLevelSelect,
+        Action::ToggleMap,
+        Action::ManualSave,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::UseTool => "Swing tool",
+            Action::Interact => "Talk / interact",
+            Action::Sleep => "Sleep",
+            Action::ToggleInventory => "Inventory",
+            Action::ToggleJournal => "Journal",
+            Action::ToggleBuilding => "Build",
+            Action::OpenMagic => "Spellbook",
+            Action::OpenSkills => "Training ledger",
+            Action::OpenLevelSelect => "Change mountain",
+            Action::ToggleMap => "Map",
+            Action::ManualSave => "Save expedition",
+        }
+    }
+
+    fn default_key(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "W",
+            Action::MoveDown => "S",
+            Action::MoveLeft => "A",
+            Action::MoveRight => "D",
+            Action::UseTool => "Space",
+            Action::Interact => "E",
+            Action::Sleep => "Z",
+            Action::ToggleInventory => "I",
+            Action::ToggleJournal => "J",
+            Action::ToggleBuilding => "B",
+            Action::OpenMagic => "M",
+            Action::OpenSkills => "K",
+            Action::OpenLevelSelect => "L",
+            Action::ToggleMap => "Tab",
+            Action::ManualSave => "F5",
+        }
+    }
+}
+
+/// Action-to-key table, stored as key names so the RON file stays
+/// readable. Missing or unknown entries fall back to the defaults
+/// rather than bricking the game.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct InputMap {
+    pub keys: HashMap<Action, String>,
+}
+
+impl InputMap {
+    pub fn key_name(&self, action: Action) -> &str {
+        self.keys
+            .get(&action)
+            .map(String::as_str)
+            .filter(|name| key_code(name).is_some())
+            .unwrap_or_else(|| action.default_key())
+    }
+
+    pub fn key(&self, action: Action) -> KeyCode {
+        key_code(self.key_name(action)).expect("default bindings always resolve")
+    }
+
+    pub fn pressed(&self, keyboard: &ButtonInput<KeyCode>, action: Action) -> bool {
+        keyboard.pressed(self.key(action))
+    }
+
+    pub fn just_pressed(&self, keyboard: &ButtonInput<KeyCode>, action: Action) -> bool {
+        keyboard.just_pressed(self.key(action))
+    }
+
+    pub fn bind(&mut self, action: Action, name: impl Into<String>) {
+        self.keys.insert(action, name.into());
+    }
+}
+
+/// Resolve a stored key name to a key code.
+fn key_code(name: &str) -> Option<KeyCode> {
+    Some(match name.to_ascii_uppercase().as_str() {
+        "SPACE" => KeyCode::Space,
+        "TAB" => KeyCode::Tab,
+        "F5" => KeyCode::F5,
+        "F6" => KeyCode::F6,
+        "F7" => KeyCode::F7,
+        "F8" => KeyCode::F8,
+        "F9" => KeyCode::F9,
+        "A" => KeyCode::KeyA,
+        "B" => KeyCode::KeyB,
+        "C" => KeyCode::KeyC,
+        "D" => KeyCode::KeyD,
+        "E" => KeyCode::KeyE,
+        "F" => KeyCode::KeyF,
+        "G" => KeyCode::KeyG,
+        "H" => KeyCode::KeyH,
+        "I" => KeyCode::KeyI,
+        "J" => KeyCode::KeyJ,
+        "K" => KeyCode::KeyK,
+        "L" => KeyCode::KeyL,
+        "M" => KeyCode::KeyM,
+        "N" => KeyCode::KeyN,
+        "O" => KeyCode::KeyO,
+        "P" => KeyCode::KeyP,
+        "Q" => KeyCode::KeyQ,
+        "R" => KeyCode::KeyR,
+        "S" => KeyCode::KeyS,
+        "T" => KeyCode::KeyT,
+        "U" => KeyCode::KeyU,
+        "V" => KeyCode::KeyV,
+        "W" => KeyCode::KeyW,
+        "X" => KeyCode::KeyX,
+        "Y" => KeyCode::KeyY,
+        "Z" => KeyCode::KeyZ,
+        "UP" => KeyCode::ArrowUp,
+        "DOWN" => KeyCode::ArrowDown,
+        "LEFT" => KeyCode::ArrowLeft,
+        "RIGHT" => KeyCode::ArrowRight,
+        _ => return None,
+    })
+}
+
+/// The display name a pressed key would be stored under, for the
+/// rebinding flow; `None` for keys we don't allow binding.
+fn key_name_of(key: KeyCode) -> Option<&'static str> {
+    Some(match key {
+        KeyCode::KeyA => "A",
+        KeyCode::KeyB => "B",
+        KeyCode::KeyC => "C",
+        KeyCode::KeyD => "D",
+        KeyCode::KeyE => "E",
+        KeyCode::KeyF => "F",
+        KeyCode::KeyG => "G",
+        KeyCode::KeyH => "H",
+        KeyCode::KeyI => "I",
+        KeyCode::KeyJ => "J",
+        KeyCode::KeyK => "K",
+        KeyCode::KeyL => "L",
+        KeyCode::KeyM => "M",
+        KeyCode::KeyN => "N",
+        KeyCode::KeyO => "O",
+        KeyCode::KeyP => "P",
+        KeyCode::KeyQ => "Q",
+        KeyCode::KeyR => "R",
+        KeyCode::KeyS => "S",
+        KeyCode::KeyT => "T",
+        KeyCode::KeyU => "U",
+        KeyCode::KeyV => "V",
+        KeyCode::KeyW => "W",
+        KeyCode::KeyX => "X",
+        KeyCode::KeyY => "Y",
+        KeyCode::KeyZ => "Z",
+        KeyCode::ArrowUp => "Up",
+        KeyCode::ArrowDown => "Down",
+        KeyCode::ArrowLeft => "Left",
+        KeyCode::ArrowRight => "Right",
+        KeyCode::Space => "Space",
+        KeyCode::Tab => "Tab",
+        KeyCode::F5 => "F5",
+        KeyCode::F6 => "F6",
+        KeyCode::F7 => "F7",
+        KeyCode::F8 => "F8",
+        KeyCode::F9 => "F9",
+        _ => return None,
+    })
+}
+
+/// Every player-tunable option, serialized as one RON table.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub effects_volume: f32,
+    pub ui_scale: f32,
+    /// Fraction of the remaining distance the camera covers per frame;
+    /// 1.0 locks it to the player.
+    pub camera_smoothing: f32,
+    pub difficulty: Difficulty,
+    pub palette: ColorPalette,
+    pub language: crate::localization::Language,
+    pub bindings: InputMap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            effects_volume: 1.0,
+            ui_scale: 1.0,
+            camera_smoothing: 0.1,
+            difficulty: Difficulty::default(),
+            palette: ColorPalette::default(),
+            language: crate::localization::Language::default(),
+            bindings: InputMap::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let Ok(contents) = fs::read_to_string(SETTINGS_PATH) else {
+            return Self::default();
+        };
+        match ron::from_str(&contents) {
+            Ok(settings) => settings,
+            Err(e) => {
+                error!("Failed to parse {SETTINGS_PATH}: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        if let Some(parent) = Path::new(SETTINGS_PATH).parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(SETTINGS_PATH, contents) {
+                    error!("Failed to write {SETTINGS_PATH}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to serialize settings: {e}"),
+        }
+    }
+}
+
+/// Push changed settings out to the things they steer — UI scale, the
+/// master volume, and the terrain palette — and persist them. Most
+/// systems just read the resource directly each frame.
+pub fn apply_settings_system(
+    settings: Res<Settings>,
+    mut ui_scale: ResMut<UiScale>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut dirty: ResMut<DirtyChunks>,
+    current_level: Res<CurrentLevel>,
+    mut strings: ResMut<crate::localization::Strings>,
+    mut applied_palette: Local<Option<ColorPalette>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    ui_scale.0 = settings.ui_scale;
+    global_volume.volume = bevy::audio::Volume::new(settings.master_volume);
+    if strings.language != settings.language {
+        *strings = crate::localization::Strings::load(settings.language);
+    }
+    if *applied_palette != Some(settings.palette) {
+        // Retint what's already on screen; fresh chunks pick the
+        // palette up as they spawn.
+        dirty
+            .chunks
+            .extend(current_level.spawned_chunks.iter().copied());
+        *applied_palette = Some(settings.palette);
+    }
+    settings.save();
+}
+
+/// When rebinding, the index into [`Action::ALL`] waiting for a key.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<usize>);
+
+/// Cycle an option with its number key; 9 walks through every action
+/// waiting for a key each. Escape backs out of either.
+pub fn settings_input_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Some(index) = rebinding.0 {
+        if keyboard.just_pressed(KeyCode::Escape) {
+            rebinding.0 = None;
+            return;
+        }
+        let Some(pressed) = keyboard.get_just_pressed().next() else {
+            return;
+        };
+        let Some(name) = key_name_of(*pressed) else {
+            return;
+        };
+        settings.bindings.bind(Action::ALL[index], name);
+        rebinding.0 = if index + 1 < Action::ALL.len() {
+            Some(index + 1)
+        } else {
+            None
+        };
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+        return;
+    }
+    let cycle_volume = |volume: f32| if volume >= 1.0 { 0.0 } else { volume + 0.25 };
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+        KeyCode::Digit0,
+    ];
+    let Some(index) = keys.iter().position(|key| keyboard.just_pressed(*key)) else {
+        return;
+    };
+    match index {
+        0 => settings.master_volume = cycle_volume(settings.master_volume),
+        1 => settings.music_volume = cycle_volume(settings.music_volume),
+        2 => settings.effects_volume = cycle_volume(settings.effects_volume),
+        3 => {
+            let scales = [0.75, 1.0, 1.25, 1.5];
+            let at = scales
+                .iter()
+                .position(|scale| (scale - settings.ui_scale).abs() < 0.01)
+                .unwrap_or(scales.len() - 1);
+            settings.ui_scale = scales[(at + 1) % scales.len()];
+        }
+        4 => {
+            let speeds = [0.05, 0.1, 0.2, 1.0];
+            let at = speeds
+                .iter()
+                .position(|speed| (speed - settings.camera_smoothing).abs() < 0.01)
+                .unwrap_or(speeds.len() - 1);
+            settings.camera_smoothing = speeds[(at + 1) % speeds.len()];
+        }
+        5 => settings.difficulty = settings.difficulty.next(),
+        6 => settings.palette = settings.palette.next(),
+        7 => {
+            // Quick toggle for the common case; 9 rebinds one by one
+            let arrows = settings.bindings.key_name(Action::MoveUp) == "Up";
+            let scheme: [(Action, &str); 4] = if arrows {
+                [
+                    (Action::MoveUp, "W"),
+                    (Action::MoveDown, "S"),
+                    (Action::MoveLeft, "A"),
+                    (Action::MoveRight, "D"),
+                ]
+            } else {
+                [
+                    (Action::MoveUp, "Up"),
+                    (Action::MoveDown, "Down"),
+                    (Action::MoveLeft, "Left"),
+                    (Action::MoveRight, "Right"),
+                ]
+            };
+            for (action, name) in scheme {
+                settings.bindings.bind(action, name);
+            }
+        }
+        8 => rebinding.0 = Some(0),
+        9 => settings.language = settings.language.next(),
+        _ => {}
+    }
+}
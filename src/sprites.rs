@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+use crate::components::*;
+use crate::settings::{Action, Settings};
+
+/// Where the art lives. Every sheet is optional: a missing file keeps
+/// the hand-tinted quads, so the game runs before any art is drawn.
+pub const TERRAIN_SHEET_PATH: &str = "assets/sprites/terrain.png";
+pub const CHARACTER_SHEET_PATH: &str = "assets/sprites/characters.png";
+pub const WILDLIFE_SHEET_PATH: &str = "assets/sprites/wildlife.png";
+
+/// Frames per animation row in the character and wildlife sheets.
+pub const FRAME_COLS: usize = 4;
+/// Pixel size of one character/wildlife frame.
+const FRAME_SIZE: u32 = 32;
+
+/// Animation rows in the character sheet.
+pub const ROW_IDLE: usize = 0;
+pub const ROW_WALK: usize = 1;
+pub const ROW_CLIMB: usize = 2;
+
+/// Atlas handles for whichever sheets exist on disk.
+#[derive(Resource)]
+pub struct SpriteSheets {
+    /// One 16px tile per [`TerrainType`], in declaration order.
+    pub terrain: Option<Handle<Image>>,
+    /// Rows of [`FRAME_COLS`] frames: idle, walk, climb.
+    pub characters: Option<Handle<Image>>,
+    pub character_layout: Handle<TextureAtlasLayout>,
+    /// One row of [`FRAME_COLS`] frames per [`WildlifeSpecies`].
+    pub wildlife: Option<Handle<Image>>,
+    pub wildlife_layout: Handle<TextureAtlasLayout>,
+}
+
+/// Load whatever sheets are present and build their grid layouts.
+pub fn load_sprite_sheets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let load_if_present = |path: &str| {
+        Path::new(path)
+            .exists()
+            .then(|| asset_server.load(path.trim_start_matches("assets/").to_string()))
+    };
+    let character_layout = layouts.add(TextureAtlasLayout::from_grid(
+        UVec2::splat(FRAME_SIZE),
+        FRAME_COLS as u32,
+        3,
+        None,
+        None,
+    ));
+    let wildlife_layout = layouts.add(TextureAtlasLayout::from_grid(
+        UVec2::splat(FRAME_SIZE),
+        FRAME_COLS as u32,
+        6,
+        None,
+        None,
+    ));
+    commands.insert_resource(SpriteSheets {
+        terrain: load_if_present(TERRAIN_SHEET_PATH),
+        characters: load_if_present(CHARACTER_SHEET_PATH),
+        character_layout,
+        wildlife: load_if_present(WILDLIFE_SHEET_PATH),
+        wildlife_layout,
+    });
+}
+
+/// Which terrain-sheet column a terrain type's tile sits in.
+pub fn terrain_atlas_column(terrain_type: TerrainType) -> usize {
+    match terrain_type {
+        TerrainType::Rock => 0,
+        TerrainType::Ice => 1,
+        TerrainType::Snow => 2,
+        TerrainType::Grass => 3,
+        TerrainType::Soil => 4,
+        TerrainType::Glacier => 5,
+        TerrainType::Lava => 6,
+        TerrainType::Coast => 7,
+        TerrainType::Cliff => 8,
+        TerrainType::Water => 9,
+        TerrainType::Shrub => 10,
+        TerrainType::Tree => 11,
+    }
+}
+
+/// How many columns the terrain sheet has.
+pub const TERRAIN_ATLAS_COLUMNS: usize = 12;
+
+fn wildlife_row(species: WildlifeSpecies) -> usize {
+    match species {
+        WildlifeSpecies::Sheep => 0,
+        WildlifeSpecies::Horse => 1,
+        WildlifeSpecies::ArcticFox => 2,
+        WildlifeSpecies::Eagle => 3,
+        WildlifeSpecies::Wolf => 4,
+        WildlifeSpecies::Bear => 5,
+    }
+}
+
+/// Frame selection state for an animated sheet-backed sprite.
+#[derive(Component)]
+pub struct CharacterAnimation {
+    pub row: usize,
+    pub frame: usize,
+    pub timer: Timer,
+}
+
+impl Default for CharacterAnimation {
+    fn default() -> Self {
+        Self {
+            row: ROW_IDLE,
+            frame: 0,
+            timer: Timer::from_seconds(0.15, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Swap freshly spawned quads for sheet-backed sprites where the art
+/// exists. The tint goes to white so the texture shows unstained.
+#[allow(clippy::type_complexity)]
+pub fn attach_sprites_system(
+    mut commands: Commands,
+    sheets: Res<SpriteSheets>,
+    mut character_query: Query<
+        (Entity, &mut Sprite),
+        (Or<(Added<Player>, Added<NPC>)>, Without<Wildlife>),
+    >,
+    mut wildlife_query: Query<(Entity, &mut Sprite, &Wildlife), Added<Wildlife>>,
+) {
+    if let Some(texture) = &sheets.characters {
+        for (entity, mut sprite) in character_query.iter_mut() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).insert((
+                texture.clone(),
+                TextureAtlas {
+                    layout: sheets.character_layout.clone(),
+                    index: 0,
+                },
+                CharacterAnimation::default(),
+            ));
+        }
+    }
+    if let Some(texture) = &sheets.wildlife {
+        for (entity, mut sprite, wildlife) in wildlife_query.iter_mut() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).insert((
+                texture.clone(),
+                TextureAtlas {
+                    layout: sheets.wildlife_layout.clone(),
+                    index: wildlife_row(wildlife.species) * FRAME_COLS,
+                },
+                CharacterAnimation {
+                    row: wildlife_row(wildlife.species),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+/// Pick the player's animation row from what the body is doing:
+/// roped in or airborne reads as climbing, walking when keys are down,
+/// idle otherwise.
+#[allow(clippy::type_complexity)]
+pub fn player_animation_row_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut player_query: Query<
+        (&mut CharacterAnimation, Option<&Climbing>, Option<&Falling>),
+        With<Player>,
+    >,
+) {
+    let Ok((mut animation, climbing, falling)) = player_query.get_single_mut() else {
+        return;
+    };
+    let bindings = &settings.bindings;
+    let moving = bindings.pressed(&keyboard, Action::MoveUp)
+        || bindings.pressed(&keyboard, Action::MoveDown)
+        || bindings.pressed(&keyboard, Action::MoveLeft)
+        || bindings.pressed(&keyboard, Action::MoveRight);
+    let row = if climbing.is_some() || falling.is_some() {
+        ROW_CLIMB
+    } else if moving {
+        ROW_WALK
+    } else {
+        ROW_IDLE
+    };
+    if animation.row != row {
+        animation.row = row;
+        animation.frame = 0;
+    }
+}
+
+/// Advance every animated sprite through its current row.
+pub fn animate_sprites_system(
+    time: Res<Time>,
+    mut query: Query<(&mut CharacterAnimation, &mut TextureAtlas)>,
+) {
+    for (mut animation, mut atlas) in query.iter_mut() {
+        if animation.timer.tick(time.delta()).just_finished() {
+            animation.frame = (animation.frame + 1) % FRAME_COLS;
+        }
+        atlas.index = animation.row * FRAME_COLS + animation.frame;
+    }
+}
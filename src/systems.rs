@@ -0,0 +1,5489 @@
+use bevy::app::AppExit;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::tasks::futures_lite::future;
+use bevy::tasks::{block_on, AsyncComputeTaskPool, Task};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::components::*;
+use crate::dialogue::{
+    create_guide_dialogue, create_hermit_dialogue, create_trader_dialogue, ActiveDialogue,
+    ConversationMemory, DialogueLibrary,
+};
+use crate::items::ItemDatabase;
+use crate::levels::{self, AvailableLevels, CurrentLevel, LevelStack, LevelStackFrame, TILE_SIZE};
+use crate::settings::Action;
+use crate::terrain::{self, DirtyChunks, TerrainChunkMesh, TerrainIndex};
+
+#[derive(Event)]
+pub struct TerrainBrokenEvent {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub terrain_type: TerrainType,
+}
+
+#[derive(Event)]
+pub struct FallStartEvent {
+    pub position: Vec2,
+}
+
+/// How loudly a feed message should read; sets its colour.
+#[derive(Clone, Copy)]
+pub enum MessageSeverity {
+    Info,
+    Warning,
+    Danger,
+}
+
+impl MessageSeverity {
+    pub fn color(&self) -> Color {
+        match self {
+            MessageSeverity::Info => Color::srgb(0.85, 0.85, 0.85),
+            MessageSeverity::Warning => Color::srgb(1.0, 0.8, 0.3),
+            MessageSeverity::Danger => Color::srgb(1.0, 0.35, 0.3),
+        }
+    }
+}
+
+/// One line for the on-screen message feed. Gameplay feedback goes
+/// here; `info!` is for the developer console.
+#[derive(Event)]
+pub struct GameMessageEvent {
+    pub text: String,
+    pub severity: MessageSeverity,
+}
+
+impl GameMessageEvent {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            severity: MessageSeverity::Info,
+        }
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            severity: MessageSeverity::Warning,
+        }
+    }
+
+    pub fn danger(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            severity: MessageSeverity::Danger,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct PlayerLandedEvent {
+    pub position: Vec2,
+    pub fall_distance: f32,
+}
+
+#[derive(Event)]
+pub struct PlayerSlippedEvent {
+    /// How far the tile's difficulty exceeded the player's effective skill.
+    pub overreach: f32,
+}
+
+#[derive(Event)]
+pub struct PartyInvitationEvent {
+    pub npc: Entity,
+    pub npc_name: String,
+}
+
+/// An NPC offering to join answers here: there has to be room on the
+/// rope team, and whether they say yes rides on the player's standing.
+pub fn party_invitation_system(
+    reputation: Res<crate::dialogue::PlayerReputation>,
+    mut invitations: EventReader<PartyInvitationEvent>,
+    mut memory: ResMut<ConversationMemory>,
+    mut party: ResMut<Party>,
+    mut warning: ResMut<WarningMessage>,
+    mut messages: EventWriter<GameMessageEvent>,
+    mut npc_query: Query<&mut NPC>,
+) {
+    for invitation in invitations.read() {
+        if party.members.contains(&invitation.npc) {
+            continue;
+        }
+        if party.members.len() >= party.max_size {
+            warning.show("Your party is full");
+            continue;
+        }
+        if !rand::thread_rng().gen_bool(reputation.join_chance()) {
+            memory.remember(&invitation.npc_name).invitation_declined = true;
+            memory.save();
+            warning.show(format!(
+                "{} doesn't trust you enough to rope up",
+                invitation.npc_name
+            ));
+            continue;
+        }
+        party.members.push(invitation.npc);
+        if let Ok(mut npc) = npc_query.get_mut(invitation.npc) {
+            npc.behavior = NpcBehaviorType::Follow;
+        }
+        messages.send(GameMessageEvent::info(format!(
+            "{} joins your party",
+            invitation.npc_name
+        )));
+    }
+}
+
+/// P sends the most recently joined companion home.
+pub fn party_dismiss_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut party: ResMut<Party>,
+    mut warning: ResMut<WarningMessage>,
+    mut npc_query: Query<&mut NPC, Without<Player>>,
+    mut morale_query: Query<&mut Morale, With<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    let Some(entity) = party.members.pop() else {
+        return;
+    };
+    let Ok(mut npc) = npc_query.get_mut(entity) else {
+        return;
+    };
+    npc.behavior = NpcBehaviorType::ReturnHome;
+    // The rope feels longer with one fewer on it
+    if let Ok(mut morale) = morale_query.get_single_mut() {
+        morale.adjust(-10.0);
+    }
+    warning.show(format!("{} heads back down", npc.name));
+}
+
+/// Spawn the camera, generate levels, and start parsing the default
+/// mountain while the main menu is up.
+pub fn setup(
+    mut commands: Commands,
+    library: Res<levels::LevelLibrary>,
+    database: Res<ItemDatabase>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut shop: ResMut<ShopInventory>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    *shop = crate::items::stock_shop(&database);
+    library.ensure_sample_levels();
+
+    // Parsing a 40k-tile level takes long enough to notice; do it on a
+    // background task and show the loading screen meanwhile.
+    current_level.name = "large_mountain_01".to_string();
+    let task = AsyncComputeTaskPool::get().spawn(async {
+        levels::load_level(Path::new("levels/large_mountain_01.ron")).or_else(|| {
+            levels::LevelLibrary::embedded_levels()
+                .into_iter()
+                .next()
+                .map(|(_, level)| level)
+        })
+    });
+    commands.insert_resource(PendingLevelLoad { task: Some(task) });
+}
+
+/// Spawn the player as the chosen archetype. The level parse started
+/// at startup keeps cooking while the choice is made.
+pub const PLAYER_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+/// The player's tint while exhausted: the same red, drained grey.
+const EXHAUSTED_COLOR: Color = Color::srgb(0.6, 0.5, 0.5);
+
+fn spawn_player(commands: &mut Commands, database: &ItemDatabase, archetype: CharacterArchetype) {
+    let mut spawned = commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: PLAYER_COLOR,
+                custom_size: Some(Vec2::new(22.0, 28.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, 2.0),
+            ..default()
+        },
+        Player,
+        Health {
+            current: archetype.max_health(),
+            max: archetype.max_health(),
+        },
+        Stamina {
+            current: 100.0,
+            max: 100.0,
+        },
+        MovementStats {
+            speed: archetype.speed(),
+            climbing_skill: archetype.climbing_skill(),
+        },
+        Velocity::default(),
+        Inventory {
+            items: archetype
+                .starting_items()
+                .iter()
+                .filter_map(|id| database.get(id))
+                .collect(),
+            ..default()
+        },
+        EquippedItems {
+            tool: database.get("ice_axe"),
+            ..default()
+        },
+        Money(50.0),
+        Hunger {
+            current: 100.0,
+            max: 100.0,
+        },
+        Thirst {
+            current: 100.0,
+            max: 100.0,
+        },
+        BodyTemperature::default(),
+        Frostbite::default(),
+        Wetness::default(),
+    ));
+    // A bundle holds at most fifteen components; the rest go on with a
+    // second insert on the same entity.
+    spawned.insert((
+        MagicUser {
+            mana: archetype.max_mana(),
+            max_mana: archetype.max_mana(),
+            known_spells: archetype.known_spells(),
+        },
+        Experience::default(),
+        Perks::default(),
+        Morale::default(),
+        Facing::default(),
+    ));
+}
+
+/// Drive the title screen: entries respond to clicks and to the
+/// number keys.
+pub fn main_menu_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    row_query: Query<(&Interaction, &crate::ui::MenuAction)>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut warning: ResMut<WarningMessage>,
+    mut exit: EventWriter<AppExit>,
+) {
+    let clicked = row_query
+        .iter()
+        .find(|(interaction, _)| matches!(interaction, Interaction::Pressed))
+        .map(|(_, action)| *action);
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+    ];
+    let actions = [
+        crate::ui::MenuAction::Continue,
+        crate::ui::MenuAction::NewExpedition,
+        crate::ui::MenuAction::LevelSelect,
+        crate::ui::MenuAction::Settings,
+        crate::ui::MenuAction::Quit,
+    ];
+    let typed = keys
+        .iter()
+        .position(|key| keyboard.just_pressed(*key))
+        .map(|index| actions[index]);
+    let Some(action) = clicked.or(typed) else {
+        return;
+    };
+    match action {
+        crate::ui::MenuAction::Continue => match crate::saves::latest_checkpoint() {
+            Some(save) => {
+                crate::saves::restore(&mut commands, &mut current_level, save);
+                next_state.set(GameState::Loading);
+            }
+            None => warning.show("No expedition to continue"),
+        },
+        crate::ui::MenuAction::NewExpedition => next_state.set(GameState::CharacterSelection),
+        crate::ui::MenuAction::LevelSelect => next_state.set(GameState::LevelSelect),
+        crate::ui::MenuAction::Settings => next_state.set(GameState::Settings),
+        crate::ui::MenuAction::Quit => {
+            exit.send(AppExit::Success);
+        }
+    }
+}
+
+/// Escape twice within a couple of seconds abandons the climb and
+/// returns to the main menu; a single press just arms the warning.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn return_to_menu_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut confirm_window: Local<f32>,
+    mut warning: ResMut<WarningMessage>,
+    player_query: Query<Entity, With<Player>>,
+    level_entity_query: Query<
+        Entity,
+        Or<(
+            With<TerrainTile>,
+            With<TerrainChunkMesh>,
+            With<NPC>,
+            With<RopeAnchor>,
+            With<Entrance>,
+            With<Structure>,
+            With<ItemPickup>,
+            With<RouteMarker>,
+            With<SecretMarker>,
+            With<Wildlife>,
+        )>,
+    >,
+    mut party: ResMut<Party>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    *confirm_window = (*confirm_window - time.delta_seconds()).max(0.0);
+    if !keyboard.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    if *confirm_window <= 0.0 {
+        *confirm_window = 2.0;
+        warning.show("Press Escape again to abandon the climb");
+        return;
+    }
+    for entity in player_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    despawn_level_entities(&mut commands, &level_entity_query);
+    party.members.clear();
+    current_level.spawned_chunks.clear();
+    next_state.set(GameState::Menu);
+}
+
+/// Pick an archetype with the number keys; the expedition sets out as
+/// soon as one is chosen.
+pub fn character_select_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    database: Res<ItemDatabase>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Enter resumes the saved expedition instead of starting fresh
+    if keyboard.just_pressed(KeyCode::Enter) {
+        if let Some(save) = crate::saves::read_slot() {
+            crate::saves::restore(&mut commands, &mut current_level, save);
+            next_state.set(GameState::Loading);
+        }
+        return;
+    }
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+    ];
+    let Some(index) = keys.iter().position(|key| keyboard.just_pressed(*key)) else {
+        return;
+    };
+    let archetype = CharacterArchetype::ALL[index];
+    commands.insert_resource(SelectedCharacter { archetype });
+    spawn_player(&mut commands, &database, archetype);
+    next_state.set(GameState::Loading);
+}
+
+/// Background parse of the level being loaded, if one is in flight.
+#[derive(Resource, Default)]
+pub struct PendingLevelLoad {
+    pub task: Option<Task<Option<levels::LevelDefinition>>>,
+}
+
+/// 0..1 fraction shown by the loading bar.
+#[derive(Resource, Default)]
+pub struct LevelLoadProgress(pub f32);
+
+/// Drive the loading state: poll the parse task, then wait for the
+/// chunk manager to spawn the first view's worth of terrain (it
+/// spreads the work over several frames), then start the climb.
+pub fn level_loading_system(
+    mut pending: ResMut<PendingLevelLoad>,
+    mut progress: ResMut<LevelLoadProgress>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Some(task) = pending.task.as_mut() {
+        let Some(result) = block_on(future::poll_once(task)) else {
+            progress.0 = 0.2;
+            return;
+        };
+        pending.task = None;
+        match result {
+            Some(level) => {
+                current_level.definition = Some(level);
+                current_level.spawned_chunks.clear();
+                current_level.needs_spawn = true;
+            }
+            None => {
+                error!("Level failed to load; returning to the climb");
+                next_state.set(GameState::Climbing);
+                return;
+            }
+        }
+    }
+    let view_chunks = (2 * terrain::VIEW_DISTANCE_CHUNKS + 1).pow(2) as usize;
+    let spawned = current_level.spawned_chunks.len().min(view_chunks);
+    progress.0 = 0.2 + 0.8 * spawned as f32 / view_chunks as f32;
+    if spawned == view_chunks {
+        next_state.set(GameState::Climbing);
+    }
+}
+
+/// True if the tile under `position` can't be walked or climbed onto.
+fn position_blocked(
+    position: Vec2,
+    current_level: &CurrentLevel,
+    terrain_query: &Query<&TerrainTile>,
+) -> bool {
+    let Some(level) = &current_level.definition else {
+        return false;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    terrain_query
+        .iter()
+        .find(|tile| tile.grid_x == grid_x && tile.grid_y == grid_y)
+        .is_some_and(|tile| tile.solid || !tile.climbable)
+}
+
+/// No amount of stamina gets you up terrain this far beyond your skill.
+const IMPOSSIBLE_DIFFICULTY_MARGIN: f32 = 4.0;
+
+/// The tuning knobs of the climbing model, gathered into a resource so
+/// the weight formulas live in one place and tests can pin down the
+/// break-points.
+#[derive(Resource)]
+pub struct ClimbingRules {
+    /// Carried kilograms that climb for free before the load counts.
+    pub free_carry: f32,
+    /// Difficulty added per kilogram carried above [`free_carry`].
+    ///
+    /// [`free_carry`]: Self::free_carry
+    pub difficulty_per_kg: f32,
+    /// Fall distance that never hurts, in world units.
+    pub safe_fall_distance: f32,
+    /// Damage per tile fallen beyond the safe distance.
+    pub fall_damage_per_tile: f32,
+    /// Extra fall damage fraction per kilogram above [`free_carry`].
+    ///
+    /// [`free_carry`]: Self::free_carry
+    pub fall_damage_per_kg: f32,
+}
+
+impl Default for ClimbingRules {
+    fn default() -> Self {
+        Self {
+            free_carry: 10.0,
+            difficulty_per_kg: 0.15,
+            safe_fall_distance: SAFE_FALL_DISTANCE,
+            fall_damage_per_tile: FALL_DAMAGE_PER_TILE,
+            fall_damage_per_kg: 0.02,
+        }
+    }
+}
+
+impl ClimbingRules {
+    /// What a face of the given difficulty actually demands of someone
+    /// hauling this much weight.
+    pub fn effective_difficulty(&self, base: f32, carried_weight: f32) -> f32 {
+        base + (carried_weight - self.free_carry).max(0.0) * self.difficulty_per_kg
+    }
+
+    /// Damage for landing after a fall of `fall_distance`, heavier
+    /// loads hitting harder.
+    pub fn fall_damage(&self, fall_distance: f32, carried_weight: f32) -> f32 {
+        if fall_distance <= self.safe_fall_distance {
+            return 0.0;
+        }
+        let tiles_fallen = (fall_distance - self.safe_fall_distance) / TILE_SIZE;
+        let weight_factor =
+            1.0 + (carried_weight - self.free_carry).max(0.0) * self.fall_damage_per_kg;
+        tiles_fallen * self.fall_damage_per_tile * weight_factor
+    }
+}
+
+/// Difficulty of the climbable tile under `position`, or 0.0 for open ground.
+fn climb_difficulty_at(
+    position: Vec2,
+    current_level: &CurrentLevel,
+    climbable_query: &Query<(&TerrainTile, &Climbable)>,
+) -> f32 {
+    let Some(level) = &current_level.definition else {
+        return 0.0;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    climbable_query
+        .iter()
+        .find(|(tile, _)| tile.grid_x == grid_x && tile.grid_y == grid_y)
+        .map(|(_, climbable)| climbable.difficulty)
+        .unwrap_or(0.0)
+}
+
+/// Gear ids from `required_gear` that the player is missing for the tile
+/// under `position`, if any.
+fn missing_gear_at(
+    position: Vec2,
+    current_level: &CurrentLevel,
+    climbable_query: &Query<(&TerrainTile, &Climbable)>,
+    equipped: &EquippedItems,
+) -> Vec<String> {
+    let Some(level) = &current_level.definition else {
+        return Vec::new();
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    climbable_query
+        .iter()
+        .find(|(tile, _)| tile.grid_x == grid_x && tile.grid_y == grid_y)
+        .map(|(_, climbable)| {
+            climbable
+                .required_gear
+                .iter()
+                .filter(|gear| !equipped.has_gear(gear))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Movement on the bound keys (WASD out of the box). Climbing upward
+/// costs stamina, and solid or unclimbable tiles block movement
+/// (sliding along the free axis). Tiles that demand gear the player
+/// isn't wearing also refuse entry.
+pub fn player_movement_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    current_level: Res<CurrentLevel>,
+    rules: Res<ClimbingRules>,
+    spells: Res<ActiveSpells>,
+    selected: Res<SelectedCharacter>,
+    terrain_query: Query<&TerrainTile>,
+    climbable_query: Query<(&TerrainTile, &Climbable)>,
+    mut warning: ResMut<WarningMessage>,
+    mut slip_events: EventWriter<PlayerSlippedEvent>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &MovementStats,
+            &mut Stamina,
+            &mut Experience,
+            &Inventory,
+            &EquippedItems,
+            &Frostbite,
+            &Wetness,
+            &mut Facing,
+            Option<&Climbing>,
+            Option<&Exhausted>,
+        ),
+        With<Player>,
+    >,
+) {
+    let Ok((
+        mut transform,
+        stats,
+        mut stamina,
+        mut experience,
+        inventory,
+        equipped,
+        frostbite,
+        wetness,
+        mut facing,
+        climbing,
+        exhausted,
+    )) =
+        query.get_single_mut()
+    else {
+        return;
+    };
+
+    let bindings = &settings.bindings;
+    let mut direction = Vec2::ZERO;
+    if bindings.pressed(&keyboard, Action::MoveUp) {
+        direction.y += 1.0;
+    }
+    if bindings.pressed(&keyboard, Action::MoveDown) {
+        direction.y -= 1.0;
+    }
+    if bindings.pressed(&keyboard, Action::MoveLeft) {
+        direction.x -= 1.0;
+    }
+    if bindings.pressed(&keyboard, Action::MoveRight) {
+        direction.x += 1.0;
+    }
+
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let direction = direction.normalize();
+    facing.direction = direction;
+    let current = transform.translation.truncate();
+
+    // A pack past three-quarters full weighs on every step: the pace
+    // drops and the extra load burns stamina even on the flat.
+    let load_ratio = inventory.current_weight() / inventory.weight_limit;
+    let overload = ((load_ratio - ENCUMBRANCE_THRESHOLD)
+        / (1.0 - ENCUMBRANCE_THRESHOLD))
+        .clamp(0.0, 1.0);
+    let encumbrance_modifier = 1.0 - overload * 0.4;
+    if overload > 0.0 {
+        stamina.current = (stamina.current - overload * 4.0 * time.delta_seconds()).max(0.0);
+    }
+
+    // Moving upward is climbing: stamina drain scales with how far the
+    // tile's difficulty exceeds the player's skill plus gear bonus.
+    let effective_skill =
+        stats.climbing_skill + equipped.climbing_bonus() + spells.climbing_bonus();
+    if direction.y > 0.0 {
+        if stamina.current <= 0.0 || exhausted.is_some() {
+            return;
+        }
+        // The route gets harder the more the pack weighs
+        let difficulty = rules.effective_difficulty(
+            climb_difficulty_at(
+                current + Vec2::new(0.0, TILE_SIZE),
+                &current_level,
+                &climbable_query,
+            ),
+            inventory.current_weight(),
+        );
+        if difficulty > effective_skill + IMPOSSIBLE_DIFFICULTY_MARGIN {
+            warning.show("This face is too difficult for your skill and gear");
+            return;
+        }
+        let drain = (8.0 + (difficulty - effective_skill).max(0.0) * 2.5)
+            * selected.archetype.climb_drain_multiplier();
+        stamina.current = (stamina.current - drain * time.delta_seconds()).max(0.0);
+
+        // Hard ground teaches: XP accrues with the grade of the climb
+        if experience.award(difficulty * 0.6 * time.delta_seconds()) {
+            warning.show(format!(
+                "You've grown as a climber (level {}) — skill point earned",
+                experience.level
+            ));
+        }
+
+        // Climbing above your skill risks a slip: a short drop and a scrape
+        let overreach = (difficulty - effective_skill).max(0.0);
+        if overreach > 0.0
+            && rand::thread_rng().gen_bool(
+                (overreach as f64 * 0.05 * time.delta_seconds() as f64).min(1.0),
+            )
+        {
+            transform.translation.y -= TILE_SIZE;
+            warning.show("You slip!");
+            slip_events.send(PlayerSlippedEvent { overreach });
+            return;
+        }
+    }
+
+    // Slower going on snow, ice, and rough ground
+    let terrain_modifier = current_level
+        .definition
+        .as_ref()
+        .and_then(|level| {
+            let (grid_x, grid_y) = levels::world_to_grid(current, level.width, level.height);
+            terrain_query
+                .iter()
+                .find(|tile| tile.grid_x == grid_x && tile.grid_y == grid_y)
+                .map(|tile| tile.terrain_type.movement_modifier())
+        })
+        .unwrap_or(1.0);
+
+    // Frostbitten limbs and waterlogged clothes both slow the pace,
+    // and an exhausted climber can only shuffle
+    let exhaustion_modifier = if exhausted.is_some() { 0.4 } else { 1.0 };
+    let condition_modifier = (1.0 - frostbite.severity * 0.4)
+        * (1.0 - wetness.level * 0.15)
+        * exhaustion_modifier;
+    // Leaning into a gale costs ground; a strong tailwind gives a bit
+    let headwind = (-direction.dot(weather.wind_direction)).max(0.0);
+    let wind_modifier =
+        (1.0 - headwind * (weather.wind_speed - 6.0).max(0.0) * 0.03).clamp(0.4, 1.1);
+    let movement = direction
+        * stats.speed
+        * terrain_modifier
+        * condition_modifier
+        * encumbrance_modifier
+        * wind_modifier
+        * time.delta_seconds();
+
+    let anchored = climbing.is_some();
+    let mut gear_gate = |target: Vec2| -> bool {
+        let missing = missing_gear_at(target, &current_level, &climbable_query, equipped);
+        if !missing.is_empty() {
+            warning.show(format!("You need: {}", missing.join(", ")));
+            return true;
+        }
+        // Deep water can only be forded along a rope line
+        if let Some(level) = &current_level.definition {
+            let (grid_x, grid_y) = levels::world_to_grid(target, level.width, level.height);
+            let deep = climbable_query.iter().any(|(tile, climbable)| {
+                tile.grid_x == grid_x
+                    && tile.grid_y == grid_y
+                    && tile.terrain_type == TerrainType::Water
+                    && climbable.difficulty > levels::DEEP_WATER_DIFFICULTY
+            });
+            if deep && !anchored {
+                warning.show("The water is too deep — you need a rope line");
+                return true;
+            }
+        }
+        false
+    };
+
+    // Check each axis separately so the player slides along walls
+    // instead of stopping dead on a diagonal input.
+    let x_target = current + Vec2::new(movement.x, 0.0);
+    if !position_blocked(x_target, &current_level, &terrain_query) && !gear_gate(x_target) {
+        transform.translation.x = x_target.x;
+    }
+    let y_target = transform.translation.truncate() + Vec2::new(0.0, movement.y);
+    if !position_blocked(y_target, &current_level, &terrain_query) && !gear_gate(y_target) {
+        transform.translation.y = y_target.y;
+    }
+}
+
+const GRAVITY: f32 = 600.0;
+/// Climbable tiles at or above this difficulty are vertical slope and
+/// give no support on their own.
+const STEEP_DIFFICULTY: f32 = 7.0;
+const SAFE_FALL_DISTANCE: f32 = TILE_SIZE * 2.0;
+const FALL_DAMAGE_PER_TILE: f32 = 8.0;
+
+fn tile_supports(tile: &TerrainTile, climbable: Option<&Climbable>) -> bool {
+    tile.climbable && climbable.map_or(true, |c| c.difficulty < STEEP_DIFFICULTY)
+}
+
+/// Drop the player when they leave supporting terrain, accumulate
+/// velocity while airborne, and land them on the first supportive tile.
+pub fn gravity_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    terrain_query: Query<(&TerrainTile, Option<&Climbable>)>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            Option<&Falling>,
+            Option<&Climbing>,
+        ),
+        With<Player>,
+    >,
+    mut fall_events: EventWriter<FallStartEvent>,
+    mut land_events: EventWriter<PlayerLandedEvent>,
+    mut messages: EventWriter<GameMessageEvent>,
+) {
+    let Ok((entity, mut transform, mut velocity, falling, climbing)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+
+    let support_at = |position: Vec2| -> Option<bool> {
+        let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+        terrain_query
+            .iter()
+            .find(|(tile, _)| tile.grid_x == grid_x && tile.grid_y == grid_y)
+            .map(|(tile, climbable)| tile_supports(tile, climbable))
+    };
+
+    let position = transform.translation.truncate();
+
+    if let Some(falling) = falling {
+        velocity.y -= GRAVITY * time.delta_seconds();
+        transform.translation.y += velocity.y * time.delta_seconds();
+        let new_position = transform.translation.truncate();
+        if support_at(new_position) == Some(true) {
+            let fall_distance = falling.start_y - new_position.y;
+            commands.entity(entity).remove::<Falling>().insert(Grounded);
+            velocity.y = 0.0;
+            land_events.send(PlayerLandedEvent {
+                position: new_position,
+                fall_distance,
+            });
+        }
+        return;
+    }
+
+    // Standing on steep terrain counts as supported if the tile directly
+    // below can be stood on — the player is bracing against it.
+    // Hanging from a taut-enough rope counts as support: the player can
+    // rappel down steep faces instead of dropping.
+    let anchored = climbing
+        .is_some_and(|c| position.distance(c.anchor_point) <= c.rope_length);
+    let here = support_at(position);
+    let below = support_at(position - Vec2::new(0.0, TILE_SIZE));
+    if here == Some(false) && below != Some(true) && !anchored {
+        commands
+            .entity(entity)
+            .remove::<Grounded>()
+            .insert(Falling { start_y: position.y });
+        fall_events.send(FallStartEvent { position });
+        messages.send(GameMessageEvent::warning("Lost your footing!"));
+    } else {
+        commands.entity(entity).insert(Grounded);
+    }
+}
+
+const ROPE_LENGTH: f32 = TILE_SIZE * 5.0;
+
+/// Press R on rock to hammer in an anchor (consumes a rope), or press R
+/// while anchored to untie.
+pub fn anchor_placement_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    current_level: Res<CurrentLevel>,
+    terrain_query: Query<&TerrainTile>,
+    mut player_query: Query<
+        (Entity, &Transform, &mut Inventory, Option<&Climbing>),
+        With<Player>,
+    >,
+    anchor_query: Query<Entity, With<RopeAnchor>>,
+    mut messages: EventWriter<GameMessageEvent>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    let Ok((entity, transform, mut inventory, climbing)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    if climbing.is_some() {
+        commands.entity(entity).remove::<Climbing>();
+        for anchor in anchor_query.iter() {
+            commands.entity(anchor).despawn();
+        }
+        messages.send(GameMessageEvent::info("Untied from the anchor"));
+        return;
+    }
+
+    let position = transform.translation.truncate();
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    let on_rock = terrain_query.iter().any(|tile| {
+        tile.grid_x == grid_x && tile.grid_y == grid_y && tile.terrain_type == TerrainType::Rock
+    });
+    if !on_rock {
+        messages.send(GameMessageEvent::warning("You need solid rock to place an anchor"));
+        return;
+    }
+
+    let Some(rope_index) = inventory
+        .items
+        .iter()
+        .position(|item| item.name.eq_ignore_ascii_case("rope"))
+    else {
+        messages.send(GameMessageEvent::warning("No rope in your pack"));
+        return;
+    };
+    inventory.items.remove(rope_index);
+
+    commands.entity(entity).insert(Climbing {
+        anchor_point: position,
+        rope_length: ROPE_LENGTH,
+    });
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.75, 0.55, 0.2),
+                custom_size: Some(Vec2::splat(8.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.5),
+            ..default()
+        },
+        RopeAnchor,
+    ));
+    messages.send(GameMessageEvent::info("Anchor placed — roped in"));
+}
+
+/// Keep an anchored player within rope length; a fall past the rope's
+/// end is arrested instead of continuing.
+pub fn rope_tether_system(
+    mut commands: Commands,
+    mut player_query: Query<
+        (Entity, &mut Transform, &mut Velocity, &Climbing, Option<&Falling>),
+        With<Player>,
+    >,
+    mut messages: EventWriter<GameMessageEvent>,
+) {
+    let Ok((entity, mut transform, mut velocity, climbing, falling)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let offset = position - climbing.anchor_point;
+    if offset.length() <= climbing.rope_length {
+        return;
+    }
+    let clamped = climbing.anchor_point + offset.normalize() * climbing.rope_length;
+    transform.translation.x = clamped.x;
+    transform.translation.y = clamped.y;
+    if falling.is_some() {
+        commands.entity(entity).remove::<Falling>();
+        velocity.y = 0.0;
+        messages.send(GameMessageEvent::warning("The rope catches you!"));
+    }
+}
+
+/// Slips scrape the player up a little on top of the lost ground.
+pub fn slip_damage_system(
+    time: Res<Time>,
+    mut slip_events: EventReader<PlayerSlippedEvent>,
+    mut player_query: Query<&mut Health, With<Player>>,
+    mut last_damage: ResMut<LastDamage>,
+) {
+    let Ok(mut health) = player_query.get_single_mut() else {
+        return;
+    };
+    for event in slip_events.read() {
+        health.current -= 2.0 + event.overreach;
+        last_damage.note("a bad slip", time.elapsed_seconds_f64());
+    }
+}
+
+/// Landing from higher than the rules' safe distance hurts, and a
+/// heavy pack makes the landing worse.
+pub fn fall_damage_system(
+    time: Res<Time>,
+    rules: Res<ClimbingRules>,
+    settings: Res<crate::settings::Settings>,
+    mut land_events: EventReader<PlayerLandedEvent>,
+    mut player_query: Query<(&mut Health, &mut Morale, &Inventory), With<Player>>,
+    mut last_damage: ResMut<LastDamage>,
+    mut messages: EventWriter<GameMessageEvent>,
+) {
+    let Ok((mut health, mut morale, inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    for event in land_events.read() {
+        let damage = rules.fall_damage(event.fall_distance, inventory.current_weight())
+            * settings.difficulty.damage_multiplier();
+        if damage > 0.0 {
+            health.current -= damage;
+            last_damage.note("a hard fall", time.elapsed_seconds_f64());
+            // A bad fall shakes the nerve as well as the bones
+            morale.adjust(-damage * 0.3);
+            messages.send(GameMessageEvent::danger(format!(
+                "Hard landing! Took {damage:.0} damage"
+            )));
+        }
+    }
+}
+
+/// Remove every entity belonging to the active level so another one
+/// can spawn in its place.
+#[allow(clippy::type_complexity)]
+pub fn despawn_level_entities(
+    commands: &mut Commands,
+    level_entity_query: &Query<
+        Entity,
+        Or<(
+            With<TerrainTile>,
+            With<TerrainChunkMesh>,
+            With<NPC>,
+            With<RopeAnchor>,
+            With<Entrance>,
+            With<Structure>,
+            With<ItemPickup>,
+            With<RouteMarker>,
+            With<SecretMarker>,
+            With<Wildlife>,
+        )>,
+    >,
+) {
+    for entity in level_entity_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Make sure the current level's cave and crevasse mouths exist.
+pub fn spawn_entrances_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    entrance_query: Query<&Transform, With<Entrance>>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    if entrance_query.iter().count() == level.entrances.len() {
+        return;
+    }
+    let existing: Vec<(i32, i32)> = entrance_query
+        .iter()
+        .map(|transform| {
+            levels::world_to_grid(transform.translation.truncate(), level.width, level.height)
+        })
+        .collect();
+    for entrance in &level.entrances {
+        if existing.contains(&entrance.position) {
+            continue;
+        }
+        let (x, y) = entrance.position;
+        let position = levels::calculate_tile_position(x, y, level.width, level.height);
+        let color = match entrance.kind {
+            EntranceKind::Exit => Color::srgb(0.6, 0.6, 0.7),
+            _ => Color::srgb(0.08, 0.08, 0.1),
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(TILE_SIZE * 0.7)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(position.x, position.y, 1.2),
+                ..default()
+            },
+            Entrance {
+                kind: entrance.kind,
+            },
+        ));
+    }
+}
+
+/// Make sure the current level's authored item spawns are lying out.
+pub fn spawn_item_pickups_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    database: Res<ItemDatabase>,
+    pickup_query: Query<&Transform, With<ItemPickup>>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    if pickup_query.iter().count() >= level.items.len() {
+        return;
+    }
+    let existing: Vec<Vec2> = pickup_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    for spawn in &level.items {
+        let position = Vec2::new(spawn.position.0, spawn.position.1);
+        if existing
+            .iter()
+            .any(|taken| taken.distance(position) < TILE_SIZE * 0.5)
+        {
+            continue;
+        }
+        let Some(item) = database.get(&spawn.item_id) else {
+            warn!("Level {} spawns unknown item {:?}", level.name, spawn.item_id);
+            continue;
+        };
+        spawn_item_pickup(&mut commands, item, position);
+    }
+}
+
+/// Drop an item into the world as a pickup with its prompt attached.
+pub fn spawn_item_pickup(commands: &mut Commands, item: Item, position: Vec2) {
+    let prompt = format!("Press F to pick up {}", item.name);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.85, 0.8, 0.3),
+                custom_size: Some(Vec2::splat(TILE_SIZE * 0.4)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.0),
+            ..default()
+        },
+        ItemPickup { item },
+        Interactable { prompt },
+    ));
+}
+
+/// Pick up the nearest item with F, pack space and back permitting.
+pub fn item_pickup_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    current_level: Res<CurrentLevel>,
+    mut runes: ResMut<RuneCollection>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Inventory, &mut MagicUser), With<Player>>,
+    pickup_query: Query<(Entity, &Transform, &ItemPickup), Without<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok((transform, mut inventory, mut caster)) = player_query.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let nearest = pickup_query
+        .iter()
+        .map(|(entity, pickup_transform, pickup)| {
+            let distance = position.distance(pickup_transform.translation.truncate());
+            (entity, pickup, distance)
+        })
+        .filter(|(_, _, distance)| *distance < TILE_SIZE * 1.2)
+        .min_by(|a, b| a.2.total_cmp(&b.2));
+    let Some((entity, pickup, _)) = nearest else {
+        return;
+    };
+    if inventory.items.len() >= inventory.capacity {
+        warning.show("Your pack has no room left");
+        return;
+    }
+    if inventory.current_weight() + pickup.item.properties.weight > inventory.weight_limit {
+        warning.show(format!("The {} is too heavy to carry", pickup.item.name));
+        return;
+    }
+    warning.show(format!("Picked up {}", pickup.item.name));
+    inventory.items.push(pickup.item.clone());
+    commands.entity(entity).despawn();
+    if pickup.item.name == "Rune Stone" {
+        read_rune_stone(&current_level, &mut runes, &mut caster, &mut warning);
+    }
+}
+
+/// Reading a rune stone grants the next undeciphered rune of the level
+/// it was found on: a new spell or a deeper well of mana.
+fn read_rune_stone(
+    current_level: &CurrentLevel,
+    runes: &mut RuneCollection,
+    caster: &mut MagicUser,
+    warning: &mut WarningMessage,
+) {
+    let level_name = current_level
+        .definition
+        .as_ref()
+        .map(|level| level.name.as_str())
+        .unwrap_or_default();
+    let catalog = rune_catalog();
+    let Some(rune) = catalog
+        .iter()
+        .find(|rune| rune.level == level_name && !runes.has(rune.id))
+    else {
+        warning.show("The stone's markings mean nothing to you");
+        return;
+    };
+    runes.record(rune.id);
+    match &rune.reward {
+        RuneReward::Spell(spell) => {
+            warning.show(format!("The rune {} teaches you {}", rune.name, spell.name));
+            caster.known_spells.push(spell.clone());
+        }
+        RuneReward::MaxMana(bonus) => {
+            caster.max_mana += bonus;
+            warning.show(format!("The rune {} deepens your well of mana", rune.name));
+        }
+    }
+}
+
+/// Surface the prompt of whatever interactable the player is standing
+/// by, without talking over a live warning.
+pub fn interactable_prompt_system(
+    mut warning: ResMut<WarningMessage>,
+    player_query: Query<&Transform, With<Player>>,
+    interactable_query: Query<(&Transform, &Interactable), Without<Player>>,
+) {
+    if warning.remaining > 0.0 {
+        return;
+    }
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let nearest = interactable_query
+        .iter()
+        .map(|(interactable_transform, interactable)| {
+            let distance = position.distance(interactable_transform.translation.truncate());
+            (interactable, distance)
+        })
+        .filter(|(_, distance)| *distance < TILE_SIZE * 1.2)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    if let Some((interactable, _)) = nearest {
+        warning.show(interactable.prompt.clone());
+    }
+}
+
+/// Press F next to a cave mouth to climb in, or next to an interior
+/// exit to climb back out to where you left the overworld.
+#[allow(clippy::too_many_arguments)]
+pub fn enter_area_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut level_stack: ResMut<LevelStack>,
+    player_query: Query<&Transform, With<Player>>,
+    entrance_query: Query<(&Transform, &Entrance), Without<Player>>,
+    level_entity_query: Query<
+        Entity,
+        Or<(
+            With<TerrainTile>,
+            With<TerrainChunkMesh>,
+            With<NPC>,
+            With<RopeAnchor>,
+            With<Entrance>,
+            With<Structure>,
+            With<ItemPickup>,
+            With<RouteMarker>,
+            With<SecretMarker>,
+            With<Wildlife>,
+        )>,
+    >,
+    mut messages: EventWriter<GameMessageEvent>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyF) {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let Some((_, entrance)) = entrance_query
+        .iter()
+        .find(|(transform, _)| player_pos.distance(transform.translation.truncate()) < TILE_SIZE)
+    else {
+        return;
+    };
+
+    match entrance.kind {
+        EntranceKind::Exit => {
+            let Some(frame) = level_stack.frames.pop() else {
+                return;
+            };
+            despawn_level_entities(&mut commands, &level_entity_query);
+            current_level.name = frame.name;
+            current_level.definition = Some(frame.definition);
+            current_level.spawned_chunks.clear();
+            current_level.return_position = Some(frame.return_position);
+            current_level.needs_spawn = true;
+            messages.send(GameMessageEvent::info("You climb back out into the daylight"));
+        }
+        kind => {
+            let Some(definition) = current_level.definition.take() else {
+                return;
+            };
+            level_stack.frames.push(LevelStackFrame {
+                name: current_level.name.clone(),
+                definition,
+                return_position: player_pos,
+            });
+            despawn_level_entities(&mut commands, &level_entity_query);
+            let seed = level_stack.frames.last().map(|f| f.definition.seed).unwrap_or(0);
+            let interior = levels::generate_interior(
+                kind,
+                seed.wrapping_add(player_pos.x as u64 ^ (player_pos.y as u64) << 16),
+            );
+            current_level.name = interior.name.clone();
+            current_level.definition = Some(interior);
+            current_level.spawned_chunks.clear();
+            current_level.return_position = None;
+            current_level.needs_spawn = true;
+            messages.send(GameMessageEvent::info("You squeeze into the dark..."));
+        }
+    }
+}
+
+/// How much stability standing on fragile ground costs per second.
+const DESTABILIZE_RATE: f32 = 0.12;
+/// Below this the tile visibly cracks and the ground trembles.
+const CRACK_THRESHOLD: f32 = 0.4;
+
+/// Crack sprite drawn over a tile that's about to give way.
+#[derive(Component)]
+pub struct CrackOverlay {
+    pub tile: Entity,
+}
+
+/// Standing on low-stability ground (crevasse lips, lava crust) wears
+/// it down: first cracks and trembling, then the tile collapses into
+/// the hazard underneath.
+#[allow(clippy::too_many_arguments)]
+pub fn tile_stability_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    mut dirty: ResMut<DirtyChunks>,
+    mut warning: ResMut<WarningMessage>,
+    player_query: Query<&Transform, With<Player>>,
+    mut tile_query: Query<&mut TerrainTile>,
+    crack_query: Query<(Entity, &CrackOverlay)>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    mut rng_seed: Local<u32>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let position = player_transform.translation.truncate();
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    if let Some(entity) = index.get(grid_x, grid_y) {
+        if let Ok(mut tile) = tile_query.get_mut(entity) {
+            // Only ground that spawned below full stability wears down
+            if tile.stability < 1.0 && tile.stability > 0.0 {
+                let before = tile.stability;
+                tile.stability -= DESTABILIZE_RATE * time.delta_seconds();
+                if before >= CRACK_THRESHOLD && tile.stability < CRACK_THRESHOLD {
+                    warning.show("The ground cracks beneath you!");
+                    let tile_pos =
+                        levels::calculate_tile_position(grid_x, grid_y, level.width, level.height);
+                    commands.spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                color: Color::srgba(0.05, 0.05, 0.05, 0.8),
+                                custom_size: Some(Vec2::new(TILE_SIZE * 0.8, 3.0)),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(tile_pos.x, tile_pos.y, 0.5)
+                                .with_rotation(Quat::from_rotation_z(0.6)),
+                            ..default()
+                        },
+                        CrackOverlay { tile: entity },
+                    ));
+                }
+                // Tremble harder as collapse nears
+                if tile.stability < CRACK_THRESHOLD {
+                    if let Ok(mut camera_transform) = camera_query.get_single_mut() {
+                        *rng_seed = rng_seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                        let jitter = (*rng_seed >> 16) as f32 / 65535.0 - 0.5;
+                        let strength = (CRACK_THRESHOLD - tile.stability) * 8.0;
+                        camera_transform.translation.x += jitter * strength;
+                        camera_transform.translation.y -= jitter * strength * 0.6;
+                    }
+                }
+                if tile.stability <= 0.0 {
+                    collapse_tile(&mut tile);
+                    warning.show("The ground gives way!");
+                    dirty.chunks.insert(terrain::chunk_of(grid_x, grid_y));
+                }
+            }
+        }
+    }
+    // Drop crack overlays whose tile collapsed, recovered, or despawned
+    for (entity, overlay) in crack_query.iter() {
+        let stale = tile_query
+            .get(overlay.tile)
+            .map(|tile| tile.stability >= CRACK_THRESHOLD || tile.stability <= 0.0)
+            .unwrap_or(true);
+        if stale {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Turn a collapsed tile into whatever lies beneath it.
+fn collapse_tile(tile: &mut TerrainTile) {
+    tile.terrain_type = match tile.terrain_type {
+        // Frozen surfaces drop into meltwater
+        TerrainType::Ice | TerrainType::Snow | TerrainType::Glacier => TerrainType::Water,
+        // Lava crust was only ever a skin over the flow
+        other => other,
+    };
+    tile.stability = 0.0;
+    tile.climbable = true;
+    tile.solid = false;
+}
+
+/// How stirred up the mountainside is; breaking terrain and storms
+/// raise it, and it settles back down over time.
+#[derive(Resource, Default)]
+pub struct RockfallAgitation(pub f32);
+
+/// A stone about to arrive: the overhead indicator that gives the
+/// player a moment to dodge.
+#[derive(Component)]
+pub struct RockfallWarning {
+    pub timer: Timer,
+    pub spawn_point: Vec2,
+}
+
+#[derive(Component)]
+pub struct FallingRock {
+    pub velocity: f32,
+}
+
+/// Base rockfalls per second near high rock and cliff terrain.
+const ROCKFALL_BASE_RATE: f32 = 0.04;
+const ROCKFALL_DAMAGE: f32 = 18.0;
+
+/// Periodically shake stones loose from rock and cliff tiles above the
+/// player, more often in storms and right after hammering the terrain.
+#[allow(clippy::too_many_arguments)]
+pub fn rockfall_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    spells: Res<ActiveSpells>,
+    mut agitation: ResMut<RockfallAgitation>,
+    mut broken_events: EventReader<TerrainBrokenEvent>,
+    current_level: Res<CurrentLevel>,
+    player_query: Query<&Transform, With<Player>>,
+    tile_query: Query<&TerrainTile>,
+) {
+    for _ in broken_events.read() {
+        agitation.0 = (agitation.0 + 1.0).min(4.0);
+    }
+    agitation.0 = (agitation.0 - time.delta_seconds() * 0.1).max(0.0);
+
+    // A stonebinding holds every loose rock where it sits
+    if spells.rock_held() {
+        return;
+    }
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let weather_factor = match weather.current_weather {
+        Weather::Storm => 3.0,
+        Weather::Rain | Weather::Snow => 1.5,
+        _ => 1.0,
+    };
+    let rate = ROCKFALL_BASE_RATE * weather_factor * (1.0 + agitation.0);
+    let mut rng = rand::thread_rng();
+    if !rng.gen_bool((rate * time.delta_seconds()).clamp(0.0, 1.0) as f64) {
+        return;
+    }
+    let player_pos = player_transform.translation.truncate();
+    let (player_x, player_y) = levels::world_to_grid(player_pos, level.width, level.height);
+    // A loose stone needs exposed rock above the player's elevation
+    let sources: Vec<&TerrainTile> = tile_query
+        .iter()
+        .filter(|tile| {
+            matches!(tile.terrain_type, TerrainType::Rock | TerrainType::Cliff)
+                && tile.grid_y > player_y + 3
+                && (tile.grid_x - player_x).abs() < 10
+        })
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+    let source = sources[rng.gen_range(0..sources.len())];
+    let spawn_point = levels::calculate_tile_position(
+        source.grid_x,
+        source.grid_y,
+        level.width,
+        level.height,
+    )
+    .truncate();
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(1.0, 0.3, 0.2),
+                custom_size: Some(Vec2::new(10.0, 10.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(spawn_point.x, player_pos.y + 140.0, 3.0),
+            ..default()
+        },
+        RockfallWarning {
+            timer: Timer::from_seconds(0.8, TimerMode::Once),
+            spawn_point,
+        },
+    ));
+}
+
+/// Turn expired warnings into falling rocks, move the rocks, and apply
+/// impact damage to whatever they land on.
+#[allow(clippy::too_many_arguments)]
+pub fn falling_rock_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<crate::settings::Settings>,
+    current_level: Res<CurrentLevel>,
+    index: Res<TerrainIndex>,
+    mut warning_query: Query<(Entity, &mut RockfallWarning)>,
+    mut rock_query: Query<(Entity, &mut Transform, &mut FallingRock), Without<Player>>,
+    mut player_query: Query<(&Transform, &mut Health), With<Player>>,
+    tile_query: Query<&TerrainTile>,
+    mut warning_text: ResMut<WarningMessage>,
+    mut last_damage: ResMut<LastDamage>,
+) {
+    for (entity, mut warning) in warning_query.iter_mut() {
+        if warning.timer.tick(time.delta()).just_finished() {
+            let spawn = warning.spawn_point;
+            commands.entity(entity).despawn();
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgb(0.35, 0.3, 0.28),
+                        custom_size: Some(Vec2::new(14.0, 12.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(spawn.x, spawn.y, 2.5),
+                    ..default()
+                },
+                FallingRock { velocity: 60.0 },
+            ));
+        }
+    }
+
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    for (entity, mut transform, mut rock) in rock_query.iter_mut() {
+        rock.velocity += GRAVITY * 0.6 * time.delta_seconds();
+        transform.translation.y -= rock.velocity * time.delta_seconds();
+        let position = transform.translation.truncate();
+
+        if let Ok((player_transform, mut health)) = player_query.get_single_mut() {
+            if position.distance(player_transform.translation.truncate()) < 14.0 {
+                health.current -= ROCKFALL_DAMAGE * settings.difficulty.damage_multiplier();
+                last_damage.note("falling rock", time.elapsed_seconds_f64());
+                warning_text.show("Struck by falling rock!");
+                commands.entity(entity).despawn();
+                continue;
+            }
+        }
+        // Rocks shatter on the first solid ground below their source
+        let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+        let landed = index
+            .get(grid_x, grid_y)
+            .and_then(|tile_entity| tile_query.get(tile_entity).ok())
+            .is_some_and(|tile| {
+                tile.solid || matches!(tile.terrain_type, TerrainType::Water)
+            });
+        if landed || grid_y < 0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Soak the player in rain, snow, and river crossings, and dry them
+/// out near a fire pit or inside a shelter.
+pub fn wetness_system(
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    tile_query: Query<&TerrainTile>,
+    structure_query: Query<(&Transform, &Structure), Without<Player>>,
+    mut player_query: Query<(&Transform, &mut Wetness), With<Player>>,
+) {
+    let Ok((transform, mut wetness)) = player_query.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_seconds();
+    let position = transform.translation.truncate();
+
+    let in_water = current_level.definition.as_ref().is_some_and(|level| {
+        let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+        index
+            .get(grid_x, grid_y)
+            .and_then(|entity| tile_query.get(entity).ok())
+            .is_some_and(|tile| tile.terrain_type == TerrainType::Water)
+    });
+    let soak_rate = if in_water {
+        0.8
+    } else {
+        match weather.current_weather {
+            Weather::Rain | Weather::Storm => 0.08,
+            Weather::Snow => 0.03,
+            _ => 0.0,
+        }
+    };
+
+    let near_warmth = structure_query.iter().any(|(structure_transform, structure)| {
+        let range = match structure.structure_type {
+            StructureType::FirePit => TILE_SIZE * 3.0,
+            StructureType::Tent | StructureType::Shelter => TILE_SIZE * 1.5,
+        };
+        position.distance(structure_transform.translation.truncate()) < range
+    });
+    let dry_rate = if near_warmth {
+        0.2
+    } else if soak_rate == 0.0 {
+        0.02
+    } else {
+        0.0
+    };
+
+    wetness.level = (wetness.level + (soak_rate - dry_rate) * dt).clamp(0.0, 1.0);
+}
+
+/// Below this core temperature frostbite starts to set in.
+const HYPOTHERMIA_THRESHOLD: f32 = 35.0;
+/// Below this the cold itself does damage.
+const SEVERE_COLD_THRESHOLD: f32 = 32.0;
+
+/// Drift the player's body temperature toward what the weather, wind,
+/// and worn gear add up to; prolonged cold builds frostbite, warmth
+/// slowly heals it.
+pub fn body_temperature_system(
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    mut warning: ResMut<WarningMessage>,
+    structure_query: Query<(&Transform, &Structure), Without<Player>>,
+    mut query: Query<
+        (
+            &Transform,
+            &mut BodyTemperature,
+            &mut Frostbite,
+            &mut Health,
+            &EquippedItems,
+            &Wetness,
+            &Experience,
+            &Perks,
+        ),
+        With<Player>,
+    >,
+) {
+    let Ok((transform, mut body, mut frostbite, mut health, equipped, wetness, experience, perks)) =
+        query.get_single_mut()
+    else {
+        return;
+    };
+    // Wind chill: every m/s of wind feels like roughly another half
+    // degree off the air temperature. Soaked clothes bleed heat and
+    // cancel out much of their warmth.
+    let warmth = equipped.get_total_warmth() * (1.0 - wetness.level * 0.6);
+    let by_the_fire = structure_query.iter().any(|(structure_transform, structure)| {
+        structure.structure_type == StructureType::FirePit
+            && transform
+                .translation
+                .truncate()
+                .distance(structure_transform.translation.truncate())
+                < CAMPFIRE_WARMTH_RANGE
+    });
+    let fire_warmth = if by_the_fire { 12.0 } else { 0.0 };
+    let felt =
+        weather.temperature - weather.wind_speed * 0.5 + warmth + fire_warmth - wetness.level * 6.0;
+    let dt = time.delta_seconds();
+    if felt >= 0.0 {
+        body.current = (body.current + 0.4 * dt).min(37.0);
+        frostbite.severity = (frostbite.severity - 0.02 * dt).max(0.0);
+    } else {
+        // Trained cold tolerance slows the loss, never stops it
+        let mut tolerance =
+            1.0 / (1.0 + experience.trained_warmth as f32 * WARMTH_TRAINING_FACTOR);
+        if perks.has(Perk::ColdBlood) {
+            tolerance *= 0.5;
+        }
+        body.current = (body.current + felt * 0.01 * tolerance * dt).max(25.0);
+    }
+    if body.current < HYPOTHERMIA_THRESHOLD {
+        let before = frostbite.severity;
+        frostbite.severity =
+            (frostbite.severity + (HYPOTHERMIA_THRESHOLD - body.current) * 0.01 * dt).min(1.0);
+        if before == 0.0 && frostbite.severity > 0.0 {
+            warning.show("Your fingers are going numb with cold");
+        }
+    }
+    if body.current < SEVERE_COLD_THRESHOLD {
+        health.current -= (SEVERE_COLD_THRESHOLD - body.current) * 0.3 * dt;
+    }
+}
+
+/// Standing next to a hazard still singes; on it, the full rate.
+const ADJACENT_HAZARD_FACTOR: f32 = 0.35;
+
+/// Apply `damage_per_second` from hazardous tiles the player stands on
+/// or beside, blunted by heat gear and worn protection.
+pub fn hazard_damage_system(
+    time: Res<Time>,
+    settings: Res<crate::settings::Settings>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    hazard_query: Query<&Hazardous, With<TerrainTile>>,
+    mut player_query: Query<(&Transform, &mut Health, &EquippedItems), With<Player>>,
+    mut warning: ResMut<WarningMessage>,
+    mut last_damage: ResMut<LastDamage>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok((transform, mut health, equipped)) = player_query.get_single_mut() else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(
+        transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let mut rate: f32 = 0.0;
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let Some(entity) = index.get(grid_x + dx, grid_y + dy) else {
+                continue;
+            };
+            let Ok(hazard) = hazard_query.get(entity) else {
+                continue;
+            };
+            let factor = if dx == 0 && dy == 0 {
+                1.0
+            } else {
+                ADJACENT_HAZARD_FACTOR
+            };
+            rate = rate.max(hazard.damage_per_second * factor);
+        }
+    }
+    if rate <= 0.0 {
+        return;
+    }
+    if equipped.has_gear("heat_protection") {
+        rate *= 0.25;
+    }
+    rate *= 1.0 - (equipped.total_protection() * 0.05).min(0.7);
+    rate *= settings.difficulty.damage_multiplier();
+    health.current -= rate * time.delta_seconds();
+    last_damage.note("the searing heat", time.elapsed_seconds_f64());
+    warning.show("The heat is searing!");
+}
+
+/// Mtime polling for the current level's source file, so designers can
+/// edit a level on disk and see it respawn without restarting.
+#[derive(Resource)]
+pub struct LevelWatcher {
+    timer: Timer,
+    /// Which level the recorded mtime belongs to, so switching levels
+    /// doesn't look like an edit.
+    watched: String,
+    mtime: Option<std::time::SystemTime>,
+}
+
+impl Default for LevelWatcher {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            watched: String::new(),
+            mtime: None,
+        }
+    }
+}
+
+fn level_source_mtime(name: &str) -> Option<std::time::SystemTime> {
+    ["ron", "png", "json", "heightmap.ron", "tiled.ron"]
+        .iter()
+        .filter_map(|ext| {
+            std::fs::metadata(format!("levels/{name}.{ext}"))
+                .and_then(|meta| meta.modified())
+                .ok()
+        })
+        .max()
+}
+
+/// Reload the current level in place when its file changes on disk.
+/// The player stays where they are; terrain respawns around them.
+#[allow(clippy::too_many_arguments)]
+pub fn level_hot_reload_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut watcher: ResMut<LevelWatcher>,
+    mut current_level: ResMut<CurrentLevel>,
+    player_query: Query<&Transform, With<Player>>,
+    level_entity_query: Query<
+        Entity,
+        Or<(
+            With<TerrainTile>,
+            With<TerrainChunkMesh>,
+            With<NPC>,
+            With<RopeAnchor>,
+            With<Entrance>,
+            With<Structure>,
+            With<ItemPickup>,
+            With<RouteMarker>,
+            With<SecretMarker>,
+            With<Wildlife>,
+        )>,
+    >,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(mtime) = level_source_mtime(&current_level.name) else {
+        return; // generated interiors and the like have no file to watch
+    };
+    let changed =
+        watcher.watched == current_level.name && watcher.mtime.is_some_and(|known| known != mtime);
+    watcher.watched = current_level.name.clone();
+    watcher.mtime = Some(mtime);
+    if !changed {
+        return;
+    }
+    let Some((_, level)) = levels::list_levels()
+        .into_iter()
+        .find(|(name, _)| *name == current_level.name)
+    else {
+        warn!("Level {} changed on disk but no longer parses", current_level.name);
+        return;
+    };
+    info!("Reloading level {} from disk", current_level.name);
+    despawn_level_entities(&mut commands, &level_entity_query);
+    current_level.definition = Some(level);
+    current_level.spawned_chunks.clear();
+    // Keep the player where they stood rather than back at the start
+    if let Ok(player_transform) = player_query.get_single() {
+        current_level.return_position = Some(player_transform.translation.truncate());
+    }
+    current_level.needs_spawn = true;
+}
+
+/// Open the level-select screen from the climb (L by default).
+pub fn open_level_select_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings.bindings.just_pressed(&keyboard, Action::OpenLevelSelect) {
+        next_state.set(GameState::LevelSelect);
+    }
+}
+
+/// Open the training ledger (K by default).
+pub fn open_skills_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings.bindings.just_pressed(&keyboard, Action::OpenSkills) {
+        next_state.set(GameState::Skills);
+    }
+}
+
+/// Trained cold tolerance: each level bought slows heat loss by this
+/// fraction.
+pub const WARMTH_TRAINING_FACTOR: f32 = 0.15;
+
+/// What a perk costs in skill points.
+pub const PERK_COST: u32 = 2;
+
+/// Spend skill points with the number keys; Escape or the ledger key
+/// closes it. Skill and stamina land on their components outright; carry
+/// and warmth are recorded for the systems that recompute them, and
+/// 5-7 buy one-off perks at a steeper price.
+pub fn skill_spend_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<
+        (&mut Experience, &mut MovementStats, &mut Stamina, &mut Perks),
+        With<Player>,
+    >,
+) {
+    if keyboard.just_pressed(KeyCode::Escape)
+        || settings.bindings.just_pressed(&keyboard, Action::OpenSkills)
+    {
+        next_state.set(GameState::Climbing);
+        return;
+    }
+    let Ok((mut experience, mut stats, mut stamina, mut perks)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    let perk_keys = [KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7];
+    if let Some(index) = perk_keys.iter().position(|key| keyboard.just_pressed(*key)) {
+        let perk = Perk::ALL[index];
+        if perks.has(perk) {
+            warning.show(format!("You already have {}", perk.name()));
+            return;
+        }
+        if experience.skill_points < PERK_COST {
+            warning.show(format!("{} costs {PERK_COST} skill points", perk.name()));
+            return;
+        }
+        experience.skill_points -= PERK_COST;
+        perks.owned.push(perk);
+        warning.show(format!("Perk taken: {}", perk.name()));
+        return;
+    }
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+    ];
+    let Some(index) = keys.iter().position(|key| keyboard.just_pressed(*key)) else {
+        return;
+    };
+    if experience.skill_points == 0 {
+        warning.show("No skill points to spend — climb more");
+        return;
+    }
+    experience.skill_points -= 1;
+    match index {
+        0 => {
+            stats.climbing_skill += 0.5;
+            warning.show("Your technique sharpens");
+        }
+        1 => {
+            stamina.max += 10.0;
+            warning.show("Your endurance deepens");
+        }
+        2 => {
+            experience.trained_warmth += 1;
+            warning.show("The cold bothers you a little less");
+        }
+        _ => {
+            experience.trained_carry += 3.0;
+            warning.show("Your back grows stronger");
+        }
+    }
+}
+
+/// Open the spellbook with M, for those who have one.
+pub fn open_magic_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    caster_query: Query<&MagicUser, With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings.bindings.just_pressed(&keyboard, Action::OpenMagic)
+        && caster_query.get_single().is_ok()
+    {
+        next_state.set(GameState::Magic);
+    }
+}
+
+/// Cast a known spell with the number keys; Escape or the spellbook
+/// key closes it. A Mage in the party lends their focus, halving mana costs.
+pub fn spellcasting_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    party: Res<Party>,
+    npc_query: Query<&NPC>,
+    mut spells: ResMut<ActiveSpells>,
+    mut calm: ResMut<crate::weather::WeatherCalm>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut caster_query: Query<(&mut MagicUser, &mut Health), (With<Player>, Without<NPC>)>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape)
+        || settings.bindings.just_pressed(&keyboard, Action::OpenMagic)
+    {
+        next_state.set(GameState::Climbing);
+        return;
+    }
+    let Ok((mut caster, mut health)) = caster_query.get_single_mut() else {
+        return;
+    };
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    let Some(index) = keys
+        .iter()
+        .position(|key| keyboard.just_pressed(*key))
+    else {
+        return;
+    };
+    let Some(spell) = caster.known_spells.get(index).cloned() else {
+        return;
+    };
+    if spells.cooldowns.get(&spell.name).copied().unwrap_or(0.0) > 0.0 {
+        warning.show(format!("{} isn't ready yet", spell.name));
+        return;
+    }
+    let mage_along = party_has(&party, &npc_query, NPCType::Mage);
+    let cost = if mage_along {
+        spell.mana_cost * 0.5
+    } else {
+        spell.mana_cost
+    };
+    if caster.mana < cost {
+        warning.show("Not enough mana");
+        return;
+    }
+    caster.mana -= cost;
+    spells.cooldowns.insert(spell.name.clone(), spell.cooldown);
+    match spell.effect {
+        // Instant effects land now; the rest linger and tick elsewhere
+        SpellEffect::Heal(amount) => {
+            health.current = (health.current + amount).min(health.max);
+        }
+        SpellEffect::WeatherControl => {
+            calm.invoke(crate::weather::WEATHER_CALM_HOURS);
+        }
+        _ => spells.effects.push(ActiveSpell {
+            effect: spell.effect.clone(),
+            remaining: spell.duration,
+        }),
+    }
+    warning.show(format!("You cast {}", spell.name));
+    next_state.set(GameState::Climbing);
+}
+
+/// Mana returned per second of not casting.
+const MANA_REGEN_RATE: f32 = 0.8;
+
+/// Run down spell durations and cooldowns, apply lingering warmth,
+/// and let mana seep back.
+pub fn spell_tick_system(
+    time: Res<Time>,
+    selected: Res<SelectedCharacter>,
+    mut spells: ResMut<ActiveSpells>,
+    mut caster_query: Query<(&mut MagicUser, &mut BodyTemperature), With<Player>>,
+) {
+    let dt = time.delta_seconds();
+    for remaining in spells.cooldowns.values_mut() {
+        *remaining = (*remaining - dt).max(0.0);
+    }
+    for active in spells.effects.iter_mut() {
+        active.remaining -= dt;
+    }
+    let warmth = spells.warmth();
+    spells.effects.retain(|active| active.remaining > 0.0);
+    let Ok((mut caster, mut temperature)) = caster_query.get_single_mut() else {
+        return;
+    };
+    let regen = MANA_REGEN_RATE * selected.archetype.mana_regen_multiplier();
+    caster.mana = (caster.mana + regen * dt).min(caster.max_mana);
+    if warmth > 0.0 {
+        temperature.current = (temperature.current + warmth * dt).min(37.5);
+    }
+}
+
+/// Re-scan the levels directory when the select screen opens.
+pub fn refresh_available_levels(mut available: ResMut<AvailableLevels>) {
+    available.levels = levels::list_levels();
+}
+
+/// Pick a level with the number keys; Escape returns to the climb,
+/// or to the menu when no expedition has set out yet.
+pub fn level_select_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    available: Res<AvailableLevels>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut pending: ResMut<PendingLevelLoad>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<(), With<Player>>,
+    level_entity_query: Query<
+        Entity,
+        Or<(
+            With<TerrainTile>,
+            With<TerrainChunkMesh>,
+            With<NPC>,
+            With<RopeAnchor>,
+            With<Entrance>,
+            With<Structure>,
+            With<ItemPickup>,
+            With<RouteMarker>,
+            With<SecretMarker>,
+            With<Wildlife>,
+        )>,
+    >,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        if player_query.is_empty() {
+            next_state.set(GameState::Menu);
+        } else {
+            next_state.set(GameState::Climbing);
+        }
+        return;
+    }
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (index, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        let Some((name, level)) = available.levels.get(index) else {
+            continue;
+        };
+        // Tear down the old level; the chunk manager respawns terrain
+        // around the new start position.
+        despawn_level_entities(&mut commands, &level_entity_query);
+        // The level parse kicked off at startup is for the default
+        // mountain; drop it so it can't clobber this choice.
+        pending.task = None;
+        current_level.name = name.clone();
+        current_level.definition = Some(level.clone());
+        current_level.spawned_chunks.clear();
+        current_level.needs_spawn = true;
+        if player_query.is_empty() {
+            // Straight from the menu: still need a climber.
+            next_state.set(GameState::CharacterSelection);
+        } else {
+            next_state.set(GameState::Climbing);
+        }
+        info!("Switched to level {}", level.name);
+        return;
+    }
+}
+
+/// Put the player (and camera) on the level's start tile whenever a
+/// level is loaded or restarted.
+pub fn place_player_at_start(
+    mut current_level: ResMut<CurrentLevel>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    if !current_level.needs_spawn {
+        return;
+    }
+    current_level.needs_spawn = false;
+    current_level.completed = false;
+    let return_position = current_level.return_position.take();
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let start = match return_position {
+        Some(position) => position.extend(0.0),
+        None => {
+            let (x, y) = level.start_position;
+            levels::calculate_tile_position(x, y, level.width, level.height)
+        }
+    };
+    if let Ok(mut transform) = player_query.get_single_mut() {
+        transform.translation.x = start.x;
+        transform.translation.y = start.y;
+    }
+    if let Ok(mut transform) = camera_query.get_single_mut() {
+        transform.translation.x = start.x;
+        transform.translation.y = start.y;
+    }
+}
+
+/// Pay out the level reward when the player first reaches the goal,
+/// with a guiding bonus for every companion brought along.
+pub fn level_complete_system(
+    mut current_level: ResMut<CurrentLevel>,
+    party: Res<Party>,
+    mut autosave: ResMut<crate::saves::AutosaveState>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Money, &mut Experience, &mut Morale), With<Player>>,
+) {
+    if current_level.completed {
+        return;
+    }
+    let Ok((transform, mut money, mut experience, mut morale)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let (gx, gy) = level.goal_position;
+    let goal = levels::calculate_tile_position(gx, gy, level.width, level.height);
+    if transform
+        .translation
+        .truncate()
+        .distance(goal.truncate())
+        >= TILE_SIZE * 1.5
+    {
+        return;
+    }
+    let guiding_bonus = party.members.len() as f32 * 25.0;
+    let payout = level.reward + guiding_bonus;
+    money.0 += payout;
+    // A summit is worth more the harder the mountain fought
+    experience.award(25.0 * level.difficulty as f32);
+    morale.adjust(20.0);
+    autosave.request("summit");
+    current_level.completed = true;
+    if payout > 0.0 {
+        warning.show(format!("You reach the goal! +{payout:.0} kr"));
+    } else {
+        warning.show("You reach the goal!");
+    }
+}
+
+pub fn camera_follow_system(
+    settings: Res<crate::settings::Settings>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    player_query: Query<&Transform, (With<Player>, Without<Camera>)>,
+    mut camera_query: Query<&mut Transform, With<Camera>>,
+) {
+    // Free-look has the camera while the middle button is held; on
+    // release the usual lerp eases it back onto the player.
+    if mouse.pressed(MouseButton::Middle) {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let Ok(mut camera_transform) = camera_query.get_single_mut() else {
+        return;
+    };
+    let target = player_transform.translation;
+    let smoothing = settings.camera_smoothing;
+    camera_transform.translation.x += (target.x - camera_transform.translation.x) * smoothing;
+    camera_transform.translation.y += (target.y - camera_transform.translation.y) * smoothing;
+}
+
+/// Tightest and widest the camera will zoom.
+const ZOOM_RANGE: (f32, f32) = (0.5, 3.0);
+
+/// Mouse wheel (or +/-) zooms the view, clamped so the player can't
+/// zoom out far enough to melt the chunk manager.
+pub fn camera_zoom_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut wheel_events: EventReader<MouseWheel>,
+    mut camera_query: Query<&mut OrthographicProjection, With<Camera>>,
+) {
+    let mut steps = 0.0;
+    for event in wheel_events.read() {
+        steps += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 20.0,
+        };
+    }
+    if keyboard.just_pressed(KeyCode::Equal) {
+        steps += 1.0;
+    }
+    if keyboard.just_pressed(KeyCode::Minus) {
+        steps -= 1.0;
+    }
+    if steps == 0.0 {
+        return;
+    }
+    let Ok(mut projection) = camera_query.get_single_mut() else {
+        return;
+    };
+    // Scroll up tightens the view
+    projection.scale = (projection.scale * 0.9f32.powf(steps)).clamp(ZOOM_RANGE.0, ZOOM_RANGE.1);
+}
+
+/// Drag with the middle mouse button to scout the face without moving;
+/// wider zooms pan faster so a drag covers the same screen distance.
+pub fn camera_pan_system(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut camera_query: Query<(&mut Transform, &OrthographicProjection), With<Camera>>,
+) {
+    if !mouse.pressed(MouseButton::Middle) {
+        motion_events.clear();
+        return;
+    }
+    let Ok((mut transform, projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+    for event in motion_events.read() {
+        transform.translation.x -= event.delta.x * projection.scale;
+        transform.translation.y += event.delta.y * projection.scale;
+    }
+}
+
+/// Mirror the player sprite to match which way they're moving.
+pub fn player_facing_system(mut query: Query<(&Facing, &mut Sprite), With<Player>>) {
+    let Ok((facing, mut sprite)) = query.get_single_mut() else {
+        return;
+    };
+    if facing.direction.x.abs() > 0.01 {
+        sprite.flip_x = facing.direction.x < 0.0;
+    }
+}
+
+/// A sprite or label that only exists while the map is open.
+#[derive(Component)]
+pub struct MapPin;
+
+/// Where the camera sat before the map took it over.
+#[derive(Resource)]
+pub struct MapCameraRestore {
+    translation: Vec3,
+    scale: f32,
+}
+
+/// Open the full-level map (Tab by default).
+pub fn open_map_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings.bindings.just_pressed(&keyboard, Action::ToggleMap) {
+        next_state.set(GameState::Map);
+    }
+}
+
+/// Remember hazardous tiles as their chunks spawn, so the map can pin
+/// dangers near ground the player has actually covered.
+pub fn hazard_sighting_system(
+    mut seen: ResMut<SeenHazards>,
+    tile_query: Query<&TerrainTile, Added<Hazardous>>,
+) {
+    for tile in tile_query.iter() {
+        seen.positions.insert((tile.grid_x, tile.grid_y));
+    }
+}
+
+/// Frame the whole level and pin what the expedition knows about it:
+/// camps, people met, dangers seen, and the player's own cairns.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn setup_map_view(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    memory: Res<ConversationMemory>,
+    seen: Res<SeenHazards>,
+    markers: Res<MapMarkers>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    structure_query: Query<&Transform, With<Structure>>,
+    npc_query: Query<(&Transform, &NPC)>,
+    mut camera_query: Query<
+        (&mut Transform, &mut OrthographicProjection),
+        (With<Camera>, Without<Structure>, Without<NPC>),
+    >,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok((mut camera_transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+    commands.insert_resource(MapCameraRestore {
+        translation: camera_transform.translation,
+        scale: projection.scale,
+    });
+    let level_size = Vec2::new(level.width as f32, level.height as f32) * TILE_SIZE;
+    let window_size = window_query
+        .get_single()
+        .map(|window| Vec2::new(window.width(), window.height()))
+        .unwrap_or(Vec2::new(1280.0, 720.0));
+    projection.scale =
+        (level_size.x / window_size.x).max(level_size.y / window_size.y) * 1.05;
+    // The tile grid is centred on the origin
+    camera_transform.translation.x = 0.0;
+    camera_transform.translation.y = 0.0;
+
+    let pin_size = Vec2::splat(projection.scale * 8.0);
+    let pin = |commands: &mut Commands, position: Vec2, color: Color| {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(pin_size),
+                    ..default()
+                },
+                transform: Transform::from_xyz(position.x, position.y, 5.0),
+                ..default()
+            },
+            MapPin,
+        ));
+    };
+    for transform in structure_query.iter() {
+        pin(
+            &mut commands,
+            transform.translation.truncate(),
+            Color::srgb(0.9, 0.8, 0.3),
+        );
+    }
+    for (transform, npc) in npc_query.iter() {
+        let met = memory
+            .by_npc
+            .get(&npc.name)
+            .map(|remembered| remembered.met)
+            .unwrap_or(false);
+        if met {
+            pin(
+                &mut commands,
+                transform.translation.truncate(),
+                Color::srgb(0.3, 0.9, 0.4),
+            );
+        }
+    }
+    for &(grid_x, grid_y) in &seen.positions {
+        let position =
+            levels::calculate_tile_position(grid_x, grid_y, level.width, level.height);
+        pin(&mut commands, position.truncate(), Color::srgb(0.9, 0.3, 0.2));
+    }
+    for marker in &markers.markers {
+        let position = Vec2::new(marker.position.0, marker.position.1);
+        pin(&mut commands, position, Color::WHITE);
+        spawn_marker_label(&mut commands, marker, projection.scale);
+    }
+}
+
+/// The floating label beside a player-placed cairn.
+fn spawn_marker_label(commands: &mut Commands, marker: &MapMarker, scale: f32) {
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                marker.label.clone(),
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_xyz(
+                marker.position.0,
+                marker.position.1 + scale * 10.0,
+                5.0,
+            )
+            .with_scale(Vec3::splat(scale)),
+            ..default()
+        },
+        MapPin,
+    ));
+}
+
+/// On the map, P drops a numbered cairn where the player stands; the
+/// map key or Escape goes back to the climb.
+pub fn map_input_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut markers: ResMut<MapMarkers>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<&Transform, With<Player>>,
+    camera_query: Query<&OrthographicProjection, With<Camera>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape)
+        || settings.bindings.just_pressed(&keyboard, Action::ToggleMap)
+    {
+        next_state.set(GameState::Climbing);
+        return;
+    }
+    if !keyboard.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    let marker = MapMarker {
+        label: format!("Cairn {}", markers.markers.len() + 1),
+        position: (transform.translation.x, transform.translation.y),
+    };
+    if let Ok(projection) = camera_query.get_single() {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::WHITE,
+                    custom_size: Some(Vec2::splat(projection.scale * 8.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(marker.position.0, marker.position.1, 5.0),
+                ..default()
+            },
+            MapPin,
+        ));
+        spawn_marker_label(&mut commands, &marker, projection.scale);
+    }
+    warning.show(format!("{} placed", marker.label));
+    markers.markers.push(marker);
+}
+
+/// Tear the pins down and hand the camera back.
+pub fn cleanup_map_view(
+    mut commands: Commands,
+    restore: Option<Res<MapCameraRestore>>,
+    pin_query: Query<Entity, With<MapPin>>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
+) {
+    for entity in pin_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let Some(restore) = restore else {
+        return;
+    };
+    if let Ok((mut transform, mut projection)) = camera_query.get_single_mut() {
+        transform.translation = restore.translation;
+        projection.scale = restore.scale;
+    }
+    commands.remove_resource::<MapCameraRestore>();
+}
+
+/// Wading through water is exhausting and bitterly cold.
+pub fn water_crossing_system(
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    terrain_query: Query<&TerrainTile>,
+    mut player_query: Query<(&Transform, &mut Stamina, &mut Health), With<Player>>,
+) {
+    let Ok((transform, mut stamina, mut health)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(
+        transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let in_water = terrain_query.iter().any(|tile| {
+        tile.grid_x == grid_x && tile.grid_y == grid_y && tile.terrain_type == TerrainType::Water
+    });
+    if in_water {
+        stamina.current = (stamina.current - 6.0 * time.delta_seconds()).max(0.0);
+        // Glacial meltwater saps warmth fast
+        health.current -= 1.5 * time.delta_seconds();
+    }
+}
+
+/// How long bottoming out the stamina bar costs in forced slow-walking.
+const EXHAUSTION_DURATION: f32 = 4.0;
+
+/// Running the stamina bar dry drops the player into a few seconds of
+/// exhaustion: a grey tint, periodic gasps, and no climbing.
+pub fn exhaustion_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut messages: EventWriter<GameMessageEvent>,
+    mut player_query: Query<
+        (Entity, &Transform, &Stamina, &mut Sprite, Option<&mut Exhausted>),
+        With<Player>,
+    >,
+    mut gasp_timer: Local<f32>,
+) {
+    let Ok((entity, transform, stamina, mut sprite, exhausted)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    match exhausted {
+        Some(mut exhausted) => {
+            exhausted.remaining -= time.delta_seconds();
+            *gasp_timer -= time.delta_seconds();
+            if *gasp_timer <= 0.0 {
+                spawn_floating_text(
+                    &mut commands,
+                    transform.translation.truncate(),
+                    "* gasp *".to_string(),
+                    Color::srgb(0.8, 0.8, 0.85),
+                );
+                *gasp_timer = 0.8;
+            }
+            if exhausted.remaining <= 0.0 {
+                commands.entity(entity).remove::<Exhausted>();
+                sprite.color = PLAYER_COLOR;
+            }
+        }
+        None => {
+            if stamina.current <= 0.0 {
+                commands.entity(entity).insert(Exhausted {
+                    remaining: EXHAUSTION_DURATION,
+                });
+                sprite.color = EXHAUSTED_COLOR;
+                *gasp_timer = 0.0;
+                messages.send(GameMessageEvent::warning(
+                    "You're spent — legs shaking, lungs burning",
+                ));
+            }
+        }
+    }
+}
+
+/// Regenerate stamina while standing still or on easy ground; a
+/// downhearted climber catches their breath at half the rate.
+pub fn terrain_interaction_system(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut query: Query<(&mut Stamina, &Morale), With<Player>>,
+    mut messages: EventWriter<GameMessageEvent>,
+    mut was_winded: Local<bool>,
+) {
+    let Ok((mut stamina, morale)) = query.get_single_mut() else {
+        return;
+    };
+    let bindings = &settings.bindings;
+    let moving = bindings.pressed(&keyboard, Action::MoveUp)
+        || bindings.pressed(&keyboard, Action::MoveLeft)
+        || bindings.pressed(&keyboard, Action::MoveDown)
+        || bindings.pressed(&keyboard, Action::MoveRight);
+    if !moving {
+        let spirit = if morale.downhearted() { 0.5 } else { 1.0 };
+        stamina.current = (stamina.current
+            + stamina.max * time.delta_seconds() * 0.5 * spirit)
+            .min(stamina.max);
+        if stamina.current < stamina.max * 0.2 {
+            if !*was_winded {
+                messages.send(GameMessageEvent::info("Catching your breath..."));
+                *was_winded = true;
+            }
+        } else {
+            *was_winded = false;
+        }
+    }
+}
+
+/// The translucent square over the tile a swing would hit.
+#[derive(Component)]
+pub struct AimHighlight;
+
+/// A world-space number that drifts upward and fades: damage taken or
+/// health regained, coloured by what caused it.
+#[derive(Component)]
+pub struct FloatingText {
+    pub remaining: f32,
+}
+
+/// How long a floating number lives.
+const FLOATING_TEXT_LIFETIME: f32 = 1.2;
+
+fn spawn_floating_text(commands: &mut Commands, position: Vec2, text: String, color: Color) {
+    let jitter = rand::thread_rng().gen_range(-6.0..6.0);
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    font_size: 14.0,
+                    color,
+                    ..default()
+                },
+            ),
+            transform: Transform::from_xyz(position.x + jitter, position.y + 12.0, 6.0),
+            ..default()
+        },
+        FloatingText {
+            remaining: FLOATING_TEXT_LIFETIME,
+        },
+    ));
+}
+
+/// What colour a health loss reads in, judged by its recorded cause.
+fn damage_color(cause: Option<&str>) -> Color {
+    match cause {
+        Some(cause) if cause.contains("cold") => Color::srgb(0.5, 0.75, 1.0),
+        Some(cause) if cause.contains("fall") || cause.contains("slip") => {
+            Color::srgb(1.0, 0.6, 0.2)
+        }
+        Some(cause) if cause.contains("attack") || cause.contains("ambush") => {
+            Color::srgb(1.0, 0.25, 0.25)
+        }
+        Some(cause) if cause.contains("heat") || cause.contains("rock") => {
+            Color::srgb(1.0, 0.45, 0.15)
+        }
+        _ => Color::srgb(0.9, 0.4, 0.4),
+    }
+}
+
+/// Watch the player's health and raise a floating number whenever it
+/// changes. Slow drains (cold, heat) are batched so they read as one
+/// number instead of a stream of fractions.
+pub fn floating_number_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    last_damage: Res<LastDamage>,
+    player_query: Query<(&Transform, &Health), With<Player>>,
+    mut previous: Local<Option<f32>>,
+    mut pending: Local<f32>,
+    mut hold: Local<f32>,
+) {
+    let Ok((transform, health)) = player_query.get_single() else {
+        *previous = None;
+        return;
+    };
+    let Some(prev) = *previous else {
+        *previous = Some(health.current);
+        return;
+    };
+    *previous = Some(health.current);
+    *pending += health.current - prev;
+    *hold -= time.delta_seconds();
+    if *hold > 0.0 || pending.abs() < 1.0 {
+        return;
+    }
+    let position = transform.translation.truncate();
+    if *pending > 0.0 {
+        spawn_floating_text(
+            &mut commands,
+            position,
+            format!("+{:.0}", *pending),
+            Color::srgb(0.4, 0.9, 0.4),
+        );
+    } else {
+        spawn_floating_text(
+            &mut commands,
+            position,
+            format!("{:.0}", *pending),
+            damage_color(last_damage.cause.as_deref()),
+        );
+    }
+    *pending = 0.0;
+    *hold = 0.4;
+}
+
+/// Drift floating numbers upward and fade them out before despawning.
+pub fn floating_text_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut text_query: Query<(Entity, &mut Transform, &mut Text, &mut FloatingText)>,
+) {
+    for (entity, mut transform, mut text, mut floating) in text_query.iter_mut() {
+        floating.remaining -= time.delta_seconds();
+        if floating.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += 24.0 * time.delta_seconds();
+        let color = &mut text.sections[0].style.color;
+        *color = color.with_alpha((floating.remaining / FLOATING_TEXT_LIFETIME).min(1.0));
+    }
+}
+
+/// Where the cursor sits in the world, if it's over the window.
+fn cursor_world_position(
+    window_query: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    let window = window_query.get_single().ok()?;
+    let cursor = window.cursor_position()?;
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    camera.viewport_to_world_2d(camera_transform, cursor)
+}
+
+/// The breakable tile under the cursor, if it's within arm's reach of
+/// the player.
+fn aimed_tile(
+    cursor_pos: Vec2,
+    player_pos: Vec2,
+    level: &levels::LevelDefinition,
+    terrain_index: &TerrainIndex,
+) -> Option<Entity> {
+    let (grid_x, grid_y) = levels::world_to_grid(cursor_pos, level.width, level.height);
+    let (player_x, player_y) = levels::world_to_grid(player_pos, level.width, level.height);
+    if (grid_x - player_x).abs() > 1 || (grid_y - player_y).abs() > 1 {
+        return None;
+    }
+    terrain_index.get(grid_x, grid_y)
+}
+
+/// Keep a faint square over whichever breakable tile the cursor aims
+/// at, so the player can see what a swing will hit.
+pub fn aim_highlight_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    terrain_index: Res<TerrainIndex>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    player_query: Query<&Transform, (With<Player>, Without<AimHighlight>)>,
+    tile_query: Query<(&Transform, &Breakable), (With<TerrainTile>, Without<AimHighlight>)>,
+    mut highlight_query: Query<(&mut Transform, &mut Visibility), With<AimHighlight>>,
+) {
+    let target = (|| {
+        let level = current_level.definition.as_ref()?;
+        let player_pos = player_query.get_single().ok()?.translation.truncate();
+        let cursor_pos = cursor_world_position(&window_query, &camera_query)?;
+        let entity = aimed_tile(cursor_pos, player_pos, level, &terrain_index)?;
+        let (transform, _) = tile_query.get(entity).ok()?;
+        let tile_pos = transform.translation.truncate();
+        (player_pos.distance(tile_pos) < TILE_SIZE * 1.3).then_some(tile_pos)
+    })();
+    let Ok((mut transform, mut visibility)) = highlight_query.get_single_mut() else {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 1.0, 1.0, 0.25),
+                    custom_size: Some(Vec2::splat(TILE_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 1.5),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            AimHighlight,
+        ));
+        return;
+    };
+    match target {
+        Some(tile_pos) => {
+            transform.translation.x = tile_pos.x;
+            transform.translation.y = tile_pos.y;
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+/// Swing the equipped tool, on click at the tile under the cursor or
+/// on the bound key at whatever breakable is in reach. The hammer
+/// instead knocks down the nearest structure.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn tool_use_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    current_level: Res<CurrentLevel>,
+    terrain_index: Res<TerrainIndex>,
+    database: Res<ItemDatabase>,
+    mut built: ResMut<BuiltStructures>,
+    mut warning: ResMut<WarningMessage>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mut player_query: Query<
+        (&Transform, &mut EquippedItems, &Frostbite, &Perks, &Facing),
+        With<Player>,
+    >,
+    mut terrain_query: Query<(&Transform, &TerrainTile, &mut Breakable)>,
+    structure_query: Query<(Entity, &Transform, &Structure), Without<Player>>,
+    mut broken_events: EventWriter<TerrainBrokenEvent>,
+    mut messages: EventWriter<GameMessageEvent>,
+) {
+    let clicked = mouse.just_pressed(MouseButton::Left);
+    if !clicked && !settings.bindings.just_pressed(&keyboard, Action::UseTool) {
+        return;
+    }
+    let Ok((player_transform, mut equipped, frostbite, perks, facing)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    // Numb hands fumble swings outright
+    if frostbite.severity > 0.0
+        && rand::thread_rng().gen_bool((frostbite.severity * 0.5) as f64)
+    {
+        messages.send(GameMessageEvent::warning("Your numb fingers fumble the swing"));
+        return;
+    }
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Some(tool) = equipped.tool.as_mut() else {
+        messages.send(GameMessageEvent::info("No tool equipped!"));
+        return;
+    };
+    let ItemType::Tool(tool_type) = tool.item_type else {
+        return;
+    };
+    if tool.properties.durability <= 0.0 {
+        warning.show(format!("Your {} is broken — it needs repairing", tool.name));
+        return;
+    }
+
+    let player_pos = player_transform.translation.truncate();
+
+    // The hammer tears down what the player has built
+    if tool_type == ToolType::Hammer {
+        let nearest = structure_query
+            .iter()
+            .map(|(entity, structure_transform, structure)| {
+                let position = structure_transform.translation.truncate();
+                (entity, position, structure, player_pos.distance(position))
+            })
+            .filter(|(_, _, _, distance)| *distance < TILE_SIZE * 1.3)
+            .min_by(|a, b| a.3.total_cmp(&b.3));
+        let Some((entity, position, structure, _)) = nearest else {
+            warning.show("Nothing here to knock down");
+            return;
+        };
+        commands.entity(entity).despawn();
+        let grid = levels::world_to_grid(position, level.width, level.height);
+        built.remove_at(&current_level.name, grid);
+        // Salvage about half the wood that went in
+        let salvage = structure.structure_type.wood_cost().unwrap_or(0) / 2;
+        for _ in 0..salvage {
+            if let Some(item) = database.get("wood") {
+                spawn_item_pickup(&mut commands, item, position);
+            }
+        }
+        tool.properties.durability = (tool.properties.durability - 2.0).max(0.0);
+        warning.show("You knock the structure down");
+        return;
+    }
+
+    // Mouse aim strikes exactly the tile under the cursor; the bound
+    // key (or a cursor off-window, as on gamepad) falls back to the
+    // nearest breakable neighbour.
+    let aimed = cursor_world_position(&window_query, &camera_query)
+        .and_then(|cursor_pos| aimed_tile(cursor_pos, player_pos, level, &terrain_index));
+    let candidates: Vec<Entity> = match aimed {
+        Some(entity) if clicked => vec![entity],
+        None if clicked => {
+            warning.show("Too far to swing");
+            return;
+        }
+        _ => {
+            let (grid_x, grid_y) = levels::world_to_grid(player_pos, level.width, level.height);
+            // The tile the player faces takes the swing first; the
+            // rest of the eight neighbours line up behind it
+            let face_x = facing.direction.x.round() as i32;
+            let face_y = facing.direction.y.round() as i32;
+            let mut offsets: Vec<(i32, i32)> = vec![(face_x, face_y)];
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if (dx, dy) != (face_x, face_y) {
+                        offsets.push((dx, dy));
+                    }
+                }
+            }
+            offsets
+                .into_iter()
+                .filter_map(|(dx, dy)| terrain_index.get(grid_x + dx, grid_y + dy))
+                .collect()
+        }
+    };
+    let mut wrong_tool: Option<ToolType> = None;
+    for entity in candidates {
+        let Ok((transform, tile, mut breakable)) = terrain_query.get_mut(entity) else {
+            continue;
+        };
+        let tile_pos = transform.translation.truncate();
+        if player_pos.distance(tile_pos) >= TILE_SIZE * 1.3 {
+            continue;
+        }
+        if breakable.tool_required != tool_type {
+            wrong_tool = Some(breakable.tool_required);
+            continue;
+        }
+        // Every swing wears the edge; glacier ice eats it fastest
+        let wear = match tile.terrain_type {
+            TerrainType::Glacier => 2.5,
+            TerrainType::Rock => 1.5,
+            _ => 1.0,
+        };
+        tool.properties.durability = (tool.properties.durability - wear).max(0.0);
+        let worn = tool.properties.durability / tool.properties.max_durability;
+        if worn < 0.2 {
+            warning.show(format!("Your {} is nearly spent", tool.name));
+        }
+        // A dulled edge glances off as often as it bites
+        if worn < 0.5 && rand::thread_rng().gen_bool(0.4) {
+            messages.send(GameMessageEvent::info(format!(
+                "The dull {} glances off",
+                tool.name
+            )));
+            return;
+        }
+        // An ice specialist's swing goes clean through frozen ground
+        let one_hit = perks.has(Perk::IceSpecialist)
+            && matches!(
+                tile.terrain_type,
+                TerrainType::Ice | TerrainType::Glacier
+            );
+        if one_hit {
+            breakable.current_hits = breakable.hits_required;
+        } else {
+            breakable.current_hits += 1;
+        }
+        messages.send(GameMessageEvent::info(format!(
+            "Crack! ({}/{})",
+            breakable.current_hits, breakable.hits_required
+        )));
+        if breakable.current_hits >= breakable.hits_required {
+            broken_events.send(TerrainBrokenEvent {
+                entity,
+                position: tile_pos,
+                terrain_type: tile.terrain_type,
+            });
+        }
+        return;
+    }
+    if let Some(required) = wrong_tool {
+        warning.show(format!("That needs a {required:?}, not your {}", tool.name));
+    }
+}
+
+/// React to broken terrain by converting the tile, queueing its chunk
+/// mesh for a rebuild, and dropping whatever the break uncovered.
+pub fn terrain_broken_handler_system(
+    mut commands: Commands,
+    mut events: EventReader<TerrainBrokenEvent>,
+    database: Res<ItemDatabase>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut broken: ResMut<crate::saves::BrokenTiles>,
+    mut dirty: ResMut<DirtyChunks>,
+    mut warning: ResMut<WarningMessage>,
+    mut terrain_query: Query<&mut TerrainTile>,
+    mut experience_query: Query<&mut Experience, With<Player>>,
+) {
+    let mut rng = rand::thread_rng();
+    for event in events.read() {
+        if let Ok(mut experience) = experience_query.get_single_mut() {
+            if experience.award(3.0) {
+                warning.show(format!(
+                    "You've grown as a climber (level {}) — skill point earned",
+                    experience.level
+                ));
+            }
+        }
+        if let Ok(mut tile) = terrain_query.get_mut(event.entity) {
+            complete_terrain_break(&mut tile);
+            dirty.chunks.insert(terrain::chunk_of(tile.grid_x, tile.grid_y));
+            // Stamp the break into the definition too, so it outlives
+            // chunk respawns and rides along in the save slot
+            broken.record(&current_level.name, (tile.grid_x, tile.grid_y));
+            let (grid_x, grid_y) = (tile.grid_x, tile.grid_y);
+            if let Some(level) = current_level.definition.as_mut() {
+                if let Some(data) = level
+                    .terrain
+                    .iter_mut()
+                    .find(|data| data.x == grid_x && data.y == grid_y)
+                {
+                    data.terrain_type = TerrainType::Soil;
+                }
+            }
+            for id in break_loot(event.terrain_type, &mut rng) {
+                let Some(item) = database.get(id) else {
+                    continue;
+                };
+                // Scatter drops a little so they don't stack invisibly
+                let offset = Vec2::new(rng.gen_range(-6.0..6.0), rng.gen_range(-6.0..6.0));
+                spawn_item_pickup(&mut commands, item, event.position + offset);
+            }
+        }
+    }
+}
+
+/// What breaking this terrain leaves behind to pick up: ice yields
+/// chunks, rock can reveal minerals, and glaciers occasionally give up
+/// something older.
+fn break_loot(terrain_type: TerrainType, rng: &mut impl Rng) -> Vec<&'static str> {
+    let mut loot = Vec::new();
+    match terrain_type {
+        TerrainType::Ice => {
+            loot.push("ice_chunk");
+            if rng.gen_bool(0.2) {
+                loot.push("mineral");
+            }
+        }
+        TerrainType::Glacier => {
+            loot.push("ice_chunk");
+            if rng.gen_bool(0.3) {
+                loot.push("mineral");
+            }
+            if rng.gen_bool(0.1) {
+                loot.push("rune_stone");
+            }
+        }
+        TerrainType::Rock => {
+            if rng.gen_bool(0.5) {
+                loot.push("mineral");
+            }
+            if rng.gen_bool(0.05) {
+                loot.push("obsidian");
+            }
+        }
+        _ => {}
+    }
+    loot
+}
+
+/// Broken ice becomes bare soil.
+pub fn complete_terrain_break(tile: &mut TerrainTile) {
+    tile.terrain_type = TerrainType::Soil;
+    tile.climbable = true;
+    tile.solid = false;
+}
+
+/// Watch the player's vitals and end the climb when they give out,
+/// working out what did it for the death screen: a recent acute hit
+/// if there was one, otherwise whichever need ran dry.
+pub fn check_player_death(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_time: Res<GameTime>,
+    last_damage: Res<LastDamage>,
+    query: Query<(&Transform, &Health, &Hunger, &Thirst, &BodyTemperature), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Ok((transform, health, hunger, thirst, body)) = query.get_single() else {
+        return;
+    };
+    if health.current > 0.0 {
+        return;
+    }
+    let recent_hit = last_damage
+        .cause
+        .as_ref()
+        .filter(|_| time.elapsed_seconds_f64() - last_damage.at < 4.0)
+        .cloned();
+    let cause = if let Some(hit) = recent_hit {
+        hit
+    } else if thirst.current <= 0.0 {
+        "dehydration".to_string()
+    } else if hunger.current <= 0.0 {
+        "starvation".to_string()
+    } else if body.current < SEVERE_COLD_THRESHOLD {
+        "the cold".to_string()
+    } else {
+        "exhaustion and injuries".to_string()
+    };
+    error!("Player died: {}", cause);
+    commands.insert_resource(DeathReport {
+        cause,
+        altitude: transform.translation.y,
+        day: game_time.day,
+        hour: game_time.hour,
+    });
+    next_state.set(GameState::GameOver);
+}
+
+/// Hunger and thirst slowly damage a starving player.
+pub fn health_system(
+    time: Res<Time>,
+    mut query: Query<(&mut Health, &Hunger, &Thirst), With<Player>>,
+) {
+    for (mut health, hunger, thirst) in query.iter_mut() {
+        if hunger.current <= 0.0 {
+            health.current -= 1.0 * time.delta_seconds();
+        }
+        if thirst.current <= 0.0 {
+            health.current -= 2.0 * time.delta_seconds();
+        }
+    }
+}
+
+/// Wear morale down through dark hours, storms and frostbite, and let
+/// a fire nearby lift it back. The bigger boosts — summits, hot meals,
+/// a rescue — land where those happen.
+pub fn morale_system(
+    time: Res<Time>,
+    time_of_day: Res<State<TimeOfDay>>,
+    weather: Res<WeatherSystem>,
+    structure_query: Query<(&Transform, &Structure), Without<Player>>,
+    mut player_query: Query<(&Transform, &mut Morale, &Frostbite), With<Player>>,
+) {
+    let Ok((transform, mut morale, frostbite)) = player_query.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_seconds();
+    if *time_of_day.get() == TimeOfDay::Night {
+        morale.adjust(-0.05 * dt);
+    }
+    if weather.current_weather == Weather::Storm {
+        morale.adjust(-0.08 * dt);
+    }
+    if frostbite.severity > 0.0 {
+        morale.adjust(-0.1 * frostbite.severity * dt);
+    }
+    let by_the_fire = structure_query.iter().any(|(structure_transform, structure)| {
+        structure.structure_type == StructureType::FirePit
+            && transform
+                .translation
+                .truncate()
+                .distance(structure_transform.translation.truncate())
+                < CAMPFIRE_WARMTH_RANGE
+    });
+    if by_the_fire {
+        morale.adjust(0.3 * dt);
+    }
+}
+
+/// Burn through food and water faster when working hard, and drink
+/// faster still in volcanic heat.
+pub fn hunger_thirst_system(
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    mut warning: ResMut<WarningMessage>,
+    mut query: Query<(&mut Hunger, &mut Thirst, &Stamina), With<Player>>,
+) {
+    let Ok((mut hunger, mut thirst, stamina)) = query.get_single_mut() else {
+        return;
+    };
+    let dt = time.delta_seconds();
+    // Exertion shows up as spent stamina
+    let exertion = 1.0 + 2.0 * (1.0 - stamina.current / stamina.max);
+    hunger.current = (hunger.current - 0.12 * exertion * dt).max(0.0);
+    let heat = if weather.temperature > 15.0 { 2.0 } else { 1.0 };
+    thirst.current = (thirst.current - 0.2 * exertion * heat * dt).max(0.0);
+    if hunger.current == 0.0 || thirst.current == 0.0 {
+        warning.show("You're running on empty — eat and drink something");
+    }
+}
+
+/// In the inventory screen, number keys consume food and drink, or
+/// equip wearables and tools (swapping out whatever held the slot).
+pub fn consume_item_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut warning: ResMut<WarningMessage>,
+    mut query: Query<
+        (
+            &mut Inventory,
+            &mut EquippedItems,
+            &mut Hunger,
+            &mut Thirst,
+            &mut Morale,
+        ),
+        With<Player>,
+    >,
+) {
+    // Shifted digits belong to crafting, Ctrl digits to the hotbar
+    if keyboard.pressed(KeyCode::ShiftLeft)
+        || keyboard.pressed(KeyCode::ShiftRight)
+        || keyboard.pressed(KeyCode::ControlLeft)
+        || keyboard.pressed(KeyCode::ControlRight)
+    {
+        return;
+    }
+    let Ok((mut inventory, mut equipped, mut hunger, mut thirst, mut morale)) =
+        query.get_single_mut()
+    else {
+        return;
+    };
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (index, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        let Some(item) = inventory.items.get(index) else {
+            return;
+        };
+        match item.item_type {
+            ItemType::Food | ItemType::Drink => {
+                let item = inventory.items.remove(index);
+                hunger.current = (hunger.current + item.properties.nutrition).min(hunger.max);
+                thirst.current = (thirst.current + item.properties.water).min(thirst.max);
+                // A proper meal does more for the spirit than a handful
+                morale.adjust(2.0 + item.properties.nutrition * 0.1);
+                warning.show(format!("You consume the {}", item.name));
+            }
+            _ => {
+                if let Some(slot) = item.equipment_slot() {
+                    let item = inventory.items.remove(index);
+                    warning.show(format!("You equip the {}", item.name));
+                    if let Some(previous) = equipped.slot_mut(slot).replace(item) {
+                        inventory.items.push(previous);
+                    }
+                } else {
+                    warning.show(format!("You can't use the {}", item.name));
+                }
+            }
+        }
+        return;
+    }
+}
+
+/// How full the pack can get before the load starts to tell.
+const ENCUMBRANCE_THRESHOLD: f32 = 0.75;
+
+/// Seconds between uses of the same hotbar slot.
+const HOTBAR_COOLDOWN: f32 = 8.0;
+
+/// Use hotbar consumables with 1-5 while climbing, no inventory screen
+/// needed. Digits are left to the dialogue system while a conversation
+/// is up. Each slot cools down after use so a full pack of rations
+/// can't be wolfed down mid-crux.
+pub fn hotbar_use_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    active: Res<ActiveDialogue>,
+    mut hotbar: ResMut<Hotbar>,
+    mut warning: ResMut<WarningMessage>,
+    mut query: Query<
+        (&mut Inventory, &mut Hunger, &mut Thirst, &mut BodyTemperature),
+        With<Player>,
+    >,
+) {
+    for cooldown in hotbar.cooldowns.iter_mut() {
+        *cooldown = (*cooldown - time.delta_seconds()).max(0.0);
+    }
+    if active.tree.is_some() {
+        return;
+    }
+    let Ok((mut inventory, mut hunger, mut thirst, mut temperature)) = query.get_single_mut()
+    else {
+        return;
+    };
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+    ];
+    for (slot, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        let Some(name) = hotbar.slots[slot].clone() else {
+            continue;
+        };
+        if hotbar.cooldowns[slot] > 0.0 {
+            warning.show(format!("Not yet — {:.0}s", hotbar.cooldowns[slot].ceil()));
+            return;
+        }
+        let Some(index) = inventory.items.iter().position(|item| item.name == name) else {
+            warning.show(format!("No {} left in the pack", name));
+            return;
+        };
+        let item = inventory.items.remove(index);
+        hunger.current = (hunger.current + item.properties.nutrition).min(hunger.max);
+        thirst.current = (thirst.current + item.properties.water).min(thirst.max);
+        // Warming drinks push core temperature back up a little
+        if item.properties.warmth > 0.0 {
+            temperature.current = (temperature.current + item.properties.warmth * 0.2).min(39.0);
+        }
+        warning.show(format!("You consume the {}", item.name));
+        hotbar.cooldowns[slot] = HOTBAR_COOLDOWN;
+        return;
+    }
+}
+
+/// Extra kilograms a Viking companion shoulders.
+const VIKING_CARRY_BONUS: f32 = 15.0;
+
+/// Keep carrying limits in sync with the worn backpack: the bigger the
+/// pack, the more slots and weight the player can manage. A Viking in
+/// the party shoulders part of the load on top of that.
+pub fn backpack_capacity_system(
+    party: Res<Party>,
+    selected: Res<SelectedCharacter>,
+    npc_query: Query<&NPC>,
+    experience_query: Query<&Experience, With<Player>>,
+    mut query: Query<(&mut Inventory, &EquippedItems, &Perks), With<Player>>,
+) {
+    let Ok((mut inventory, equipped, perks)) = query.get_single_mut() else {
+        return;
+    };
+    let (capacity_bonus, weight_bonus) = equipped
+        .backpack
+        .as_ref()
+        .map(|pack| {
+            (
+                pack.properties.capacity_bonus,
+                pack.properties.weight_limit_bonus,
+            )
+        })
+        .unwrap_or((0, 0.0));
+    let viking_bonus = if party_has(&party, &npc_query, NPCType::Viking) {
+        VIKING_CARRY_BONUS
+    } else {
+        0.0
+    };
+    let capacity = BASE_CAPACITY + capacity_bonus;
+    let trained = experience_query
+        .get_single()
+        .map(|experience| experience.trained_carry)
+        .unwrap_or(0.0);
+    let sherpa_bonus = if perks.has(Perk::Sherpa) { 10.0 } else { 0.0 };
+    let weight_limit = BASE_WEIGHT_LIMIT
+        + weight_bonus
+        + viking_bonus
+        + selected.archetype.carry_bonus()
+        + trained
+        + sherpa_bonus;
+    if inventory.capacity != capacity {
+        inventory.capacity = capacity;
+    }
+    if inventory.weight_limit != weight_limit {
+        inventory.weight_limit = weight_limit;
+    }
+}
+
+/// Hour the player gets up after a night's sleep.
+/// Craft from the inventory screen: Shift+1-9 builds the matching
+/// recipe if the materials are in the pack.
+pub fn craft_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    book: Res<crate::items::RecipeBook>,
+    database: Res<ItemDatabase>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<&mut Inventory, With<Player>>,
+) {
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    if !shift {
+        return;
+    }
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        return;
+    };
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (index, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        let Some(recipe) = book.recipes.get(index) else {
+            continue;
+        };
+        let Some(output) = database.get(&recipe.output) else {
+            warn!("Recipe output {:?} is not in the item database", recipe.output);
+            continue;
+        };
+        // Gather the pack indices each input needs, bailing if short
+        let mut used: Vec<usize> = Vec::new();
+        let mut short = None;
+        for (input_id, count) in &recipe.inputs {
+            let Some(input) = database.get(input_id) else {
+                short = Some(input_id.clone());
+                break;
+            };
+            let mut found = 0;
+            for (item_index, item) in inventory.items.iter().enumerate() {
+                if item.name == input.name && !used.contains(&item_index) {
+                    used.push(item_index);
+                    found += 1;
+                    if found == *count {
+                        break;
+                    }
+                }
+            }
+            if found < *count {
+                short = Some(input.name.clone());
+                break;
+            }
+        }
+        if let Some(missing) = short {
+            warning.show(format!("You're short of {missing} for the {}", output.name));
+            return;
+        }
+        used.sort_unstable();
+        for item_index in used.into_iter().rev() {
+            inventory.items.remove(item_index);
+        }
+        warning.show(format!("Crafted {}", output.name));
+        inventory.items.push(output);
+        return;
+    }
+}
+
+const WAKE_HOUR: f32 = 7.0;
+/// How fast the clock runs while asleep, in game hours per second.
+const SLEEP_TIME_SCALE: f32 = 2.0;
+
+/// Pitch a carried tent on flat open ground with T.
+#[allow(clippy::too_many_arguments)]
+pub fn pitch_tent_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    mut built: ResMut<BuiltStructures>,
+    tile_query: Query<&TerrainTile>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+    let Ok((transform, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Some(tent_index) = inventory.items.iter().position(|item| item.name == "Tent") else {
+        warning.show("You don't have a tent");
+        return;
+    };
+    let position = transform.translation.truncate();
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    let flat = index
+        .get(grid_x, grid_y)
+        .and_then(|entity| tile_query.get(entity).ok())
+        .is_some_and(|tile| {
+            matches!(
+                tile.terrain_type,
+                TerrainType::Grass | TerrainType::Soil | TerrainType::Snow
+            )
+        });
+    if !flat {
+        warning.show("You need flat open ground to pitch the tent");
+        return;
+    }
+    inventory.items.remove(tent_index);
+    spawn_structure(&mut commands, StructureType::Tent, position);
+    built.record(&current_level.name, StructureType::Tent, (grid_x, grid_y));
+    warning.show("Tent pitched");
+}
+
+/// Turn in for the night (Z by default); sheltered if a tent or
+/// shelter is close by, exposed (and risky) otherwise.
+pub fn start_sleep_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    structure_query: Query<(&Transform, &Structure), Without<Player>>,
+) {
+    if !settings.bindings.just_pressed(&keyboard, Action::Sleep) {
+        return;
+    }
+    let Ok((entity, transform)) = player_query.get_single() else {
+        return;
+    };
+    let sheltered = structure_query.iter().any(|(structure_transform, structure)| {
+        matches!(
+            structure.structure_type,
+            StructureType::Tent | StructureType::Shelter
+        ) && transform
+            .translation
+            .truncate()
+            .distance(structure_transform.translation.truncate())
+            < TILE_SIZE * 1.5
+    });
+    if !sheltered {
+        warning.show("You bed down in the open — a cold, risky night");
+    }
+    commands.entity(entity).insert(Sleeping {
+        wake_hour: WAKE_HOUR,
+    });
+    next_state.set(GameState::Sleeping);
+}
+
+/// Fast-forward the night, restoring the sleeper — or hurting them if
+/// they're out in the cold where the wolves walk.
+#[allow(clippy::too_many_arguments)]
+pub fn sleeping_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    mut game_time: ResMut<GameTime>,
+    mut autosave: ResMut<crate::saves::AutosaveState>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<
+        (Entity, &Transform, &Sleeping, &mut Health, &mut Stamina),
+        With<Player>,
+    >,
+    structure_query: Query<(&Transform, &Structure), Without<Player>>,
+    wildlife_query: Query<&Wildlife>,
+    mut last_damage: ResMut<LastDamage>,
+) {
+    let Ok((entity, transform, sleeping, mut health, mut stamina)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    let dt = time.delta_seconds();
+    let before = game_time.hour;
+    game_time.hour += SLEEP_TIME_SCALE * dt;
+    if game_time.hour >= 24.0 {
+        game_time.hour -= 24.0;
+        game_time.day += 1;
+    }
+
+    let sheltered = structure_query.iter().any(|(structure_transform, structure)| {
+        matches!(
+            structure.structure_type,
+            StructureType::Tent | StructureType::Shelter
+        ) && transform
+            .translation
+            .truncate()
+            .distance(structure_transform.translation.truncate())
+            < TILE_SIZE * 1.5
+    });
+    stamina.current = (stamina.current + 12.0 * dt).min(stamina.max);
+    if sheltered {
+        health.current = (health.current + 2.0 * dt).min(health.max);
+    } else {
+        // Exposed: the cold gnaws, and something may come sniffing
+        if weather.temperature < 0.0 {
+            health.current -= 1.5 * dt;
+        }
+        let predators = wildlife_query
+            .iter()
+            .any(|wildlife| wildlife.aggression > 0.0);
+        if predators && rand::thread_rng().gen_bool((0.02 * dt as f64).min(1.0)) {
+            health.current -= 15.0;
+            last_damage.note("a night ambush at camp", time.elapsed_seconds_f64());
+            warning.show("You wake with a start — something attacked you in the night!");
+            commands.entity(entity).remove::<Sleeping>();
+            next_state.set(GameState::Climbing);
+            return;
+        }
+    }
+
+    // Wake at the appointed hour (crossing it this frame)
+    let wake = sleeping.wake_hour;
+    let crossed = (before < wake && game_time.hour >= wake)
+        || (before > game_time.hour && game_time.hour >= wake);
+    if crossed {
+        warning.show("You wake rested");
+        autosave.request("rested");
+        commands.entity(entity).remove::<Sleeping>();
+        next_state.set(GameState::Climbing);
+    }
+}
+
+/// How far a fire pit's warmth reaches.
+const CAMPFIRE_WARMTH_RANGE: f32 = TILE_SIZE * 3.0;
+
+/// Gather from the land with G: firewood from shrubs and trees (the
+/// plant is used up and reverts to grass), berries from grass,
+/// driftwood along the coast, and the odd fish from open water.
+pub fn gather_wood_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    mut dirty: ResMut<DirtyChunks>,
+    mut tile_query: Query<&mut TerrainTile>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyG) {
+        return;
+    }
+    let Ok((transform, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let (grid_x, grid_y) = levels::world_to_grid(
+        transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    for (dx, dy) in [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)] {
+        let Some(entity) = index.get(grid_x + dx, grid_y + dy) else {
+            continue;
+        };
+        let Ok(mut tile) = tile_query.get_mut(entity) else {
+            continue;
+        };
+        let mut rng = rand::thread_rng();
+        // Plants are consumed; ground cover yields by luck instead
+        let (item, yield_count, consumes_tile) = match tile.terrain_type {
+            TerrainType::Shrub => (create_wood(), 1, true),
+            TerrainType::Tree => (create_wood(), 3, true),
+            TerrainType::Grass => {
+                if !rng.gen_bool(0.35) {
+                    warning.show("You find no ripe berries");
+                    return;
+                }
+                (item_from_id("berries").unwrap(), 1, false)
+            }
+            TerrainType::Coast => (item_from_id("driftwood").unwrap(), 1, false),
+            TerrainType::Water => {
+                if !rng.gen_bool(0.3) {
+                    warning.show("The fish aren't biting");
+                    return;
+                }
+                (item_from_id("raw_fish").unwrap(), 1, false)
+            }
+            _ => continue,
+        };
+        let added_weight = yield_count as f32 * item.properties.weight;
+        if inventory.current_weight() + added_weight > inventory.weight_limit {
+            warning.show("Your pack is too heavy to carry more");
+            return;
+        }
+        let name = item.name.clone();
+        for _ in 0..yield_count {
+            inventory.items.push(item.clone());
+        }
+        if consumes_tile {
+            tile.terrain_type = TerrainType::Grass;
+            tile.climbable = true;
+            tile.solid = false;
+            dirty.chunks.insert(terrain::chunk_of(tile.grid_x, tile.grid_y));
+        }
+        warning.show(format!("Gathered {yield_count} {name}"));
+        return;
+    }
+    warning.show("Nothing to gather here");
+}
+
+/// Cook the first raw food in the pack over a nearby fire pit with
+/// C. Building the pit itself goes through building mode (B).
+pub fn cook_food_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Inventory), With<Player>>,
+    structure_query: Query<(&Transform, &Structure), Without<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    let Ok((transform, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let at_fire = structure_query.iter().any(|(structure_transform, structure)| {
+        structure.structure_type == StructureType::FirePit
+            && position.distance(structure_transform.translation.truncate()) < TILE_SIZE * 1.5
+    });
+    if !at_fire {
+        warning.show("You need a lit fire pit to cook");
+        return;
+    }
+    let raw = inventory.items.iter().position(|item| {
+        item.item_type == ItemType::Food && !item.name.starts_with("Cooked")
+    });
+    let Some(raw_index) = raw else {
+        warning.show("Nothing raw left to cook");
+        return;
+    };
+    let raw_item = inventory.items.remove(raw_index);
+    let nutrition = raw_item.properties.nutrition * 1.5 + 10.0;
+    inventory.items.push(create_cooked_meal(nutrition));
+    warning.show(format!("Cooked {} into a hot meal", raw_item.name));
+}
+
+/// Mend worn gear with U: each repair-kit charge fully restores the
+/// most damaged equipped item. Field repairs need the calm of a camp
+/// structure or a trader nearby.
+pub fn repair_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Inventory, &mut EquippedItems), With<Player>>,
+    structure_query: Query<&Transform, (With<Structure>, Without<Player>)>,
+    npc_query: Query<(&Transform, &NPC), Without<Player>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyU) {
+        return;
+    }
+    let Ok((transform, mut inventory, mut equipped)) = player_query.get_single_mut() else {
+        return;
+    };
+    let position = transform.translation.truncate();
+    let at_camp = structure_query.iter().any(|structure_transform| {
+        position.distance(structure_transform.translation.truncate()) < TILE_SIZE * 2.0
+    });
+    let at_trader = npc_query.iter().any(|(npc_transform, npc)| {
+        npc.npc_type == NPCType::Trader
+            && position.distance(npc_transform.translation.truncate()) < TILE_SIZE * 2.0
+    });
+    if !at_camp && !at_trader {
+        warning.show("You need a camp or a trader to make repairs");
+        return;
+    }
+    let Some(kit_index) = inventory
+        .items
+        .iter()
+        .position(|item| item.name == "Repair Kit" && item.properties.durability > 0.0)
+    else {
+        warning.show("You don't have a repair kit");
+        return;
+    };
+    let Some(worn) = equipped
+        .slots_mut()
+        .into_iter()
+        .filter(|item| {
+            item.properties.max_durability > 0.0
+                && item.properties.durability < item.properties.max_durability
+        })
+        .min_by(|a, b| {
+            (a.properties.durability / a.properties.max_durability)
+                .total_cmp(&(b.properties.durability / b.properties.max_durability))
+        })
+    else {
+        warning.show("Nothing equipped needs repairing");
+        return;
+    };
+    worn.properties.durability = worn.properties.max_durability;
+    let name = worn.name.clone();
+    let kit = &mut inventory.items[kit_index];
+    kit.properties.durability -= 1.0;
+    let spent = kit.properties.durability <= 0.0;
+    if spent {
+        inventory.items.remove(kit_index);
+        warning.show(format!("Repaired {name} — the kit is used up"));
+    } else {
+        warning.show(format!("Repaired {name}"));
+    }
+}
+
+const STRUCTURES_SAVE_PATH: &str = "saves/structures.ron";
+const RUNES_SAVE_PATH: &str = "saves/runes.ron";
+
+/// The runes the player has read, by catalog id. Written to disk on
+/// every find so knowledge survives restarts.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct RuneCollection {
+    pub found: Vec<String>,
+}
+
+impl RuneCollection {
+    pub fn load() -> Self {
+        match fs::read_to_string(RUNES_SAVE_PATH) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(collection) => collection,
+                Err(e) => {
+                    warn!("Failed to parse {RUNES_SAVE_PATH}: {e}");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn has(&self, id: &str) -> bool {
+        self.found.iter().any(|found| found == id)
+    }
+
+    pub fn record(&mut self, id: &str) {
+        self.found.push(id.to_string());
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(e) = fs::create_dir_all("saves") {
+            error!("Failed to create saves directory: {e}");
+            return;
+        }
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(RUNES_SAVE_PATH, contents) {
+                    error!("Failed to write {RUNES_SAVE_PATH}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to serialize rune collection: {e}"),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BuiltStructure {
+    pub structure_type: StructureType,
+    pub position: (i32, i32),
+}
+
+/// Everything the player has placed, grouped by level name. Written to
+/// disk on every change so camps survive level switches and restarts.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct BuiltStructures {
+    pub by_level: HashMap<String, Vec<BuiltStructure>>,
+}
+
+impl BuiltStructures {
+    pub fn load() -> Self {
+        match fs::read_to_string(STRUCTURES_SAVE_PATH) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(built) => built,
+                Err(e) => {
+                    warn!("Failed to parse {STRUCTURES_SAVE_PATH}: {e}");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn record(&mut self, level: &str, structure_type: StructureType, position: (i32, i32)) {
+        self.by_level
+            .entry(level.to_string())
+            .or_default()
+            .push(BuiltStructure {
+                structure_type,
+                position,
+            });
+        self.save();
+    }
+
+    /// Forget whatever stood on this tile, e.g. after demolition.
+    pub fn remove_at(&mut self, level: &str, position: (i32, i32)) {
+        if let Some(entries) = self.by_level.get_mut(level) {
+            if let Some(index) = entries.iter().position(|entry| entry.position == position) {
+                entries.remove(index);
+                self.save();
+            }
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = fs::create_dir_all("saves") {
+            error!("Failed to create saves directory: {e}");
+            return;
+        }
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(STRUCTURES_SAVE_PATH, contents) {
+                    error!("Failed to write {STRUCTURES_SAVE_PATH}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to serialize structures: {e}"),
+        }
+    }
+}
+
+/// Spawn a structure's sprite and marker at a world position.
+pub fn spawn_structure(commands: &mut Commands, structure_type: StructureType, position: Vec2) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: structure_type.color(),
+                custom_size: Some(structure_type.sprite_size()),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.1),
+            ..default()
+        },
+        Structure { structure_type },
+    ));
+}
+
+/// The translucent placement preview shown in building mode.
+#[derive(Component)]
+pub struct BuildGhost {
+    pub structure_type: StructureType,
+    pub cursor: (i32, i32),
+}
+
+/// How far from the player a structure may be placed, in tiles.
+const BUILD_RANGE: i32 = 3;
+
+/// Open building mode (B by default) with a ghost preview on the
+/// player's tile.
+pub fn open_building_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    current_level: Res<CurrentLevel>,
+    player_query: Query<&Transform, With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !settings.bindings.just_pressed(&keyboard, Action::ToggleBuilding) {
+        return;
+    }
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let cursor =
+        levels::world_to_grid(transform.translation.truncate(), level.width, level.height);
+    let position = levels::calculate_tile_position(cursor.0, cursor.1, level.width, level.height);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: StructureType::FirePit.color().with_alpha(0.45),
+                custom_size: Some(StructureType::FirePit.sprite_size()),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.3),
+            ..default()
+        },
+        BuildGhost {
+            structure_type: StructureType::FirePit,
+            cursor,
+        },
+    ));
+    next_state.set(GameState::Building);
+}
+
+/// Drive the placement ghost: 1/2 pick the structure, WASD nudge it a
+/// tile at a time, Enter builds if the ground allows, Escape cancels.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn building_mode_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    mut built: ResMut<BuiltStructures>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    tile_query: Query<&TerrainTile>,
+    mut ghost_query: Query<(Entity, &mut BuildGhost, &mut Transform, &mut Sprite)>,
+    mut player_query: Query<(&Transform, &mut Inventory), (With<Player>, Without<BuildGhost>)>,
+    structure_query: Query<&Transform, (With<Structure>, Without<BuildGhost>, Without<Player>)>,
+) {
+    let Ok((ghost_entity, mut ghost, mut ghost_transform, mut sprite)) =
+        ghost_query.get_single_mut()
+    else {
+        return;
+    };
+    if keyboard.just_pressed(KeyCode::Escape) {
+        commands.entity(ghost_entity).despawn();
+        next_state.set(GameState::Climbing);
+        return;
+    }
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok((player_transform, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Digit1) {
+        ghost.structure_type = StructureType::FirePit;
+    }
+    if keyboard.just_pressed(KeyCode::Digit2) {
+        ghost.structure_type = StructureType::Shelter;
+    }
+
+    let player_tile = levels::world_to_grid(
+        player_transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let mut cursor = ghost.cursor;
+    if keyboard.just_pressed(KeyCode::KeyA) {
+        cursor.0 -= 1;
+    }
+    if keyboard.just_pressed(KeyCode::KeyD) {
+        cursor.0 += 1;
+    }
+    if keyboard.just_pressed(KeyCode::KeyS) {
+        cursor.1 -= 1;
+    }
+    if keyboard.just_pressed(KeyCode::KeyW) {
+        cursor.1 += 1;
+    }
+    cursor.0 = cursor
+        .0
+        .clamp(player_tile.0 - BUILD_RANGE, player_tile.0 + BUILD_RANGE)
+        .clamp(0, level.width - 1);
+    cursor.1 = cursor
+        .1
+        .clamp(player_tile.1 - BUILD_RANGE, player_tile.1 + BUILD_RANGE)
+        .clamp(0, level.height - 1);
+    ghost.cursor = cursor;
+    let position = levels::calculate_tile_position(cursor.0, cursor.1, level.width, level.height);
+    ghost_transform.translation.x = position.x;
+    ghost_transform.translation.y = position.y;
+
+    let occupied = structure_query.iter().any(|structure_transform| {
+        levels::world_to_grid(
+            structure_transform.translation.truncate(),
+            level.width,
+            level.height,
+        ) == cursor
+    });
+    let ground_ok = index
+        .get(cursor.0, cursor.1)
+        .and_then(|entity| tile_query.get(entity).ok())
+        .is_some_and(|tile| {
+            matches!(
+                tile.terrain_type,
+                TerrainType::Grass | TerrainType::Soil | TerrainType::Snow | TerrainType::Coast
+            ) && tile.stability >= 0.8
+        });
+    let valid = ground_ok && !occupied;
+    sprite.color = if valid {
+        ghost.structure_type.color().with_alpha(0.45)
+    } else {
+        Color::srgb(0.85, 0.2, 0.2).with_alpha(0.45)
+    };
+    sprite.custom_size = Some(ghost.structure_type.sprite_size());
+
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    if !valid {
+        warning.show("Can't build there — it needs flat, stable ground");
+        return;
+    }
+    let cost = ghost.structure_type.wood_cost().unwrap_or(0);
+    let wood: Vec<usize> = inventory
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.name == "Wood")
+        .map(|(item_index, _)| item_index)
+        .take(cost)
+        .collect();
+    if wood.len() < cost {
+        warning.show(format!("You need {cost} wood to build that"));
+        return;
+    }
+    for item_index in wood.into_iter().rev() {
+        inventory.items.remove(item_index);
+    }
+    spawn_structure(&mut commands, ghost.structure_type, position.truncate());
+    built.record(&current_level.name, ghost.structure_type, cursor);
+    commands.entity(ghost_entity).despawn();
+    next_state.set(GameState::Climbing);
+}
+
+/// Make sure everything recorded as built on this level is standing.
+pub fn spawn_built_structures_system(
+    mut commands: Commands,
+    built: Res<BuiltStructures>,
+    current_level: Res<CurrentLevel>,
+    structure_query: Query<&Transform, With<Structure>>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Some(entries) = built.by_level.get(&current_level.name) else {
+        return;
+    };
+    if structure_query.iter().count() >= entries.len() {
+        return;
+    }
+    let existing: Vec<(i32, i32)> = structure_query
+        .iter()
+        .map(|transform| {
+            levels::world_to_grid(transform.translation.truncate(), level.width, level.height)
+        })
+        .collect();
+    for entry in entries {
+        if existing.contains(&entry.position) {
+            continue;
+        }
+        let position = levels::calculate_tile_position(
+            entry.position.0,
+            entry.position.1,
+            level.width,
+            level.height,
+        );
+        spawn_structure(&mut commands, entry.structure_type, position.truncate());
+    }
+}
+
+/// Wind this strong can knock an unanchored climber down in a storm.
+const KNOCKDOWN_WIND_SPEED: f32 = 22.0;
+
+/// How exposed to the wind the ground underfoot is.
+fn wind_exposure(biome: Option<Biome>) -> f32 {
+    match biome {
+        Some(Biome::Glacier) => 1.6,
+        Some(Biome::Alpine) => 1.3,
+        Some(Biome::Forest) => 0.4,
+        _ => 1.0,
+    }
+}
+
+/// Push the player downwind, charge stamina for fighting the gusts,
+/// and knock them off their feet in storm-force wind without an anchor.
+#[allow(clippy::too_many_arguments)]
+pub fn wind_push_system(
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    terrain_query: Query<&TerrainTile>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&mut Transform, &mut Stamina, Option<&Climbing>), With<Player>>,
+) {
+    let Ok((mut transform, mut stamina, climbing)) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    if weather.wind_speed < 6.0 {
+        return;
+    }
+    let position = transform.translation.truncate();
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    let exposure = wind_exposure(
+        index
+            .get(grid_x, grid_y)
+            .and_then(|entity| terrain_query.get(entity).ok())
+            .map(|tile| tile.biome),
+    );
+    let dt = time.delta_seconds();
+    let push = weather.wind_direction * weather.wind_speed * exposure * 1.1 * dt;
+    let target = position + push;
+    if !position_blocked(target, &current_level, &terrain_query) {
+        transform.translation.x = target.x;
+        transform.translation.y = target.y;
+    }
+    // Staying upright in a gale is work in itself
+    if weather.wind_speed > 14.0 {
+        stamina.current = (stamina.current - exposure * 1.5 * dt).max(0.0);
+    }
+    // Storm gusts can flatten an unanchored climber outright
+    let anchored = climbing.is_some();
+    if weather.current_weather == Weather::Storm
+        && weather.wind_speed * exposure > KNOCKDOWN_WIND_SPEED
+        && !anchored
+        && rand::thread_rng().gen_bool((0.04 * dt as f64).min(1.0))
+    {
+        let tumble = position + weather.wind_direction * TILE_SIZE;
+        if !position_blocked(tumble, &current_level, &terrain_query) {
+            transform.translation.x = tumble.x;
+            transform.translation.y = tumble.y;
+        }
+        stamina.current = (stamina.current - 20.0).max(0.0);
+        warning.show("A gust knocks you off your feet!");
+    }
+}
+
+/// Below this light level you can't read the terrain without a lamp.
+const DARK_THRESHOLD: f32 = 0.25;
+
+/// Warm glow drawn around the player while a lamp burns.
+#[derive(Component)]
+pub struct LightHalo;
+
+/// Burn an equipped headlamp or torch when it's dark (night, caves),
+/// drawing a light radius and draining its charge; without one the
+/// player stumbles blindly and takes scrapes.
+#[allow(clippy::too_many_arguments)]
+pub fn light_source_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_time: Res<GameTime>,
+    current_level: Res<CurrentLevel>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut EquippedItems, &mut Health), With<Player>>,
+    mut halo_query: Query<(Entity, &mut Transform, &mut Sprite), (With<LightHalo>, Without<Player>)>,
+    mut stumble_clock: Local<f32>,
+) {
+    let Ok((player_transform, mut equipped, mut health)) = player_query.get_single_mut() else {
+        return;
+    };
+    // Interiors are dark at any hour
+    let underground = matches!(current_level.name.as_str(), "Cave" | "Crevasse");
+    let dark = underground || game_time.light_level() < DARK_THRESHOLD;
+    if !dark {
+        for (entity, _, _) in halo_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        *stumble_clock = 0.0;
+        return;
+    }
+
+    // Find a burning light: a headlamp on the head or a torch in the
+    // backpack, whichever still has charge
+    let dt = time.delta_seconds();
+    let equipped = &mut *equipped;
+    let lit = [
+        (&mut equipped.head, 0.17, 90.0),
+        (&mut equipped.backpack, 0.5, 130.0),
+    ]
+    .into_iter()
+    .find_map(|(slot, burn_rate, radius)| {
+        let item = slot.as_mut()?;
+        if !matches!(item.name.as_str(), "Headlamp" | "Torch") || item.properties.durability <= 0.0
+        {
+            return None;
+        }
+        item.properties.durability = (item.properties.durability - burn_rate * dt).max(0.0);
+        if item.properties.durability == 0.0 {
+            return None; // just guttered out
+        }
+        Some(radius)
+    });
+
+    match lit {
+        Some(radius) => {
+            *stumble_clock = 0.0;
+            let position = player_transform.translation;
+            if let Ok((_, mut transform, mut sprite)) = halo_query.get_single_mut() {
+                transform.translation.x = position.x;
+                transform.translation.y = position.y;
+                sprite.custom_size = Some(Vec2::splat(radius * 2.0));
+            } else {
+                commands.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::srgba(1.0, 0.9, 0.6, 0.22),
+                            custom_size: Some(Vec2::splat(radius * 2.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(position.x, position.y, 8.5),
+                        ..default()
+                    },
+                    LightHalo,
+                ));
+            }
+        }
+        None => {
+            for (entity, _, _) in halo_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            *stumble_clock += dt;
+            if *stumble_clock > 5.0 {
+                *stumble_clock = 0.0;
+                warning.show("You can barely see the holds — you need a light");
+                if rand::thread_rng().gen_bool(0.5) {
+                    health.current -= 2.0;
+                }
+            }
+        }
+    }
+}
+
+/// Darkness sprite glued to the camera; alpha follows the hour.
+#[derive(Component)]
+pub struct NightOverlay;
+
+/// Tint the world darker as the sun goes down. The curve bottoms out
+/// well short of pitch black at dawn/dusk but makes true night dark
+/// enough that a light source matters.
+/// The red edge-of-vision wash once health drops below this share.
+const VIGNETTE_THRESHOLD: f32 = 0.25;
+
+#[derive(Component)]
+pub struct VignetteOverlay;
+
+/// Pulse a red wash over the screen while health is critical, so the
+/// danger reads even with the HUD ignored.
+pub fn low_health_vignette_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    player_query: Query<&Health, With<Player>>,
+    camera_query: Query<&Transform, (With<Camera>, Without<VignetteOverlay>)>,
+    mut overlay_query: Query<(Entity, &mut Transform, &mut Sprite), With<VignetteOverlay>>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let severity = player_query
+        .get_single()
+        .map(|health| 1.0 - (health.current / health.max / VIGNETTE_THRESHOLD).clamp(0.0, 1.0))
+        .unwrap_or(0.0);
+    // A slow heartbeat pulse, harder the closer to death
+    let pulse = 0.75 + 0.25 * (time.elapsed_seconds() * 4.0).sin();
+    let alpha = severity * 0.45 * pulse;
+    match overlay_query.get_single_mut() {
+        Ok((entity, mut transform, mut sprite)) => {
+            if severity <= 0.0 {
+                commands.entity(entity).despawn();
+                return;
+            }
+            transform.translation.x = camera_transform.translation.x;
+            transform.translation.y = camera_transform.translation.y;
+            sprite.color = Color::srgba(0.5, 0.0, 0.0, alpha);
+        }
+        Err(_) => {
+            if severity <= 0.0 {
+                return;
+            }
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgba(0.5, 0.0, 0.0, alpha),
+                        custom_size: Some(Vec2::new(2000.0, 1400.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(
+                        camera_transform.translation.x,
+                        camera_transform.translation.y,
+                        9.0,
+                    ),
+                    ..default()
+                },
+                VignetteOverlay,
+            ));
+        }
+    }
+}
+
+pub fn day_night_overlay_system(
+    mut commands: Commands,
+    game_time: Res<GameTime>,
+    camera_query: Query<&Transform, (With<Camera>, Without<NightOverlay>)>,
+    mut overlay_query: Query<(&mut Transform, &mut Sprite), With<NightOverlay>>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let light = game_time.light_level();
+    let darkness = (1.0 - light).powf(1.5) * 0.85;
+    // Cold blue cast at night rather than flat black
+    let color = Color::srgba(0.02, 0.03, 0.08, darkness);
+    if let Ok((mut transform, mut sprite)) = overlay_query.get_single_mut() {
+        transform.translation.x = camera_transform.translation.x;
+        transform.translation.y = camera_transform.translation.y;
+        sprite.color = color;
+    } else {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::new(2000.0, 1400.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    camera_transform.translation.x,
+                    camera_transform.translation.y,
+                    8.0,
+                ),
+                ..default()
+            },
+            NightOverlay,
+        ));
+    }
+}
+
+pub fn update_game_time(time: Res<Time>, mut game_time: ResMut<GameTime>) {
+    game_time.hour += time.delta_seconds() * game_time.time_scale;
+    if game_time.hour >= 24.0 {
+        game_time.hour -= 24.0;
+        game_time.day += 1;
+    }
+}
+
+/// Which part of the day an hour falls in.
+pub fn time_of_day_for_hour(hour: f32) -> TimeOfDay {
+    match hour {
+        h if (5.0..8.0).contains(&h) => TimeOfDay::Dawn,
+        h if (8.0..18.0).contains(&h) => TimeOfDay::Day,
+        h if (18.0..21.0).contains(&h) => TimeOfDay::Dusk,
+        _ => TimeOfDay::Night,
+    }
+}
+
+/// Keep the [`TimeOfDay`] state in step with the clock so systems can
+/// hook `OnEnter(TimeOfDay::Night)` and friends.
+pub fn time_of_day_system(
+    game_time: Res<GameTime>,
+    state: Res<State<TimeOfDay>>,
+    mut next_state: ResMut<NextState<TimeOfDay>>,
+) {
+    let wanted = time_of_day_for_hour(game_time.hour);
+    if *state.get() != wanted {
+        next_state.set(wanted);
+    }
+}
+
+pub fn on_night_falls(mut warning: ResMut<WarningMessage>) {
+    warning.show("Night falls over the mountain");
+}
+
+pub fn on_dawn_breaks(mut warning: ResMut<WarningMessage>) {
+    warning.show("Dawn breaks");
+}
+
+/// Make sure the animals the level describes are in the world; counts
+/// are compared per level so herds thinned by a reload come back.
+pub fn spawn_wildlife_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    wildlife_query: Query<&Wildlife>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let wanted: u32 = level.wildlife.iter().map(|spawn| spawn.count).sum();
+    if wildlife_query.iter().count() as u32 >= wanted {
+        return;
+    }
+    let mut rng = rand::thread_rng();
+    for spawn in &level.wildlife {
+        let present = wildlife_query
+            .iter()
+            .filter(|wildlife| {
+                wildlife.species == spawn.species
+                    && wildlife
+                        .spawn_point
+                        .distance(Vec2::new(spawn.position.0, spawn.position.1))
+                        < 1.0
+            })
+            .count() as u32;
+        for _ in present..spawn.count {
+            levels::spawn_wildlife_member(&mut commands, spawn, &mut rng);
+        }
+    }
+}
+
+/// Animals move by their species profile: the shy flee, flockers pull
+/// toward their own kind, eagles circle aloft, and everyone else
+/// ambles between spots near home.
+pub fn wildlife_system(
+    time: Res<Time>,
+    player_query: Query<&Transform, (With<Player>, Without<Wildlife>)>,
+    mut wildlife_query: Query<(&mut Transform, &mut Wildlife)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let mut rng = rand::thread_rng();
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_seconds();
+    // Snapshot positions so flocking can see the whole herd
+    let herd: Vec<(WildlifeSpecies, Vec2)> = wildlife_query
+        .iter()
+        .map(|(transform, wildlife)| (wildlife.species, transform.translation.truncate()))
+        .collect();
+    for (mut transform, mut wildlife) in wildlife_query.iter_mut() {
+        // Predators stalk instead of fleeing; see the predator system
+        if wildlife.aggression > 0.0 && !crate::components::species_profile(wildlife.species).circles
+        {
+            continue;
+        }
+        let profile = crate::components::species_profile(wildlife.species);
+        let pos = transform.translation.truncate();
+
+        // Eagles ride a slow circle over their eyrie
+        if profile.circles {
+            let offset = pos - wildlife.spawn_point;
+            let angle = offset.y.atan2(offset.x) + 0.8 * dt;
+            let target =
+                wildlife.spawn_point + Vec2::from_angle(angle) * profile.roam_radius;
+            let step = (target - pos).normalize_or_zero() * profile.roam_speed * dt;
+            transform.translation.x += step.x;
+            transform.translation.y += step.y;
+            continue;
+        }
+
+        let distance = pos.distance(player_pos);
+        if distance < wildlife.flee_distance && distance > 0.1 {
+            let away = (pos - player_pos).normalize();
+            transform.translation.x += away.x * 80.0 * dt;
+            transform.translation.y += away.y * 80.0 * dt;
+            wildlife.roam_target = None;
+            continue;
+        }
+        // Undisturbed, amble between spots near home
+        let reached = wildlife
+            .roam_target
+            .is_none_or(|target| pos.distance(target) < 4.0);
+        if reached {
+            let radius = profile.roam_radius;
+            wildlife.roam_target = Some(
+                wildlife.spawn_point
+                    + Vec2::new(rng.gen_range(-radius..radius), rng.gen_range(-radius..radius)),
+            );
+        }
+        let mut step = Vec2::ZERO;
+        if let Some(target) = wildlife.roam_target {
+            step += (target - pos).normalize_or_zero() * profile.roam_speed;
+        }
+        // Flockers drift toward the middle of their own kind
+        if profile.cohesion > 0.0 {
+            let mut centroid = Vec2::ZERO;
+            let mut count = 0;
+            for &(species, other) in &herd {
+                if species == wildlife.species && other.distance(pos) < 120.0 && other != pos {
+                    centroid += other;
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                let pull = (centroid / count as f32 - pos).normalize_or_zero();
+                step += pull * profile.cohesion * 15.0;
+            }
+        }
+        transform.translation.x += step.x * dt;
+        transform.translation.y += step.y * dt;
+    }
+}
+
+/// How close a predator has to be before it commits to the stalk.
+const PREDATOR_AGGRO_RANGE: f32 = 150.0;
+/// Predators keep away from an open flame.
+const TORCH_DETERRENT_FACTOR: f32 = 0.4;
+/// Below this fraction of their health, predators give up for good.
+const PREDATOR_RETREAT_FRACTION: f32 = 0.3;
+
+/// Wolves and bears stalk, bite, and can be driven off: a torch in the
+/// pack keeps them at a distance, a swing of the ice axe hurts and
+/// scares them, and a badly wounded animal limps home and stays there.
+pub fn predator_attack_system(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    time_of_day: Res<State<TimeOfDay>>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<
+        (&Transform, &mut Health, &Inventory, &EquippedItems),
+        (With<Player>, Without<Wildlife>),
+    >,
+    mut wildlife_query: Query<(&mut Transform, &mut Wildlife)>,
+    mut last_damage: ResMut<LastDamage>,
+) {
+    let Ok((player_transform, mut health, inventory, equipped)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let dt = time.delta_seconds();
+    let torch_carried = inventory.items.iter().any(|item| item.name == "Torch");
+    let axe_swing = settings.bindings.just_pressed(&keyboard, Action::UseTool)
+        && equipped
+            .tool
+            .as_ref()
+            .is_some_and(|tool| matches!(tool.item_type, ItemType::Tool(ToolType::IceAxe)));
+    for (mut transform, mut wildlife) in wildlife_query.iter_mut() {
+        if wildlife.aggression <= 0.0 {
+            continue;
+        }
+        wildlife.scared = (wildlife.scared - dt).max(0.0);
+        wildlife.attack_cooldown = (wildlife.attack_cooldown - dt).max(0.0);
+        let pos = transform.translation.truncate();
+        let distance = pos.distance(player_pos);
+
+        // A swing of the axe connects with anything in reach
+        if axe_swing && distance < TILE_SIZE * 1.8 {
+            let strength = equipped
+                .tool
+                .as_ref()
+                .map(|tool| tool.properties.strength)
+                .unwrap_or(5.0);
+            wildlife.health -= strength;
+            wildlife.scared = 6.0;
+            warning.show(format!("You drive the {:?} back!", wildlife.species));
+        }
+
+        let wounded = wildlife.health
+            < wildlife.species.max_health() * PREDATOR_RETREAT_FRACTION;
+        if wounded || wildlife.scared > 0.0 {
+            // Retreat toward home, faster while freshly scared
+            let speed = if wildlife.scared > 0.0 { 100.0 } else { 60.0 };
+            let step = (wildlife.spawn_point - pos).normalize_or_zero() * speed * dt;
+            transform.translation.x += step.x;
+            transform.translation.y += step.y;
+            continue;
+        }
+
+        // Night hunters roam harmlessly while the sun is up
+        let profile = crate::components::species_profile(wildlife.species);
+        if profile.nocturnal
+            && !matches!(time_of_day.get(), TimeOfDay::Night | TimeOfDay::Dusk)
+        {
+            continue;
+        }
+        let range = if torch_carried {
+            PREDATOR_AGGRO_RANGE * TORCH_DETERRENT_FACTOR
+        } else {
+            PREDATOR_AGGRO_RANGE
+        };
+        if distance > range {
+            continue;
+        }
+        if distance > 18.0 {
+            let speed = match wildlife.species {
+                WildlifeSpecies::Wolf => 70.0,
+                WildlifeSpecies::Bear => 50.0,
+                _ => 60.0,
+            };
+            let step = (player_pos - pos).normalize() * speed * dt;
+            transform.translation.x += step.x;
+            transform.translation.y += step.y;
+        } else if wildlife.attack_cooldown <= 0.0 {
+            health.current -= wildlife.attack_damage * settings.difficulty.damage_multiplier();
+            last_damage.note(
+                format!("a {} attack", wildlife.species.name()),
+                time.elapsed_seconds_f64(),
+            );
+            wildlife.attack_cooldown = 1.2;
+            // An eagle stoops once and wheels away
+            if wildlife.species == WildlifeSpecies::Eagle {
+                wildlife.scared = 8.0;
+            }
+            warning.show(format!("The {:?} bites you!", wildlife.species));
+        }
+    }
+}
+
+/// What hunting a grazer costs in standing: the farms hear about it.
+const HUNTING_REPUTATION_COST: i32 = -2;
+
+/// Hunt grazing animals with an axe swing. A felled sheep or horse
+/// drops raw meat and a pelt where it stood, and word of the killing
+/// travels — hunting is legal here, but nobody loves a poacher.
+pub fn hunt_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut warning: ResMut<WarningMessage>,
+    mut reputation: ResMut<crate::dialogue::PlayerReputation>,
+    player_query: Query<(&Transform, &EquippedItems), (With<Player>, Without<Wildlife>)>,
+    mut wildlife_query: Query<(Entity, &Transform, &mut Wildlife)>,
+) {
+    if !settings.bindings.just_pressed(&keyboard, Action::UseTool) {
+        return;
+    }
+    let Ok((player_transform, equipped)) = player_query.get_single() else {
+        return;
+    };
+    let Some(tool) = equipped.tool.as_ref() else {
+        return;
+    };
+    if !matches!(tool.item_type, ItemType::Tool(ToolType::IceAxe)) {
+        return;
+    }
+    let player_pos = player_transform.translation.truncate();
+    for (entity, transform, mut wildlife) in wildlife_query.iter_mut() {
+        // Predators defend themselves; that fight lives elsewhere
+        if wildlife.aggression > 0.0 {
+            continue;
+        }
+        let pos = transform.translation.truncate();
+        if pos.distance(player_pos) >= TILE_SIZE * 1.8 {
+            continue;
+        }
+        wildlife.health -= tool.properties.strength;
+        if wildlife.health > 0.0 {
+            wildlife.scared = 6.0;
+            wildlife.roam_target = None;
+            warning.show(format!("The {:?} bolts!", wildlife.species));
+            return;
+        }
+        spawn_item_pickup(&mut commands, item_from_id("raw_meat").unwrap(), pos);
+        spawn_item_pickup(
+            &mut commands,
+            item_from_id("raw_meat").unwrap(),
+            pos + Vec2::new(TILE_SIZE * 0.5, 0.0),
+        );
+        if wildlife.species == WildlifeSpecies::Sheep {
+            spawn_item_pickup(
+                &mut commands,
+                item_from_id("pelt").unwrap(),
+                pos + Vec2::new(-TILE_SIZE * 0.5, 0.0),
+            );
+        }
+        commands.entity(entity).despawn_recursive();
+        reputation.adjust(HUNTING_REPUTATION_COST);
+        warning.show(format!(
+            "You fell the {:?}. The farms won't thank you for it",
+            wildlife.species
+        ));
+        return;
+    }
+}
+
+/// Start a conversation with the interact key (E by default) near an
+/// NPC. The
+/// tree comes from the NPC's dialogue file; NPCs whose file is missing
+/// or unreadable fall back to the stock conversation for their type.
+pub fn npc_proximity_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    library: Res<DialogueLibrary>,
+    player_query: Query<(&Transform, &Facing), With<Player>>,
+    npc_query: Query<(Entity, &Transform, &NPC)>,
+    mut memory: ResMut<ConversationMemory>,
+    mut dialogue: ResMut<ActiveDialogue>,
+) {
+    if !settings.bindings.just_pressed(&keyboard, Action::Interact) || dialogue.tree.is_some() {
+        return;
+    }
+    let Ok((player_transform, facing)) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    // With several NPCs in range, talk to the one the player faces
+    let target = npc_query
+        .iter()
+        .filter_map(|(entity, transform, npc)| {
+            let offset = transform.translation.truncate() - player_pos;
+            (offset.length() < TILE_SIZE * 2.0).then(|| {
+                let alignment = offset.normalize_or_zero().dot(facing.direction);
+                (entity, npc, alignment)
+            })
+        })
+        .max_by(|a, b| a.2.total_cmp(&b.2));
+    let Some((entity, npc, _)) = target else {
+        return;
+    };
+    let tree = library.get(&npc.dialogue_file).cloned().unwrap_or_else(|| {
+        match npc.npc_type {
+            NPCType::Guide | NPCType::Climber => create_guide_dialogue(),
+            NPCType::Trader | NPCType::Viking => create_trader_dialogue(),
+            NPCType::Hermit | NPCType::Mage => create_hermit_dialogue(),
+        }
+    });
+    // Old acquaintances get the appropriate greeting
+    let remembered = memory.remember(&npc.name).clone();
+    dialogue.npc = Some(entity);
+    dialogue.current_node = crate::dialogue::entry_node_for(&tree, &remembered);
+    dialogue.tree = Some(tree);
+    memory.remember(&npc.name).met = true;
+    memory.save();
+    info!("{}: talking", npc.name);
+}
+
+/// Make sure NPCs defined by the current level exist in the world.
+pub fn spawn_npcs_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    npc_query: Query<&NPC>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    for spawn in &level.npcs {
+        if npc_query.iter().any(|npc| npc.name == spawn.name) {
+            continue;
+        }
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgb(0.8, 0.6, 0.2),
+                    custom_size: Some(Vec2::new(24.0, 30.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(spawn.position.0, spawn.position.1, 1.0),
+                ..default()
+            },
+            NPC {
+                npc_type: spawn.npc_type,
+                name: spawn.name.clone(),
+                behavior: NpcBehaviorType::Wander,
+                home_position: Vec2::new(spawn.position.0, spawn.position.1),
+                dialogue_file: spawn.dialogue_file.clone(),
+                wander_target: None,
+            },
+            Health {
+                current: 100.0,
+                max: 100.0,
+            },
+            Stamina {
+                current: 100.0,
+                max: 100.0,
+            },
+            Inventory {
+                items: npc_pack(spawn.npc_type),
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+/// What an NPC is meant to be doing at this hour. The table is by
+/// type: traders keep shop hours, climbers push for the summit while
+/// the light lasts, hermits and mages keep odd ones.
+fn scheduled_behavior(npc_type: NPCType, hour: f32) -> NpcBehaviorType {
+    match npc_type {
+        NPCType::Trader => match hour {
+            h if (8.0..20.0).contains(&h) => NpcBehaviorType::Patrol,
+            _ => NpcBehaviorType::Stationary,
+        },
+        NPCType::Guide => match hour {
+            h if (6.0..18.0).contains(&h) => NpcBehaviorType::Wander,
+            _ => NpcBehaviorType::Stationary,
+        },
+        NPCType::Climber => match hour {
+            h if (5.0..16.0).contains(&h) => NpcBehaviorType::Ascend,
+            h if (16.0..21.0).contains(&h) => NpcBehaviorType::ReturnHome,
+            _ => NpcBehaviorType::Stationary,
+        },
+        NPCType::Viking => match hour {
+            h if (7.0..22.0).contains(&h) => NpcBehaviorType::Wander,
+            _ => NpcBehaviorType::Stationary,
+        },
+        NPCType::Hermit | NPCType::Mage => match hour {
+            h if (10.0..15.0).contains(&h) => NpcBehaviorType::Wander,
+            _ => NpcBehaviorType::Stationary,
+        },
+    }
+}
+
+/// Point every NPC at whatever the schedule says for this hour. Party
+/// members and anyone already walking home are left to it.
+pub fn npc_schedule_system(
+    game_time: Res<GameTime>,
+    mut npc_query: Query<&mut NPC, Without<Injured>>,
+) {
+    for mut npc in npc_query.iter_mut() {
+        if matches!(
+            npc.behavior,
+            NpcBehaviorType::Follow | NpcBehaviorType::ReturnHome
+        ) {
+            continue;
+        }
+        let wanted = scheduled_behavior(npc.npc_type, game_time.hour);
+        if npc.behavior != wanted {
+            npc.behavior = wanted;
+        }
+    }
+}
+
+/// Run every NPC's behavior tree and apply the resulting movement,
+/// sliding along impassable terrain the same way the player does.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_npc_behavior(
+    time: Res<Time>,
+    weather: Res<State<Weather>>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    terrain_query: Query<&TerrainTile>,
+    hazard_query: Query<&Hazardous, With<TerrainTile>>,
+    player_query: Query<&Transform, (With<Player>, Without<NPC>)>,
+    mut npc_query: Query<(&mut Transform, &mut NPC)>,
+) {
+    let mut rng = rand::thread_rng();
+    let tree = crate::ai::npc_tree();
+    let storming = *weather.get() == Weather::Storm;
+    let player = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    for (mut transform, mut npc) in npc_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let mut ctx = crate::ai::AiContext {
+            position,
+            home: npc.home_position,
+            player,
+            hazard: nearest_hazard(position, &current_level, &index, &hazard_query),
+            storming,
+            scheduled: npc.behavior,
+            wander_target: npc.wander_target,
+        };
+        let Some(task) = tree.evaluate(&ctx) else {
+            continue;
+        };
+        let Some(velocity) = crate::ai::task_velocity(task, &mut ctx, &mut rng) else {
+            npc.wander_target = ctx.wander_target;
+            continue;
+        };
+        npc.wander_target = ctx.wander_target;
+        let step = velocity * time.delta_seconds();
+        // Axis-by-axis like the player, so NPCs slide along walls
+        let x_target = position + Vec2::new(step.x, 0.0);
+        if !position_blocked(x_target, &current_level, &terrain_query) {
+            transform.translation.x = x_target.x;
+        }
+        let y_target = transform.translation.truncate() + Vec2::new(0.0, step.y);
+        if !position_blocked(y_target, &current_level, &terrain_query) {
+            transform.translation.y = y_target.y;
+        }
+        // Reaching home after a dismissal hands control back to the
+        // schedule
+        if npc.behavior == NpcBehaviorType::ReturnHome
+            && transform.translation.truncate().distance(npc.home_position) <= 4.0
+        {
+            npc.behavior = NpcBehaviorType::Stationary;
+        }
+    }
+}
+
+/// The closest hazardous tile within two tiles of `position`, if any.
+fn nearest_hazard(
+    position: Vec2,
+    current_level: &CurrentLevel,
+    index: &TerrainIndex,
+    hazard_query: &Query<&Hazardous, With<TerrainTile>>,
+) -> Option<Vec2> {
+    let level = current_level.definition.as_ref()?;
+    let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+    let mut nearest: Option<Vec2> = None;
+    for dx in -2..=2 {
+        for dy in -2..=2 {
+            let Some(entity) = index.get(grid_x + dx, grid_y + dy) else {
+                continue;
+            };
+            if hazard_query.get(entity).is_err() {
+                continue;
+            }
+            let tile = levels::calculate_tile_position(
+                grid_x + dx,
+                grid_y + dy,
+                level.width,
+                level.height,
+            )
+            .truncate();
+            if nearest.is_none_or(|known| position.distance(tile) < position.distance(known)) {
+                nearest = Some(tile);
+            }
+        }
+    }
+    nearest
+}
+
+/// Turn bought knowledge into things on the map: hazard warnings over
+/// dangerous tiles, and revealed caches dropped in as pickups.
+pub fn secret_knowledge_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    index: Res<TerrainIndex>,
+    mut knowledge: ResMut<crate::levels::LevelKnowledge>,
+    hazard_query: Query<&Hazardous, With<TerrainTile>>,
+    marker_query: Query<Entity, With<SecretMarker>>,
+    mut marked_for: Local<Option<String>>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let secrets = knowledge.for_level(&level.name);
+    if secrets.hazards && marked_for.as_deref() != Some(level.name.as_str()) {
+        *marked_for = Some(level.name.clone());
+        for entity in marker_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        for tile in &level.terrain {
+            let Some(entity) = index.get(tile.x, tile.y) else {
+                continue;
+            };
+            if hazard_query.get(entity).is_err() {
+                continue;
+            }
+            let position =
+                levels::calculate_tile_position(tile.x, tile.y, level.width, level.height);
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgba(0.9, 0.2, 0.2, 0.35),
+                        custom_size: Some(Vec2::splat(TILE_SIZE * 0.5)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(position.x, position.y, 0.95),
+                    ..default()
+                },
+                SecretMarker,
+            ));
+        }
+    }
+    if secrets.caches && !secrets.caches_spawned {
+        secrets.caches_spawned = true;
+        let mut rng = rand::thread_rng();
+        let spots: Vec<_> = level
+            .terrain
+            .iter()
+            .filter(|tile| !tile.terrain_type.solid())
+            .collect();
+        for id in ["mineral", "rope"] {
+            let Some(item) = item_from_id(id) else {
+                continue;
+            };
+            if spots.is_empty() {
+                break;
+            }
+            let spot = spots[rng.gen_range(0..spots.len())];
+            let position =
+                levels::calculate_tile_position(spot.x, spot.y, level.width, level.height)
+                    .truncate();
+            spawn_item_pickup(&mut commands, item, position);
+        }
+    }
+}
+
+/// One rescue roll per level, so a rescued climber stays rescued.
+#[derive(Resource, Default)]
+pub struct RescueState {
+    pub rolled_for: Option<String>,
+}
+
+/// Chance a level hides a climber in trouble.
+const RESCUE_CHANCE: f64 = 0.5;
+/// What getting someone off the mountain pays.
+const RESCUE_REWARD: f32 = 80.0;
+const RESCUE_REPUTATION: i32 = 5;
+
+/// Once per level, maybe strand an injured climber somewhere steep.
+pub fn rescue_spawn_system(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    mut state: ResMut<RescueState>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    if state.rolled_for.as_deref() == Some(level.name.as_str()) {
+        return;
+    }
+    state.rolled_for = Some(level.name.clone());
+    let mut rng = rand::thread_rng();
+    if !rng.gen_bool(RESCUE_CHANCE) {
+        return;
+    }
+    // Strand them on hard ground, where climbers actually get stuck
+    let spots: Vec<_> = level
+        .terrain
+        .iter()
+        .filter(|tile| tile.difficulty >= 6.0 && !tile.terrain_type.solid())
+        .collect();
+    let Some(spot) = spots.get(rng.gen_range(0..spots.len().max(1))) else {
+        return;
+    };
+    let position =
+        levels::calculate_tile_position(spot.x, spot.y, level.width, level.height).truncate();
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::srgb(0.85, 0.3, 0.25),
+                custom_size: Some(Vec2::new(24.0, 30.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.0),
+            ..default()
+        },
+        NPC {
+            npc_type: NPCType::Climber,
+            name: "Stranded climber".to_string(),
+            behavior: NpcBehaviorType::Stationary,
+            home_position: position,
+            dialogue_file: String::new(),
+            wander_target: None,
+        },
+        Health {
+            current: 30.0,
+            max: 100.0,
+        },
+        Stamina {
+            current: 10.0,
+            max: 100.0,
+        },
+        Injured { treated: false },
+    ));
+}
+
+/// The rescue itself: F with a first aid kit patches them up, after
+/// which they follow; walking them back to the level start pays out.
+#[allow(clippy::too_many_arguments)]
+pub fn rescue_system(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    current_level: Res<CurrentLevel>,
+    mut reputation: ResMut<crate::dialogue::PlayerReputation>,
+    mut warning: ResMut<WarningMessage>,
+    mut player_query: Query<(&Transform, &mut Inventory, &mut Money, &mut Experience), With<Player>>,
+    mut injured_query: Query<(Entity, &Transform, &mut NPC, &mut Injured), Without<Player>>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok((player_transform, mut inventory, mut money, mut experience)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    let safe = levels::calculate_tile_position(
+        level.start_position.0,
+        level.start_position.1,
+        level.width,
+        level.height,
+    )
+    .truncate();
+    for (entity, transform, mut npc, mut injured) in injured_query.iter_mut() {
+        let pos = transform.translation.truncate();
+        if !injured.treated {
+            if player_pos.distance(pos) > TILE_SIZE * 1.5 {
+                continue;
+            }
+            let kit = inventory
+                .items
+                .iter()
+                .position(|item| item.name == "First Aid Kit");
+            match kit {
+                None => {
+                    if warning.remaining <= 0.0 {
+                        warning.show(format!(
+                            "{} needs a first aid kit before they can move",
+                            npc.name
+                        ));
+                    }
+                }
+                Some(index) => {
+                    if keyboard.just_pressed(KeyCode::KeyF) {
+                        inventory.items.remove(index);
+                        injured.treated = true;
+                        npc.behavior = NpcBehaviorType::Follow;
+                        warning.show(format!(
+                            "You patch {} up — get them back to the trailhead",
+                            npc.name
+                        ));
+                    } else if warning.remaining <= 0.0 {
+                        warning.show("Press F to give first aid");
+                    }
+                }
+            }
+        } else if pos.distance(safe) < TILE_SIZE * 2.0 {
+            money.0 += RESCUE_REWARD;
+            reputation.adjust(RESCUE_REPUTATION);
+            experience.award(25.0);
+            warning.show(format!(
+                "{} is safe! +{RESCUE_REWARD:.0} kr",
+                npc.name
+            ));
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Whether anyone of this type is on the rope team.
+fn party_has(party: &Party, npc_query: &Query<&NPC>, npc_type: NPCType) -> bool {
+    party
+        .members
+        .iter()
+        .any(|&member| npc_query.get(member).is_ok_and(|npc| npc.npc_type == npc_type))
+}
+
+/// Seconds between the guide re-checking the route.
+const GUIDE_ROUTE_INTERVAL: f32 = 3.0;
+/// How many tiles of the route the guide points out at a time.
+const GUIDE_ROUTE_PREVIEW: usize = 18;
+
+/// A guide in the party keeps a trail of dots laid out along the best
+/// route from the player to the goal, refreshed as the player moves.
+#[allow(clippy::too_many_arguments)]
+pub fn guide_route_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut since_refresh: Local<f32>,
+    party: Res<Party>,
+    current_level: Res<CurrentLevel>,
+    npc_query: Query<&NPC>,
+    player_query: Query<&Transform, With<Player>>,
+    marker_query: Query<Entity, With<RouteMarker>>,
+) {
+    *since_refresh += time.delta_seconds();
+    if *since_refresh < GUIDE_ROUTE_INTERVAL {
+        return;
+    }
+    *since_refresh = 0.0;
+    if !party_has(&party, &npc_query, NPCType::Guide) {
+        for entity in marker_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    for entity in marker_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let start = levels::world_to_grid(
+        player_transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let Some(path) = crate::pathfinding::find_path(
+        &level.terrain,
+        start,
+        level.goal_position,
+        crate::pathfinding::FULL_GEAR,
+    ) else {
+        return;
+    };
+    for &(x, y) in path.iter().skip(1).step_by(3).take(GUIDE_ROUTE_PREVIEW / 3) {
+        let position = levels::calculate_tile_position(x, y, level.width, level.height);
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::srgba(1.0, 0.95, 0.5, 0.8),
+                    custom_size: Some(Vec2::splat(6.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(position.x, position.y, 0.9),
+                ..default()
+            },
+            RouteMarker,
+        ));
+    }
+}
+
+/// A mage in the party keeps the cold at bay, slowly pulling a chilled
+/// climber's core back toward healthy.
+pub fn mage_warmth_system(
+    time: Res<Time>,
+    party: Res<Party>,
+    npc_query: Query<&NPC>,
+    mut player_query: Query<&mut BodyTemperature, With<Player>>,
+) {
+    if !party_has(&party, &npc_query, NPCType::Mage) {
+        return;
+    }
+    let Ok(mut temperature) = player_query.get_single_mut() else {
+        return;
+    };
+    if temperature.current < 37.0 {
+        temperature.current =
+            (temperature.current + 0.5 * time.delta_seconds()).min(37.0);
+    }
+}
+
+/// The farthest a belayed climber can drop before the rope comes taut.
+const BELAY_MAX_FALL: f32 = TILE_SIZE * 4.0;
+
+/// A climber in the party belays: however far the drop, the rope comes
+/// taut after a few tiles, capping the distance the landing counts.
+pub fn climber_belay_system(
+    party: Res<Party>,
+    npc_query: Query<&NPC>,
+    mut player_query: Query<(&Transform, &mut Falling), With<Player>>,
+) {
+    if !party_has(&party, &npc_query, NPCType::Climber) {
+        return;
+    }
+    let Ok((transform, mut falling)) = player_query.get_single_mut() else {
+        return;
+    };
+    falling.start_y = falling.start_y.min(transform.translation.y + BELAY_MAX_FALL);
+}
+
+/// Trade while in the shop state: 1-9 buys from the trader's list,
+/// Shift+1-9 sells from the pack, Escape leaves.
+pub fn shop_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    reputation: Res<crate::dialogue::PlayerReputation>,
+    mut shop: ResMut<ShopInventory>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<(&mut Money, &mut Inventory), With<Player>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Climbing);
+        return;
+    }
+    let Ok((mut money, mut inventory)) = player_query.get_single_mut() else {
+        return;
+    };
+    let selling =
+        keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (index, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        if selling {
+            if index >= inventory.items.len() {
+                continue;
+            }
+            let item = inventory.items.remove(index);
+            let value = crate::items::sell_value(&shop, &item);
+            money.0 += value;
+            warning.show(format!("Sold {} for {value:.0} kr", item.name));
+            continue;
+        }
+        let Some(entry) = shop.entries.get_mut(index) else {
+            continue;
+        };
+        if entry.stock == 0 {
+            warning.show(format!("{} is sold out", entry.item.name));
+            continue;
+        }
+        // A good name earns a discount; a bad one a surcharge
+        let price = entry.price * reputation.price_modifier();
+        if money.0 < price {
+            warning.show(format!("You can't afford the {}", entry.item.name));
+            continue;
+        }
+        money.0 -= price;
+        entry.stock -= 1;
+        inventory.items.push(entry.item.clone());
+        warning.show(format!("Bought {}", entry.item.name));
+    }
+}
+
+/// Swap goods face to face: 1-9 picks from the other side's pack,
+/// Shift+1-9 from yours, Enter proposes the trade. Whether they take
+/// the deal comes down to the values of the two items and how much
+/// they like you.
+#[allow(clippy::too_many_arguments)]
+pub fn barter_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    reputation: Res<crate::dialogue::PlayerReputation>,
+    mut barter: ResMut<ActiveBarter>,
+    mut warning: ResMut<WarningMessage>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut player_query: Query<&mut Inventory, (With<Player>, Without<NPC>)>,
+    mut npc_query: Query<(&NPC, &mut Inventory), Without<Player>>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        barter.npc = None;
+        next_state.set(GameState::Climbing);
+        return;
+    }
+    let Ok(mut inventory) = player_query.get_single_mut() else {
+        return;
+    };
+    let Some((npc, mut npc_inventory)) =
+        barter.npc.and_then(|entity| npc_query.get_mut(entity).ok())
+    else {
+        return;
+    };
+    let shift = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (index, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        if shift {
+            if index < inventory.items.len() {
+                barter.player_pick = Some(index);
+            }
+        } else if index < npc_inventory.items.len() {
+            barter.npc_pick = Some(index);
+        }
+        return;
+    }
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let (Some(theirs), Some(yours)) = (barter.npc_pick, barter.player_pick) else {
+        warning.show("Pick an item from each side first");
+        return;
+    };
+    let (Some(their_item), Some(your_item)) =
+        (npc_inventory.items.get(theirs), inventory.items.get(yours))
+    else {
+        return;
+    };
+    // Your goods count for a little more or less depending on your name
+    let offered = crate::items::barter_value(your_item)
+        * (2.0 - reputation.price_modifier());
+    let asked = crate::items::barter_value(their_item);
+    if offered < asked {
+        warning.show(format!("{} shakes their head — not enough", npc.name));
+        return;
+    }
+    let your_item = inventory.items.remove(yours);
+    let their_item = npc_inventory.items.remove(theirs);
+    warning.show(format!(
+        "Traded {} for {}",
+        your_item.name, their_item.name
+    ));
+    inventory.items.push(their_item);
+    npc_inventory.items.push(your_item);
+    barter.npc_pick = None;
+    barter.player_pick = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_loads_climb_at_face_difficulty() {
+        let rules = ClimbingRules::default();
+        assert_eq!(rules.effective_difficulty(5.0, rules.free_carry), 5.0);
+        assert_eq!(rules.effective_difficulty(5.0, 0.0), 5.0);
+    }
+
+    #[test]
+    fn weight_above_free_carry_raises_difficulty() {
+        let rules = ClimbingRules::default();
+        let loaded = rules.effective_difficulty(5.0, rules.free_carry + 10.0);
+        assert_eq!(loaded, 5.0 + 10.0 * rules.difficulty_per_kg);
+    }
+
+    #[test]
+    fn short_falls_are_free() {
+        let rules = ClimbingRules::default();
+        assert_eq!(rules.fall_damage(rules.safe_fall_distance, 30.0), 0.0);
+    }
+
+    #[test]
+    fn heavy_packs_hit_harder() {
+        let rules = ClimbingRules::default();
+        let distance = rules.safe_fall_distance + TILE_SIZE * 3.0;
+        let light = rules.fall_damage(distance, rules.free_carry);
+        let heavy = rules.fall_damage(distance, rules.free_carry + 10.0);
+        assert_eq!(light, 3.0 * rules.fall_damage_per_tile);
+        assert_eq!(heavy, light * (1.0 + 10.0 * rules.fall_damage_per_kg));
+    }
+}
@@ -0,0 +1,382 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::components::{TerrainTile, TerrainType, ToolType};
+use crate::levels::{self, CurrentLevel, TILE_SIZE};
+
+pub const TERRAIN_TYPES_PATH: &str = "assets/terrain_types.ron";
+
+/// Everything the game needs to know about one terrain type. Stored in
+/// `assets/terrain_types.ron` so tweaking terrain (or adding a new kind
+/// like Scree or Mud) doesn't require touching code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerrainTypeDef {
+    pub color: (f32, f32, f32),
+    pub movement_modifier: f32,
+    /// Default stability for freshly spawned tiles.
+    pub stability: f32,
+    pub climbable: bool,
+    pub solid: bool,
+    pub required_tool: Option<ToolType>,
+    pub hits_to_break: u32,
+    pub hazard_damage: Option<f32>,
+}
+
+impl TerrainTypeDef {
+    fn from_builtin(terrain: TerrainType) -> Self {
+        let srgba = terrain.color().to_srgba();
+        let breakability = terrain.breakability();
+        // Frozen ground and lava crust start below full stability and
+        // give way under a climber who lingers
+        let stability = match terrain {
+            TerrainType::Lava => 0.6,
+            TerrainType::Ice => 0.8,
+            TerrainType::Snow => 0.85,
+            TerrainType::Glacier => 0.9,
+            _ => 1.0,
+        };
+        Self {
+            color: (srgba.red, srgba.green, srgba.blue),
+            movement_modifier: terrain.movement_modifier(),
+            stability,
+            climbable: terrain.climbable(),
+            solid: terrain.solid(),
+            required_tool: breakability.map(|(tool, _)| tool),
+            hits_to_break: breakability.map(|(_, hits)| hits).unwrap_or(0),
+            hazard_damage: terrain.hazard_damage(),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        Color::srgb(self.color.0, self.color.1, self.color.2)
+    }
+}
+
+/// Lookup table from terrain name to its definition.
+#[derive(Resource)]
+pub struct TerrainRegistry {
+    pub types: HashMap<String, TerrainTypeDef>,
+}
+
+impl Default for TerrainRegistry {
+    fn default() -> Self {
+        let mut types = HashMap::new();
+        for terrain in [
+            TerrainType::Rock,
+            TerrainType::Ice,
+            TerrainType::Snow,
+            TerrainType::Grass,
+            TerrainType::Soil,
+            TerrainType::Glacier,
+            TerrainType::Lava,
+            TerrainType::Coast,
+            TerrainType::Cliff,
+            TerrainType::Water,
+            TerrainType::Shrub,
+            TerrainType::Tree,
+        ] {
+            types.insert(format!("{terrain:?}"), TerrainTypeDef::from_builtin(terrain));
+        }
+        Self { types }
+    }
+}
+
+impl TerrainRegistry {
+    pub fn get(&self, terrain: TerrainType) -> &TerrainTypeDef {
+        self.get_by_name(&format!("{terrain:?}"))
+            .expect("built-in terrain types are always registered")
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&TerrainTypeDef> {
+        self.types.get(name)
+    }
+}
+
+/// Grid-coordinate index of spawned tile entities, so systems can find
+/// the tile under a position without scanning every terrain entity.
+#[derive(Resource, Default)]
+pub struct TerrainIndex {
+    tiles: HashMap<(i32, i32), Entity>,
+    coords: HashMap<Entity, (i32, i32)>,
+}
+
+impl TerrainIndex {
+    pub fn get(&self, grid_x: i32, grid_y: i32) -> Option<Entity> {
+        self.tiles.get(&(grid_x, grid_y)).copied()
+    }
+}
+
+/// Keep [`TerrainIndex`] in sync as the chunk manager spawns and
+/// despawns tile entities.
+pub fn update_terrain_index(
+    mut index: ResMut<TerrainIndex>,
+    added: Query<(Entity, &TerrainTile), Added<TerrainTile>>,
+    mut removed: RemovedComponents<TerrainTile>,
+) {
+    for entity in removed.read() {
+        if let Some(coords) = index.coords.remove(&entity) {
+            index.tiles.remove(&coords);
+        }
+    }
+    for (entity, tile) in added.iter() {
+        index.tiles.insert((tile.grid_x, tile.grid_y), entity);
+        index.coords.insert(entity, (tile.grid_x, tile.grid_y));
+    }
+}
+
+/// Tiles per chunk side.
+pub const CHUNK_SIZE: i32 = 16;
+/// Chunks kept spawned in each direction around the camera.
+pub const VIEW_DISTANCE_CHUNKS: i32 = 2;
+/// New chunks spawned per frame, so loading a view spreads over a few
+/// frames instead of hitching.
+pub const MAX_CHUNK_SPAWNS_PER_FRAME: usize = 6;
+
+pub fn chunk_of(grid_x: i32, grid_y: i32) -> (i32, i32) {
+    (grid_x.div_euclid(CHUNK_SIZE), grid_y.div_euclid(CHUNK_SIZE))
+}
+
+/// One batched mesh entity rendering every tile of a chunk.
+#[derive(Component)]
+pub struct TerrainChunkMesh {
+    pub chunk: (i32, i32),
+}
+
+/// Chunks whose tiles changed (terrain broken etc.) and whose mesh
+/// needs rebuilding.
+#[derive(Resource, Default)]
+pub struct DirtyChunks {
+    pub chunks: HashSet<(i32, i32)>,
+}
+
+/// Build one quad per tile into a single mesh. UVs address the tile's
+/// column in the terrain sheet; without a sheet the material ignores
+/// them and the vertex colour carries the look alone.
+fn build_chunk_mesh(tiles: &[(Vec2, Color, TerrainType)]) -> Mesh {
+    let half = TILE_SIZE / 2.0;
+    let mut positions = Vec::with_capacity(tiles.len() * 4);
+    let mut colors = Vec::with_capacity(tiles.len() * 4);
+    let mut uvs = Vec::with_capacity(tiles.len() * 4);
+    let mut indices = Vec::with_capacity(tiles.len() * 6);
+    let column_width = 1.0 / crate::sprites::TERRAIN_ATLAS_COLUMNS as f32;
+    for (center, color, terrain_type) in tiles {
+        let base = positions.len() as u32;
+        let u0 = crate::sprites::terrain_atlas_column(*terrain_type) as f32 * column_width;
+        let u1 = u0 + column_width;
+        for ((dx, dy), uv) in [(-half, -half), (half, -half), (half, half), (-half, half)]
+            .into_iter()
+            .zip([[u0, 1.0], [u1, 1.0], [u1, 0.0], [u0, 0.0]])
+        {
+            positions.push([center.x + dx, center.y + dy, 0.0]);
+            colors.push(color.to_srgba().to_f32_array());
+            uvs.push(uv);
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
+
+fn spawn_chunk_mesh(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    chunk: (i32, i32),
+    tiles: &[(Vec2, Color, TerrainType)],
+    texture: Option<Handle<Image>>,
+) {
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(build_chunk_mesh(tiles))),
+            material: materials.add(ColorMaterial {
+                texture,
+                ..default()
+            }),
+            ..default()
+        },
+        TerrainChunkMesh { chunk },
+    ));
+}
+
+/// Keep only the chunks near the camera spawned. Tile entities carry
+/// data only; rendering is one batched mesh per chunk. Tiles are
+/// recreated deterministically from the level definition, so despawned
+/// chunks come back exactly as authored.
+pub fn terrain_chunk_system(
+    mut commands: Commands,
+    registry: Res<TerrainRegistry>,
+    settings: Res<crate::settings::Settings>,
+    sheets: Res<crate::sprites::SpriteSheets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut current_level: ResMut<CurrentLevel>,
+    camera_query: Query<&Transform, With<Camera>>,
+    tile_query: Query<(Entity, &TerrainTile)>,
+    mesh_query: Query<(Entity, &TerrainChunkMesh)>,
+) {
+    let current_level = &mut *current_level;
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+
+    let (camera_x, camera_y) = levels::world_to_grid(
+        camera_transform.translation.truncate(),
+        level.width,
+        level.height,
+    );
+    let center = chunk_of(camera_x, camera_y);
+    let mut wanted = HashSet::new();
+    for dx in -VIEW_DISTANCE_CHUNKS..=VIEW_DISTANCE_CHUNKS {
+        for dy in -VIEW_DISTANCE_CHUNKS..=VIEW_DISTANCE_CHUNKS {
+            wanted.insert((center.0 + dx, center.1 + dy));
+        }
+    }
+    if wanted == current_level.spawned_chunks {
+        return;
+    }
+
+    for (entity, tile) in tile_query.iter() {
+        if !wanted.contains(&chunk_of(tile.grid_x, tile.grid_y)) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for (entity, chunk_mesh) in mesh_query.iter() {
+        if !wanted.contains(&chunk_mesh.chunk) {
+            commands.entity(entity).despawn();
+        }
+    }
+    current_level
+        .spawned_chunks
+        .retain(|chunk| wanted.contains(chunk));
+
+    // Spawn at most a few chunks per frame, nearest first, so a fresh
+    // view fills in over several frames instead of one long hitch
+    let mut missing: Vec<(i32, i32)> = wanted
+        .iter()
+        .filter(|chunk| !current_level.spawned_chunks.contains(*chunk))
+        .copied()
+        .collect();
+    missing.sort_by_key(|(cx, cy)| (cx - center.0).abs() + (cy - center.1).abs());
+    missing.truncate(MAX_CHUNK_SPAWNS_PER_FRAME);
+
+    // Group the new tiles by chunk so each chunk gets one mesh. With a
+    // terrain sheet the texture carries the look and the tint stays
+    // white; without one the palette colour is the whole tile.
+    let mut new_chunks: HashMap<(i32, i32), Vec<(Vec2, Color, TerrainType)>> = HashMap::new();
+    for tile in &level.terrain {
+        let chunk = chunk_of(tile.x, tile.y);
+        if missing.contains(&chunk) {
+            levels::spawn_terrain_tile(&mut commands, tile, level, &registry);
+            let position =
+                levels::calculate_tile_position(tile.x, tile.y, level.width, level.height);
+            let color = if sheets.terrain.is_some() {
+                Color::WHITE
+            } else {
+                settings.palette.adjust(registry.get(tile.terrain_type).color())
+            };
+            new_chunks
+                .entry(chunk)
+                .or_default()
+                .push((position.truncate(), color, tile.terrain_type));
+        }
+    }
+    for (chunk, tiles) in new_chunks {
+        spawn_chunk_mesh(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            chunk,
+            &tiles,
+            sheets.terrain.clone(),
+        );
+    }
+    current_level.spawned_chunks.extend(missing);
+}
+
+/// Rebuild the mesh of any chunk whose tiles changed.
+pub fn rebuild_dirty_chunks(
+    mut commands: Commands,
+    registry: Res<TerrainRegistry>,
+    settings: Res<crate::settings::Settings>,
+    sheets: Res<crate::sprites::SpriteSheets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut dirty: ResMut<DirtyChunks>,
+    tile_query: Query<(&Transform, &TerrainTile)>,
+    mesh_query: Query<(Entity, &TerrainChunkMesh)>,
+) {
+    if dirty.chunks.is_empty() {
+        return;
+    }
+    let chunks = std::mem::take(&mut dirty.chunks);
+    for chunk in chunks {
+        for (entity, chunk_mesh) in mesh_query.iter() {
+            if chunk_mesh.chunk == chunk {
+                commands.entity(entity).despawn();
+            }
+        }
+        let tiles: Vec<(Vec2, Color, TerrainType)> = tile_query
+            .iter()
+            .filter(|(_, tile)| chunk_of(tile.grid_x, tile.grid_y) == chunk)
+            .map(|(transform, tile)| {
+                let color = if sheets.terrain.is_some() {
+                    Color::WHITE
+                } else {
+                    settings.palette.adjust(registry.get(tile.terrain_type).color())
+                };
+                (transform.translation.truncate(), color, tile.terrain_type)
+            })
+            .collect();
+        if !tiles.is_empty() {
+            spawn_chunk_mesh(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                chunk,
+                &tiles,
+                sheets.terrain.clone(),
+            );
+        }
+    }
+}
+
+/// Build the registry from `assets/terrain_types.ron`, overlaying the
+/// built-in defaults; missing file just means defaults (and the file is
+/// written out so there's something to edit).
+pub fn load_terrain_registry() -> TerrainRegistry {
+    let mut registry = TerrainRegistry::default();
+    let path = Path::new(TERRAIN_TYPES_PATH);
+    match fs::read_to_string(path) {
+        Ok(contents) => match ron::from_str::<HashMap<String, TerrainTypeDef>>(&contents) {
+            Ok(types) => registry.types.extend(types),
+            Err(e) => error!("Failed to parse {TERRAIN_TYPES_PATH}: {e}"),
+        },
+        Err(_) => {
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            if let Ok(contents) =
+                ron::ser::to_string_pretty(&registry.types, ron::ser::PrettyConfig::default())
+            {
+                let _ = fs::write(path, contents);
+            }
+        }
+    }
+    registry
+}
@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::components::{NPCType, TerrainType, WildlifeSpecies};
+use crate::levels::{
+    self, ItemSpawn, LevelDefinition, NPCSpawn, TerrainData, WildlifeSpawn,
+};
+
+/// How a Tiled export maps onto the game: which tile gid is which
+/// terrain, plus the level metadata Tiled has no fields for. Lives in a
+/// RON file next to the exported map.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TiledMapping {
+    pub name: String,
+    pub description: String,
+    pub difficulty: u32,
+    /// Tiled global tile id -> terrain type.
+    pub terrain: HashMap<u32, TerrainType>,
+    pub start_position: (i32, i32),
+    pub goal_position: (i32, i32),
+}
+
+// The subset of Tiled's JSON export format the importer reads. Maps
+// must be exported as JSON (File -> Export As in Tiled); TMX is the
+// same data in XML and isn't supported.
+#[derive(Deserialize)]
+struct TiledMap {
+    width: i32,
+    height: i32,
+    tilewidth: f32,
+    tileheight: f32,
+    layers: Vec<TiledLayer>,
+}
+
+#[derive(Deserialize)]
+struct TiledLayer {
+    #[serde(rename = "type")]
+    layer_type: String,
+    #[serde(default)]
+    data: Vec<u32>,
+    #[serde(default)]
+    objects: Vec<TiledObject>,
+}
+
+#[derive(Deserialize)]
+struct TiledObject {
+    name: String,
+    #[serde(rename = "type", default)]
+    object_type: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Deserialize)]
+struct TiledProperty {
+    name: String,
+    value: serde_json::Value,
+}
+
+impl TiledObject {
+    fn property(&self, name: &str) -> Option<&serde_json::Value> {
+        self.properties
+            .iter()
+            .find(|property| property.name == name)
+            .map(|property| &property.value)
+    }
+
+    fn string_property(&self, name: &str) -> Option<String> {
+        self.property(name)?.as_str().map(str::to_string)
+    }
+}
+
+/// Parse an enum variant written as a bare name in a Tiled property
+/// ("Guide", "Wolf"); serde enums accept that spelling through RON.
+fn parse_variant<T: serde::de::DeserializeOwned>(value: &str) -> Option<T> {
+    ron::from_str(value).ok()
+}
+
+/// Convert a Tiled JSON export into a level. Tile layers become
+/// terrain through the mapping's gid table; object layers become NPC,
+/// item, and wildlife spawns keyed by each object's type field.
+pub fn import_tiled(path: &Path, mapping: &TiledMapping) -> Option<LevelDefinition> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("Failed to read Tiled map {}: {e}", path.display());
+            return None;
+        }
+    };
+    let map: TiledMap = match serde_json::from_str(&contents) {
+        Ok(map) => map,
+        Err(e) => {
+            error!("Failed to parse Tiled map {}: {e}", path.display());
+            return None;
+        }
+    };
+
+    let mut terrain: Vec<TerrainData> = Vec::new();
+    let mut items = Vec::new();
+    let mut npcs = Vec::new();
+    let mut wildlife = Vec::new();
+
+    // Tiled's origin is top-left with y down; ours is bottom-left.
+    let flip_row = |row: i32| map.height - 1 - row;
+    let to_world = |x: f32, y: f32| {
+        let grid_x = (x / map.tilewidth).floor() as i32;
+        let grid_y = flip_row((y / map.tileheight).floor() as i32);
+        let position = levels::calculate_tile_position(grid_x, grid_y, map.width, map.height);
+        (position.x, position.y)
+    };
+
+    for layer in &map.layers {
+        match layer.layer_type.as_str() {
+            "tilelayer" => {
+                for (index, &gid) in layer.data.iter().enumerate() {
+                    if gid == 0 {
+                        continue; // empty cell
+                    }
+                    let Some(&terrain_type) = mapping.terrain.get(&gid) else {
+                        warn!("Tiled map {}: gid {gid} has no terrain mapping", path.display());
+                        continue;
+                    };
+                    let x = index as i32 % map.width;
+                    let y = flip_row(index as i32 / map.width);
+                    terrain.push(TerrainData {
+                        x,
+                        y,
+                        terrain_type,
+                        biome: levels::biome_for(y as f32 / map.height as f32, 0.5),
+                        difficulty: 10.0 * y as f32 / map.height as f32,
+                        required_gear: levels::default_gear_for(terrain_type),
+                    });
+                }
+            }
+            "objectgroup" => {
+                for object in &layer.objects {
+                    let position = to_world(object.x, object.y);
+                    match object.object_type.as_str() {
+                        "item" => items.push(ItemSpawn {
+                            item_id: object.name.clone(),
+                            position,
+                        }),
+                        "npc" => {
+                            let Some(npc_type) = object
+                                .string_property("npc_type")
+                                .and_then(|value| parse_variant::<NPCType>(&value))
+                            else {
+                                warn!("NPC object {:?} is missing a valid npc_type", object.name);
+                                continue;
+                            };
+                            npcs.push(NPCSpawn {
+                                npc_type,
+                                name: object.name.clone(),
+                                position,
+                                dialogue_file: object
+                                    .string_property("dialogue_file")
+                                    .unwrap_or_default(),
+                            });
+                        }
+                        "wildlife" => {
+                            let Some(species) = object
+                                .string_property("species")
+                                .and_then(|value| parse_variant::<WildlifeSpecies>(&value))
+                            else {
+                                warn!(
+                                    "Wildlife object {:?} is missing a valid species",
+                                    object.name
+                                );
+                                continue;
+                            };
+                            wildlife.push(WildlifeSpawn {
+                                species,
+                                position,
+                                count: object
+                                    .property("count")
+                                    .and_then(serde_json::Value::as_u64)
+                                    .unwrap_or(1) as u32,
+                            });
+                        }
+                        other => {
+                            warn!("Tiled object {:?} has unknown type {other:?}", object.name)
+                        }
+                    }
+                }
+            }
+            other => warn!("Tiled map {}: skipping {other:?} layer", path.display()),
+        }
+    }
+
+    let level = LevelDefinition {
+        name: mapping.name.clone(),
+        description: mapping.description.clone(),
+        difficulty: mapping.difficulty,
+        reward: mapping.difficulty as f32 * 50.0,
+        seed: 0,
+        width: map.width,
+        height: map.height,
+        start_position: mapping.start_position,
+        goal_position: mapping.goal_position,
+        terrain,
+        items,
+        npcs,
+        wildlife,
+        entrances: Vec::new(),
+    };
+    for error in level.validate() {
+        warn!("Imported level {}: {error}", path.display());
+    }
+    Some(level)
+}
@@ -0,0 +1,1736 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::dialogue::ActiveDialogue;
+use crate::levels::AvailableLevels;
+use crate::systems::LevelLoadProgress;
+
+#[derive(Component)]
+pub struct HudRoot;
+
+#[derive(Component)]
+pub struct StatusText;
+
+#[derive(Component)]
+pub struct HealthText;
+
+#[derive(Component)]
+pub struct StaminaText;
+
+#[derive(Component)]
+pub struct WeightText;
+
+#[derive(Component)]
+pub struct WalletText;
+
+/// Tags an equipped-item row with which slot it shows, so systems can
+/// find it without matching on the label text.
+#[derive(Component)]
+pub struct EquipmentSlotLabel {
+    pub slot: EquipmentSlot,
+}
+
+/// The weather/clock cluster in the top-right corner.
+#[derive(Component)]
+pub struct EnvironmentText;
+
+#[derive(Component)]
+pub struct DialogueBox;
+
+#[derive(Component)]
+pub struct WarningText;
+
+/// The scrolling feed of game messages above the hotbar.
+#[derive(Component)]
+pub struct MessageFeed;
+
+/// One line in the message feed, counting down to its fade-out.
+#[derive(Component)]
+pub struct FeedEntry {
+    pub remaining: f32,
+}
+
+#[derive(Component)]
+pub struct HotbarText;
+
+#[derive(Component)]
+pub struct PartyPanelText;
+
+#[derive(Component)]
+pub struct JournalText;
+
+#[derive(Component)]
+pub struct CharacterSelectScreen;
+
+#[derive(Component)]
+pub struct LevelSelectScreen;
+
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingBarFill;
+
+#[derive(Component)]
+pub struct ShopScreen;
+
+/// The text body of the shop screen, rewritten as stock and money
+/// change.
+#[derive(Component)]
+pub struct ShopText;
+
+#[derive(Component)]
+pub struct BarterScreen;
+
+#[derive(Component)]
+pub struct BarterText;
+
+#[derive(Component)]
+pub struct MenuScreen;
+
+/// One selectable entry on the main menu.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Continue,
+    NewExpedition,
+    LevelSelect,
+    Settings,
+    Quit,
+}
+
+#[derive(Component)]
+pub struct SettingsScreen;
+
+#[derive(Component)]
+pub struct SettingsText;
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[derive(Component)]
+pub struct SkillsScreen;
+
+#[derive(Component)]
+pub struct SkillsText;
+
+#[derive(Component)]
+pub struct MagicScreen;
+
+#[derive(Component)]
+pub struct MagicText;
+
+/// A hoverable row representing one item in a list UI (inventory,
+/// shop). Hovering it fills the tooltip panel.
+#[derive(Component)]
+pub struct ItemRow {
+    pub item: Item,
+}
+
+/// The tooltip panel that shows an item's stats against what's
+/// currently equipped in the same slot.
+#[derive(Component)]
+pub struct TooltipPanel;
+
+/// The text inside the tooltip panel.
+#[derive(Component)]
+pub struct TooltipText;
+
+pub fn setup_ui(mut commands: Commands, strings: Res<crate::localization::Strings>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(8.0),
+                    left: Val::Px(8.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            HudRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    format!("{}: 100/100", strings.get("hud-health")),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                StatusText,
+                HealthText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    format!("{}: 100/100", strings.get("hud-stamina")),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                StatusText,
+                StaminaText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    format!("{}: 0.0 kg", strings.get("hud-weight")),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                StatusText,
+                WeightText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    format!("{}: 0 kr", strings.get("hud-purse")),
+                    TextStyle {
+                        font_size: 18.0,
+                        color: Color::srgb(0.95, 0.85, 0.4),
+                        ..default()
+                    },
+                ),
+                StatusText,
+                WalletText,
+            ));
+        });
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 18.0,
+                color: Color::srgb(0.85, 0.9, 1.0),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        }),
+        EnvironmentText,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::srgb(1.0, 0.8, 0.3),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(25.0),
+            left: Val::Percent(30.0),
+            ..default()
+        }),
+        WarningText,
+    ));
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(32.0),
+                left: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ..default()
+        },
+        MessageFeed,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::srgb(0.85, 0.85, 0.85),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        }),
+        HotbarText,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::srgb(0.85, 0.9, 1.0),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        }),
+        PartyPanelText,
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::srgb(0.95, 0.9, 0.75),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(20.0),
+            right: Val::Px(8.0),
+            max_width: Val::Percent(35.0),
+            ..default()
+        }),
+        JournalText,
+    ));
+}
+
+/// J flips the journal open and shut; open, it lists every accepted
+/// quest with its description and state, and the runes found and still
+/// missing on each mountain.
+pub fn journal_ui_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    mut open: Local<bool>,
+    log: Res<crate::quests::QuestLog>,
+    catalog: Res<crate::quests::QuestCatalog>,
+    runes: Res<crate::systems::RuneCollection>,
+    mut text_query: Query<&mut Text, With<JournalText>>,
+) {
+    if settings
+        .bindings
+        .just_pressed(&keyboard, crate::settings::Action::ToggleJournal)
+    {
+        *open = !*open;
+    }
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if !*open {
+        text.sections[0].value.clear();
+        return;
+    }
+    let mut body = String::from("Journal ([J] close)");
+    if log.entries.is_empty() {
+        body.push_str("
+  No quests yet.");
+    }
+    for entry in &log.entries {
+        let Some(quest) = catalog.get(&entry.id) else {
+            continue;
+        };
+        let mark = if entry.completed { "x" } else { " " };
+        body.push_str(&format!("
+[{mark}] {}
+    {}", quest.title, quest.description));
+    }
+    body.push_str("\n\nRunes:");
+    let mut last_level = "";
+    for rune in crate::components::rune_catalog() {
+        if rune.level != last_level {
+            body.push_str(&format!("\n{}:", rune.level));
+            last_level = rune.level;
+        }
+        // Undeciphered runes keep their names to themselves
+        if runes.has(rune.id) {
+            body.push_str(&format!("  {}", rune.name));
+        } else {
+            body.push_str("  ???");
+        }
+    }
+    text.sections[0].value = body;
+}
+
+/// List each companion's condition in the top-right corner; the panel
+/// clears itself when the player climbs alone.
+pub fn update_party_ui(
+    party: Res<Party>,
+    npc_query: Query<(&NPC, &Health, &Stamina)>,
+    mut text_query: Query<&mut Text, With<PartyPanelText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if party.members.is_empty() {
+        text.sections[0].value.clear();
+        return;
+    }
+    let mut body = String::from("Party:");
+    for &member in &party.members {
+        let Ok((npc, health, stamina)) = npc_query.get(member) else {
+            continue;
+        };
+        body.push_str(&format!(
+            "
+{}  HP {:.0}/{:.0}  ST {:.0}/{:.0}",
+            npc.name, health.current, health.max, stamina.current, stamina.max
+        ));
+    }
+    body.push_str("
+[P] dismiss last");
+    text.sections[0].value = body;
+}
+
+/// Redraw the hotbar line: slot number, bound consumable, how many are
+/// left in the pack, and any cooldown still running.
+pub fn update_hotbar_ui(
+    hotbar: Res<Hotbar>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut text_query: Query<&mut Text, With<HotbarText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    let line = hotbar
+        .slots
+        .iter()
+        .enumerate()
+        .map(|(slot, bound)| match bound {
+            Some(name) => {
+                let count = inventory
+                    .items
+                    .iter()
+                    .filter(|item| &item.name == name)
+                    .count();
+                if hotbar.cooldowns[slot] > 0.0 {
+                    format!(
+                        "[{}] {} x{} ({:.0}s)",
+                        slot + 1,
+                        name,
+                        count,
+                        hotbar.cooldowns[slot].ceil()
+                    )
+                } else {
+                    format!("[{}] {} x{}", slot + 1, name, count)
+                }
+            }
+            None => format!("[{}] -", slot + 1),
+        })
+        .collect::<Vec<_>>()
+        .join("   ");
+    text.sections[0].value = line;
+}
+
+/// In the inventory screen, Ctrl+1-5 binds the hovered food or drink
+/// to that hotbar slot.
+pub fn hotbar_assign_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    row_query: Query<(&Interaction, &ItemRow)>,
+    mut hotbar: ResMut<Hotbar>,
+    mut warning: ResMut<WarningMessage>,
+) {
+    if !keyboard.pressed(KeyCode::ControlLeft) && !keyboard.pressed(KeyCode::ControlRight) {
+        return;
+    }
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+    ];
+    for (slot, key) in keys.iter().enumerate() {
+        if !keyboard.just_pressed(*key) {
+            continue;
+        }
+        let hovered = row_query
+            .iter()
+            .find(|(interaction, _)| matches!(interaction, Interaction::Hovered))
+            .map(|(_, row)| &row.item);
+        let Some(item) = hovered else {
+            warning.show("Hover an item to bind it");
+            return;
+        };
+        if !matches!(item.item_type, ItemType::Food | ItemType::Drink) {
+            warning.show(format!("The {} isn't a consumable", item.name));
+            return;
+        }
+        hotbar.slots[slot] = Some(item.name.clone());
+        warning.show(format!("{} bound to slot {}", item.name, slot + 1));
+        return;
+    }
+}
+
+/// Show the current warning line and fade it out over time.
+pub fn update_warning_text(
+    time: Res<Time>,
+    strings: Res<crate::localization::Strings>,
+    mut warning: ResMut<WarningMessage>,
+    mut text_query: Query<&mut Text, With<WarningText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if warning.remaining > 0.0 {
+        warning.remaining -= time.delta_seconds();
+        text.sections[0].value = strings.tr(&warning.text).to_string();
+    } else {
+        text.sections[0].value.clear();
+    }
+}
+
+/// How long a feed line stays up before it starts to fade.
+const FEED_LIFETIME: f32 = 6.0;
+/// How many lines the feed holds before the oldest is dropped.
+const FEED_CAPACITY: usize = 6;
+
+/// Append incoming game messages to the feed, fade old lines out over
+/// their last second, and keep the feed from growing past capacity.
+pub fn message_feed_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    strings: Res<crate::localization::Strings>,
+    mut events: EventReader<crate::systems::GameMessageEvent>,
+    feed_query: Query<(Entity, Option<&Children>), With<MessageFeed>>,
+    mut entry_query: Query<(Entity, &mut FeedEntry, &mut Text)>,
+) {
+    let Ok((feed, children)) = feed_query.get_single() else {
+        return;
+    };
+    for event in events.read() {
+        let entry = commands
+            .spawn((
+                TextBundle::from_section(
+                    strings.tr(&event.text).to_string(),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: event.severity.color(),
+                        ..default()
+                    },
+                ),
+                FeedEntry {
+                    remaining: FEED_LIFETIME,
+                },
+            ))
+            .id();
+        commands.entity(feed).add_child(entry);
+    }
+    let mut alive = 0;
+    for (entity, mut entry, mut text) in entry_query.iter_mut() {
+        entry.remaining -= time.delta_seconds();
+        if entry.remaining <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        alive += 1;
+        if entry.remaining < 1.0 {
+            let color = &mut text.sections[0].style.color;
+            *color = color.with_alpha(entry.remaining);
+        }
+    }
+    // Drop the oldest lines first; children are in spawn order
+    if alive > FEED_CAPACITY {
+        let Some(children) = children else {
+            return;
+        };
+        for child in children.iter().take(alive - FEED_CAPACITY) {
+            commands.entity(*child).despawn_recursive();
+        }
+    }
+}
+
+/// Push player stats into the HUD.
+pub fn update_health_stamina_ui(
+    strings: Res<crate::localization::Strings>,
+    player_query: Query<(&Health, &Stamina), With<Player>>,
+    mut health_query: Query<&mut Text, (With<HealthText>, Without<StaminaText>)>,
+    mut stamina_query: Query<&mut Text, With<StaminaText>>,
+) {
+    let Ok((health, stamina)) = player_query.get_single() else {
+        return;
+    };
+    if let Ok(mut text) = health_query.get_single_mut() {
+        text.sections[0].value = format!(
+            "{}: {:.0}/{:.0}",
+            strings.get("hud-health"),
+            health.current,
+            health.max
+        );
+    }
+    if let Ok(mut text) = stamina_query.get_single_mut() {
+        text.sections[0].value = format!(
+            "{}: {:.0}/{:.0}",
+            strings.get("hud-stamina"),
+            stamina.current,
+            stamina.max
+        );
+    }
+}
+
+pub fn update_weight_display(
+    strings: Res<crate::localization::Strings>,
+    player_query: Query<&Inventory, With<Player>>,
+    mut text_query: Query<&mut Text, With<WeightText>>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format!(
+            "{}: {:.1} kg",
+            strings.get("hud-weight"),
+            inventory.current_weight()
+        );
+    }
+}
+
+/// Keep the wallet line current.
+pub fn update_wallet_display(
+    strings: Res<crate::localization::Strings>,
+    player_query: Query<&Money, With<Player>>,
+    mut text_query: Query<&mut Text, With<WalletText>>,
+) {
+    let Ok(money) = player_query.get_single() else {
+        return;
+    };
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format!("{}: {:.0} kr", strings.get("hud-purse"), money.0);
+    }
+}
+
+/// Keep the top-right cluster current: conditions, wind, the height of
+/// the climb, and the expedition clock.
+pub fn update_environment_display(
+    weather: Res<WeatherSystem>,
+    game_time: Res<GameTime>,
+    player_query: Query<&Transform, With<Player>>,
+    mut text_query: Query<&mut Text, With<EnvironmentText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let icon = match weather.current_weather {
+        Weather::Clear => "*",
+        Weather::Cloudy => "~",
+        Weather::Rain => "/",
+        Weather::Snow => ".:",
+        Weather::Storm => "!!",
+        Weather::Fog => "=",
+    };
+    let wind_arrow = {
+        let direction = weather.wind_direction;
+        if direction.x.abs() >= direction.y.abs() {
+            if direction.x >= 0.0 {
+                ">"
+            } else {
+                "<"
+            }
+        } else if direction.y >= 0.0 {
+            "^"
+        } else {
+            "v"
+        }
+    };
+    let altitude = player_query
+        .get_single()
+        .map(|transform| transform.translation.y)
+        .unwrap_or(0.0);
+    let minutes = (game_time.hour.fract() * 60.0) as u32;
+    text.sections[0].value = format!(
+        "{} {:?}   {:.0}°C\nWind {:.0} m/s {}\nAltitude {:.0} m\nDay {}, {:02}:{:02}",
+        icon,
+        weather.current_weather,
+        weather.temperature,
+        weather.wind_speed,
+        wind_arrow,
+        altitude,
+        game_time.day,
+        game_time.hour as u32 % 24,
+        minutes,
+    );
+}
+
+/// Show or hide the dialogue box depending on the active conversation.
+pub fn dialogue_ui_system(
+    mut commands: Commands,
+    dialogue: Res<ActiveDialogue>,
+    strings: Res<crate::localization::Strings>,
+    reputation: Res<crate::dialogue::PlayerReputation>,
+    npc_query: Query<&NPC, Without<Player>>,
+    morale_query: Query<&Morale, With<Player>>,
+    box_query: Query<Entity, With<DialogueBox>>,
+) {
+    if !dialogue.is_changed() {
+        return;
+    }
+    for entity in box_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let Some(tree) = &dialogue.tree else {
+        return;
+    };
+    let Some(node) = tree.nodes.get(&dialogue.current_node) else {
+        return;
+    };
+    // Speaker line carries their read on the player's standing
+    let mut body = match dialogue.npc.and_then(|npc| npc_query.get(npc).ok()) {
+        Some(npc) => format!(
+            "{} ({}):
+{}",
+            npc.name,
+            reputation.mood(),
+            strings.tr(&node.text)
+        ),
+        None => strings.tr(&node.text).to_string(),
+    };
+    let spoken_choices = morale_query
+        .get_single()
+        .map(|morale| if morale.downhearted() { 2 } else { usize::MAX })
+        .unwrap_or(usize::MAX);
+    for (index, choice) in node.choices.iter().enumerate().take(spoken_choices) {
+        body.push_str(&format!("\n  {}. {}", index + 1, strings.tr(&choice.text)));
+    }
+    if node.choices.len() > spoken_choices {
+        body.push_str("\n  ... (too worn down for more)");
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(16.0),
+                    left: Val::Percent(10.0),
+                    right: Val::Percent(10.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.8).into(),
+                ..default()
+            },
+            DialogueBox,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                body,
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// The options screen: each number key cycles its row.
+pub fn setup_settings_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.04, 0.09, 1.0).into(),
+                ..default()
+            },
+            SettingsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                SettingsText,
+            ));
+        });
+}
+
+/// Redraw the settings rows with their current values, or the rebind
+/// prompt while one is running.
+pub fn update_settings_ui(
+    settings: Res<crate::settings::Settings>,
+    rebinding: Res<crate::settings::Rebinding>,
+    mut text_query: Query<&mut Text, With<SettingsText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    if let Some(index) = rebinding.0 {
+        let action = crate::settings::Action::ALL[index];
+        text.sections[0].value = format!(
+            "Rebinding keys ({}/{})\n\nPress a key for: {}\n\n[Escape] stop here",
+            index + 1,
+            crate::settings::Action::ALL.len(),
+            action.name()
+        );
+        return;
+    }
+    let percent = |volume: f32| format!("{:.0}%", volume * 100.0);
+    let bindings = &settings.bindings;
+    let mut value = format!(
+        "Settings\n\n  1. Master volume: {}\n  2. Music volume: {}\n  3. Effects volume: {}\n  4. UI scale: {:.2}\n  5. Camera smoothing: {:.2}\n  6. Difficulty: {}\n  7. Palette: {}\n  8. Movement keys: {}/{}/{}/{}\n  9. Rebind every key\n  0. Language: {}\n",
+        percent(settings.master_volume),
+        percent(settings.music_volume),
+        percent(settings.effects_volume),
+        settings.ui_scale,
+        settings.camera_smoothing,
+        settings.difficulty.name(),
+        settings.palette.name(),
+        bindings.key_name(crate::settings::Action::MoveUp),
+        bindings.key_name(crate::settings::Action::MoveLeft),
+        bindings.key_name(crate::settings::Action::MoveDown),
+        bindings.key_name(crate::settings::Action::MoveRight),
+        settings.language.native_name(),
+    );
+    value.push_str("\nBindings:\n");
+    for action in crate::settings::Action::ALL {
+        value.push_str(&format!(
+            "  {:<16} {}\n",
+            action.name(),
+            bindings.key_name(action)
+        ));
+    }
+    value.push_str("\n[1-0] cycle   [Escape] back");
+    text.sections[0].value = value;
+}
+
+pub fn cleanup_settings_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<SettingsScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// The title screen: pick an entry by click or number key.
+pub fn setup_menu_ui(mut commands: Commands, strings: Res<crate::localization::Strings>) {
+    let has_save = crate::saves::latest_checkpoint().is_some();
+    let entries: Vec<(MenuAction, String)> = vec![
+        (
+            MenuAction::Continue,
+            format!(
+                "1. {}",
+                strings.get(if has_save {
+                    "menu-continue"
+                } else {
+                    "menu-continue-none"
+                })
+            ),
+        ),
+        (
+            MenuAction::NewExpedition,
+            format!("2. {}", strings.get("menu-new")),
+        ),
+        (
+            MenuAction::LevelSelect,
+            format!("3. {}", strings.get("menu-levels")),
+        ),
+        (
+            MenuAction::Settings,
+            format!("4. {}", strings.get("menu-settings")),
+        ),
+        (MenuAction::Quit, format!("5. {}", strings.get("menu-quit"))),
+    ];
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.04, 0.09, 1.0).into(),
+                ..default()
+            },
+            MenuScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "KLIFURPLANTA\n",
+                TextStyle {
+                    font_size: 42.0,
+                    color: Color::srgb(0.85, 0.9, 1.0),
+                    ..default()
+                },
+            ));
+            for (action, label) in entries {
+                parent.spawn((
+                    TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font_size: 22.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ),
+                    Interaction::default(),
+                    action,
+                ));
+            }
+        });
+}
+
+pub fn cleanup_menu_ui(mut commands: Commands, screen_query: Query<Entity, With<MenuScreen>>) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// List the levels on disk with name, description, and difficulty.
+pub fn setup_character_select_ui(mut commands: Commands) {
+    let mut body = String::from("Who takes on the mountain?\n");
+    for (index, archetype) in CharacterArchetype::ALL.iter().enumerate() {
+        body.push_str(&format!(
+            "\n  {}. {}\n     {}\n",
+            index + 1,
+            archetype.name(),
+            archetype.blurb()
+        ));
+    }
+    body.push_str("\n[1-4] choose");
+    if crate::saves::slot_exists() {
+        body.push_str("   [Enter] continue last expedition");
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.05, 0.1, 0.9).into(),
+                ..default()
+            },
+            CharacterSelectScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                body,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub fn cleanup_character_select_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<CharacterSelectScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn setup_level_select_ui(mut commands: Commands, available: Res<AvailableLevels>) {
+    let mut body = String::from("Choose your expedition:\n");
+    for (index, (_, level)) in available.levels.iter().enumerate() {
+        body.push_str(&format!(
+            "\n  {}. {} (difficulty {})\n     {}\n",
+            index + 1,
+            level.name,
+            level.difficulty,
+            level.description
+        ));
+    }
+    if available.levels.is_empty() {
+        body.push_str("\n  No levels found in levels/");
+    }
+    body.push_str("\n[1-9] climb   [Esc] back");
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.05, 0.1, 0.9).into(),
+                ..default()
+            },
+            LevelSelectScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                body,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Full-screen loading overlay with a progress bar.
+pub fn setup_loading_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                },
+                background_color: Color::srgba(0.0, 0.05, 0.1, 1.0).into(),
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Preparing the mountain...",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(300.0),
+                        height: Val::Px(16.0),
+                        ..default()
+                    },
+                    background_color: Color::srgba(1.0, 1.0, 1.0, 0.15).into(),
+                    ..default()
+                })
+                .with_children(|bar| {
+                    bar.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.4, 0.75, 0.95).into(),
+                            ..default()
+                        },
+                        LoadingBarFill,
+                    ));
+                });
+        });
+}
+
+pub fn update_loading_bar(
+    progress: Res<LevelLoadProgress>,
+    mut fill_query: Query<&mut Style, With<LoadingBarFill>>,
+) {
+    for mut style in fill_query.iter_mut() {
+        style.width = Val::Percent(progress.0.clamp(0.0, 1.0) * 100.0);
+    }
+}
+
+pub fn cleanup_loading_ui(mut commands: Commands, screen_query: Query<Entity, With<LoadingScreen>>) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn cleanup_level_select_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<LevelSelectScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[derive(Component)]
+pub struct InventoryScreen;
+
+/// List carried items; food and drink can be consumed by number.
+pub fn setup_shop_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.05, 0.08, 0.9).into(),
+                ..default()
+            },
+            ShopScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                ShopText,
+            ));
+        });
+}
+
+/// Keep the shop listing current with stock, pack, and purse.
+pub fn update_shop_ui(
+    shop: Res<ShopInventory>,
+    reputation: Res<crate::dialogue::PlayerReputation>,
+    player_query: Query<(&Money, &Inventory), With<Player>>,
+    mut text_query: Query<&mut Text, With<ShopText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok((money, inventory)) = player_query.get_single() else {
+        return;
+    };
+    let mut body = String::from("Sigrun's trading post\n\nFor sale:");
+    for (index, entry) in shop.entries.iter().enumerate() {
+        body.push_str(&format!(
+            "\n  {}. {} — {:.0} kr ({} left)",
+            index + 1,
+            entry.item.name,
+            entry.price * reputation.price_modifier(),
+            entry.stock
+        ));
+    }
+    body.push_str("\n\nYour pack:");
+    if inventory.items.is_empty() {
+        body.push_str("\n  (empty)");
+    }
+    for (index, item) in inventory.items.iter().enumerate() {
+        body.push_str(&format!("\n  {}. {}", index + 1, item.name));
+    }
+    body.push_str(&format!("\n\nPurse: {:.0} kr", money.0));
+    body.push_str("\n\n[1-9] buy   [Shift+1-9] sell   [Esc] leave");
+    text.sections[0].value = body;
+}
+
+pub fn cleanup_shop_ui(mut commands: Commands, screen_query: Query<Entity, With<ShopScreen>>) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn setup_barter_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.05, 0.08, 0.9).into(),
+                ..default()
+            },
+            BarterScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                BarterText,
+            ));
+        });
+}
+
+/// Redraw the barter screen: both packs with their swap values, and a
+/// marker on whatever each side has put forward.
+pub fn update_barter_ui(
+    barter: Res<ActiveBarter>,
+    player_query: Query<&Inventory, With<Player>>,
+    npc_query: Query<(&NPC, &Inventory), Without<Player>>,
+    mut text_query: Query<&mut Text, With<BarterText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    let Some((npc, npc_inventory)) = barter.npc.and_then(|entity| npc_query.get(entity).ok())
+    else {
+        return;
+    };
+    let mut body = format!("Trading with {}
+
+Their goods:", npc.name);
+    if npc_inventory.items.is_empty() {
+        body.push_str("
+  (nothing left)");
+    }
+    for (index, item) in npc_inventory.items.iter().enumerate() {
+        let mark = if barter.npc_pick == Some(index) { ">" } else { " " };
+        body.push_str(&format!(
+            "
+ {mark}{}. {} ({:.0})",
+            index + 1,
+            item.name,
+            crate::items::barter_value(item)
+        ));
+    }
+    body.push_str("
+
+Your pack:");
+    if inventory.items.is_empty() {
+        body.push_str("
+  (empty)");
+    }
+    for (index, item) in inventory.items.iter().enumerate() {
+        let mark = if barter.player_pick == Some(index) { ">" } else { " " };
+        body.push_str(&format!(
+            "
+ {mark}{}. {} ({:.0})",
+            index + 1,
+            item.name,
+            crate::items::barter_value(item)
+        ));
+    }
+    body.push_str("
+
+[1-9] their item   [Shift+1-9] yours   [Enter] trade   [Esc] leave");
+    text.sections[0].value = body;
+}
+
+pub fn cleanup_barter_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<BarterScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn setup_game_over_ui(mut commands: Commands, report: Option<Res<DeathReport>>) {
+    let mut body = match report.as_deref() {
+        Some(report) => format!(
+            "The mountain claims you — {}.\n\nAltitude reached: {:.0} m\nSurvived until day {}, {:02.0}:00",
+            report.cause, report.altitude, report.day, report.hour
+        ),
+        None => String::from("The mountain claims another climber."),
+    };
+    if crate::saves::latest_checkpoint().is_some() {
+        body.push_str("\n\n[Enter] retry from checkpoint");
+    } else {
+        body.push_str("\n\nNo checkpoint to return to.");
+    }
+    body.push_str("\n[Escape] return to the main menu");
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.08, 0.0, 0.0, 0.92).into(),
+                ..default()
+            },
+            GameOverScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                body,
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::srgb(0.9, 0.8, 0.8),
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub fn cleanup_game_over_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<GameOverScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn setup_skills_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.07, 0.05, 0.9).into(),
+                ..default()
+            },
+            SkillsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                SkillsText,
+            ));
+        });
+}
+
+/// Redraw the training ledger: level, progress toward the next, and
+/// what a point buys.
+pub fn update_skills_ui(
+    player_query: Query<(&Experience, &MovementStats, &Stamina, &Perks), With<Player>>,
+    mut text_query: Query<&mut Text, With<SkillsText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok((experience, stats, stamina, perks)) = player_query.get_single() else {
+        return;
+    };
+    let mut body = format!(
+        "Training\n\nLevel {}   XP {:.0}/{:.0}   Points: {}\n\n          1. Technique (climbing skill {:.1})\n          2. Endurance (max stamina {:.0})\n          3. Cold tolerance (level {})\n          4. Load-bearing (+{:.0} kg trained)\n\nPerks ({} points each):",
+        experience.level,
+        experience.xp,
+        experience.xp_to_next(),
+        experience.skill_points,
+        stats.climbing_skill,
+        stamina.max,
+        experience.trained_warmth,
+        experience.trained_carry,
+        crate::systems::PERK_COST
+    );
+    for (index, perk) in Perk::ALL.iter().enumerate() {
+        let mark = if perks.has(*perk) { "x" } else { " " };
+        body.push_str(&format!(
+            "\n [{mark}] {}. {} — {}",
+            index + 5,
+            perk.name(),
+            perk.blurb()
+        ));
+    }
+    body.push_str("\n\n[1-7] spend   [Esc] close");
+    text.sections[0].value = body;
+}
+
+pub fn cleanup_skills_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<SkillsScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn setup_magic_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.06, 0.04, 0.10, 0.9).into(),
+                ..default()
+            },
+            MagicScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                MagicText,
+            ));
+        });
+}
+
+/// Redraw the spellbook: mana, known spells with cost and readiness,
+/// and whatever effects are still running.
+pub fn update_magic_ui(
+    spells: Res<crate::components::ActiveSpells>,
+    caster_query: Query<&MagicUser, With<Player>>,
+    mut text_query: Query<&mut Text, With<MagicText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let Ok(caster) = caster_query.get_single() else {
+        return;
+    };
+    let mut body = format!("Spellbook\n\nMana: {:.0}/{:.0}\n", caster.mana, caster.max_mana);
+    if caster.known_spells.is_empty() {
+        body.push_str("\n  You know no spells yet");
+    }
+    for (index, spell) in caster.known_spells.iter().enumerate() {
+        let cooling = spells.cooldowns.get(&spell.name).copied().unwrap_or(0.0);
+        let readiness = if cooling > 0.0 {
+            format!("ready in {cooling:.0}s")
+        } else {
+            "ready".to_string()
+        };
+        body.push_str(&format!(
+            "\n {}. {} — {:.0} mana ({readiness})",
+            index + 1,
+            spell.name,
+            spell.mana_cost
+        ));
+    }
+    if !spells.effects.is_empty() {
+        body.push_str("\n\nActive:");
+        for active in spells.effects.iter() {
+            body.push_str(&format!("\n  {:?} ({:.0}s)", active.effect, active.remaining));
+        }
+    }
+    body.push_str("\n\n[1-9] cast   [Esc] close");
+    text.sections[0].value = body;
+}
+
+pub fn cleanup_magic_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<MagicScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn setup_inventory_ui(
+    mut commands: Commands,
+    book: Res<crate::items::RecipeBook>,
+    database: Res<crate::items::ItemDatabase>,
+    player_query: Query<(&Inventory, &EquippedItems), With<Player>>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::srgba(0.05, 0.05, 0.05, 0.9).into(),
+                ..default()
+            },
+            InventoryScreen,
+        ))
+        .with_children(|parent| {
+            let text = |value: &str| {
+                TextBundle::from_section(
+                    value,
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+            };
+            parent.spawn(text("Pack contents:"));
+            let Ok((inventory, equipped)) = player_query.get_single() else {
+                return;
+            };
+            for (index, item) in inventory.items.iter().enumerate() {
+                parent.spawn((
+                    text(&format!(
+                        "  {}. {} ({:.1} kg)",
+                        index + 1,
+                        item.name,
+                        item.properties.weight
+                    )),
+                    Interaction::default(),
+                    ItemRow { item: item.clone() },
+                ));
+            }
+            if inventory.items.is_empty() {
+                parent.spawn(text("  (empty)"));
+            }
+            parent.spawn(text(&format!(
+                "\nTotal: {:.1}/{:.1} kg",
+                inventory.current_weight(),
+                inventory.weight_limit
+            )));
+            let slots = equipped.slots();
+            if !slots.is_empty() {
+                parent.spawn(text("\nEquipped:"));
+                for item in slots {
+                    let line = if item.properties.max_durability > 0.0 {
+                        format!(
+                            "  {} ({:.0}%)",
+                            item.name,
+                            100.0 * item.properties.durability / item.properties.max_durability
+                        )
+                    } else {
+                        format!("  {}", item.name)
+                    };
+                    let mut row = parent.spawn((
+                        text(&line),
+                        Interaction::default(),
+                        ItemRow { item: item.clone() },
+                    ));
+                    if let Some(slot) = item.equipment_slot() {
+                        row.insert(EquipmentSlotLabel { slot });
+                    }
+                }
+            }
+            if !book.recipes.is_empty() {
+                parent.spawn(text("\nCrafting:"));
+                for (index, recipe) in book.recipes.iter().enumerate() {
+                    let output = database
+                        .get(&recipe.output)
+                        .map(|item| item.name)
+                        .unwrap_or_else(|| recipe.output.clone());
+                    let inputs = recipe
+                        .inputs
+                        .iter()
+                        .map(|(id, count)| {
+                            let name = database
+                                .get(id)
+                                .map(|item| item.name)
+                                .unwrap_or_else(|| id.clone());
+                            format!("{count}x {name}")
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" + ");
+                    parent.spawn(text(&format!(
+                        "  Shift+{}. {} ({})",
+                        index + 1,
+                        output,
+                        inputs
+                    )));
+                }
+            }
+            parent.spawn(text("\n[1-9] eat/drink/equip   [Shift+1-9] craft   [Ctrl+1-5] hotbar   [I] close"));
+        });
+    spawn_tooltip_panel(&mut commands);
+}
+
+/// The (initially hidden) panel `item_tooltip_system` fills on hover.
+pub fn spawn_tooltip_panel(commands: &mut Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(24.0),
+                    top: Val::Percent(30.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    display: Display::None,
+                    ..default()
+                },
+                background_color: Color::srgba(0.1, 0.1, 0.15, 0.95).into(),
+                ..default()
+            },
+            TooltipPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                TooltipText,
+            ));
+        });
+}
+
+/// Fill the tooltip with the hovered item's stats, each followed by a
+/// colored diff against whatever is equipped in the same slot.
+pub fn item_tooltip_system(
+    player_query: Query<&EquippedItems, With<Player>>,
+    row_query: Query<(&Interaction, &ItemRow)>,
+    mut panel_query: Query<&mut Style, With<TooltipPanel>>,
+    mut text_query: Query<&mut Text, With<TooltipText>>,
+) {
+    let Ok(mut style) = panel_query.get_single_mut() else {
+        return;
+    };
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+    let hovered = row_query
+        .iter()
+        .find(|(interaction, _)| matches!(interaction, Interaction::Hovered))
+        .map(|(_, row)| &row.item);
+    let Some(item) = hovered else {
+        style.display = Display::None;
+        return;
+    };
+    style.display = Display::Flex;
+
+    let equipped = player_query.get_single().ok().and_then(|equipped| {
+        item.equipment_slot()
+            .and_then(|slot| equipped.in_slot(slot))
+    });
+    let mut sections = vec![TextSection::new(
+        format!("{}\n", item.name),
+        TextStyle {
+            font_size: 18.0,
+            color: Color::srgb(1.0, 0.9, 0.5),
+            ..default()
+        },
+    )];
+    // (label, value, whether more of it is better)
+    let stats = [
+        ("Weight", item.properties.weight, false),
+        ("Durability", item.properties.durability, true),
+        ("Warmth", item.properties.warmth, true),
+        ("Strength", item.properties.strength, true),
+        ("Protection", item.properties.protection, true),
+    ];
+    let equipped_stats = equipped.map(|other| {
+        [
+            other.properties.weight,
+            other.properties.durability,
+            other.properties.warmth,
+            other.properties.strength,
+            other.properties.protection,
+        ]
+    });
+    for (index, (label, value, more_is_better)) in stats.into_iter().enumerate() {
+        sections.push(TextSection::new(
+            format!("{label}: {value:.1}"),
+            TextStyle {
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        ));
+        if let Some(other) = equipped_stats {
+            let diff = value - other[index];
+            if diff.abs() > f32::EPSILON {
+                let better = (diff > 0.0) == more_is_better;
+                sections.push(TextSection::new(
+                    format!("  {diff:+.1}"),
+                    TextStyle {
+                        font_size: 16.0,
+                        color: if better {
+                            Color::srgb(0.3, 0.85, 0.3)
+                        } else {
+                            Color::srgb(0.9, 0.3, 0.3)
+                        },
+                        ..default()
+                    },
+                ));
+            }
+        }
+        sections.push(TextSection::new(
+            "\n",
+            TextStyle {
+                font_size: 16.0,
+                ..default()
+            },
+        ));
+    }
+    *text = Text::from_sections(sections);
+}
+
+#[allow(clippy::type_complexity)]
+pub fn cleanup_inventory_ui(
+    mut commands: Commands,
+    screen_query: Query<Entity, Or<(With<InventoryScreen>, With<TooltipPanel>)>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Toggle the inventory state with the bound key (I by default).
+pub fn inventory_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    settings: Res<crate::settings::Settings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if settings
+        .bindings
+        .just_pressed(&keyboard, crate::settings::Action::ToggleInventory)
+    {
+        match state.get() {
+            GameState::Climbing => next_state.set(GameState::Inventory),
+            GameState::Inventory => next_state.set(GameState::Climbing),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{Hazardous, Player, TerrainTile, TerrainType, WarningMessage, WeatherSystem};
+use crate::levels::{self, CurrentLevel};
+use crate::terrain::{self, DirtyChunks, TerrainIndex};
+
+/// Where the volcano is in its cycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolcanoPhase {
+    Dormant,
+    /// Tremors warn of what's coming.
+    Rumbling,
+    /// Lava spreads from the vents and ash cuts visibility.
+    Erupting,
+    Cooling,
+}
+
+/// Drives eruptions on levels that have volcanic ground. Levels without
+/// any lava tiles leave the resource dormant forever.
+#[derive(Resource)]
+pub struct VolcanoActivity {
+    pub phase: VolcanoPhase,
+    pub phase_timer: Timer,
+    /// Lava tiles lava spreads outward from while erupting.
+    pub vents: Vec<(i32, i32)>,
+    /// Grid position the player should run for during an eruption.
+    pub safe_zone: Option<(i32, i32)>,
+    spread_timer: Timer,
+    /// Level the vents were scanned for.
+    scanned_level: String,
+}
+
+impl Default for VolcanoActivity {
+    fn default() -> Self {
+        Self {
+            phase: VolcanoPhase::Dormant,
+            phase_timer: Timer::from_seconds(90.0, TimerMode::Once),
+            vents: Vec::new(),
+            safe_zone: None,
+            spread_timer: Timer::from_seconds(0.6, TimerMode::Repeating),
+            scanned_level: String::new(),
+        }
+    }
+}
+
+/// Radius (in tiles) around the safe zone that counts as shelter.
+pub const SAFE_ZONE_RADIUS: i32 = 6;
+
+/// Step the eruption cycle: dormant -> rumbling tremors -> eruption
+/// with spreading lava and ash -> cooling -> dormant.
+#[allow(clippy::too_many_arguments)]
+pub fn volcano_scheduler_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut activity: ResMut<VolcanoActivity>,
+    mut weather: ResMut<WeatherSystem>,
+    mut warning: ResMut<WarningMessage>,
+    mut dirty: ResMut<DirtyChunks>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    mut tile_query: Query<&mut TerrainTile>,
+    player_query: Query<&Transform, With<Player>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    if activity.scanned_level != current_level.name {
+        activity.scanned_level = current_level.name.clone();
+        activity.vents = level
+            .terrain
+            .iter()
+            .filter(|tile| tile.terrain_type == TerrainType::Lava)
+            .map(|tile| (tile.x, tile.y))
+            .collect();
+        activity.phase = VolcanoPhase::Dormant;
+        activity.phase_timer = Timer::from_seconds(90.0, TimerMode::Once);
+        activity.safe_zone = None;
+    }
+    if activity.vents.is_empty() {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    let finished = activity.phase_timer.tick(time.delta()).just_finished();
+    match activity.phase {
+        VolcanoPhase::Dormant => {
+            if finished {
+                activity.phase = VolcanoPhase::Rumbling;
+                activity.phase_timer = Timer::from_seconds(12.0, TimerMode::Once);
+                warning.show("The mountain rumbles ominously...");
+            }
+        }
+        VolcanoPhase::Rumbling => {
+            // Tremors strengthen as the eruption nears
+            if let Ok(mut camera_transform) = camera_query.get_single_mut() {
+                let strength = 1.0 + 3.0 * activity.phase_timer.fraction();
+                camera_transform.translation.x += rng.gen_range(-strength..strength);
+                camera_transform.translation.y += rng.gen_range(-strength..strength) * 0.5;
+            }
+            if finished {
+                activity.phase = VolcanoPhase::Erupting;
+                activity.phase_timer = Timer::from_seconds(35.0, TimerMode::Once);
+                activity.safe_zone = Some(level.start_position);
+                warning.show("ERUPTION! Get back down to the coast!");
+            }
+        }
+        VolcanoPhase::Erupting => {
+            // Ash chokes the sky for the duration
+            weather.visibility = weather.visibility.min(0.3);
+            if activity.spread_timer.tick(time.delta()).just_finished() {
+                spread_lava(
+                    &mut commands,
+                    &mut activity,
+                    &index,
+                    &mut tile_query,
+                    &mut dirty,
+                    level,
+                    &mut rng,
+                );
+            }
+            if finished {
+                activity.phase = VolcanoPhase::Cooling;
+                activity.phase_timer = Timer::from_seconds(20.0, TimerMode::Once);
+                let survived_in_shelter = player_query.get_single().is_ok_and(|transform| {
+                    let (x, y) = levels::world_to_grid(
+                        transform.translation.truncate(),
+                        level.width,
+                        level.height,
+                    );
+                    activity.safe_zone.is_some_and(|(sx, sy)| {
+                        (x - sx).abs() <= SAFE_ZONE_RADIUS && (y - sy).abs() <= SAFE_ZONE_RADIUS
+                    })
+                });
+                if survived_in_shelter {
+                    warning.show("The eruption subsides. You made it to safety.");
+                } else {
+                    warning.show("The eruption subsides.");
+                }
+                activity.safe_zone = None;
+            }
+        }
+        VolcanoPhase::Cooling => {
+            if finished {
+                activity.phase = VolcanoPhase::Dormant;
+                activity.phase_timer =
+                    Timer::from_seconds(rng.gen_range(60.0..150.0), TimerMode::Once);
+                weather.visibility = 1.0;
+            }
+        }
+    }
+}
+
+/// Push one tongue of lava out from a random vent.
+fn spread_lava(
+    commands: &mut Commands,
+    activity: &mut VolcanoActivity,
+    index: &TerrainIndex,
+    tile_query: &mut Query<&mut TerrainTile>,
+    dirty: &mut DirtyChunks,
+    level: &levels::LevelDefinition,
+    rng: &mut impl Rng,
+) {
+    let (vent_x, vent_y) = activity.vents[rng.gen_range(0..activity.vents.len())];
+    let (dx, dy) = [(1, 0), (-1, 0), (0, 1), (0, -1)][rng.gen_range(0..4)];
+    let (x, y) = (vent_x + dx, vent_y + dy);
+    if x < 0 || y < 0 || x >= level.width || y >= level.height {
+        return;
+    }
+    let Some(entity) = index.get(x, y) else {
+        return; // outside the spawned chunks; nobody will miss it
+    };
+    let Ok(mut tile) = tile_query.get_mut(entity) else {
+        return;
+    };
+    if matches!(tile.terrain_type, TerrainType::Lava | TerrainType::Water) {
+        return; // water quenches the flow
+    }
+    tile.terrain_type = TerrainType::Lava;
+    tile.climbable = false;
+    tile.stability = 0.6;
+    commands.entity(entity).insert(Hazardous {
+        damage_per_second: TerrainType::Lava.hazard_damage().unwrap_or(20.0),
+    });
+    dirty.chunks.insert(terrain::chunk_of(x, y));
+    activity.vents.push((x, y));
+}
@@ -0,0 +1,403 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{Biome, GameTime, Player, TerrainTile, Weather, WeatherSystem};
+use crate::levels::{self, CurrentLevel, TILE_SIZE};
+use crate::terrain::TerrainIndex;
+
+/// A weather cell drifting across the level. What the player actually
+/// experiences ([`WeatherSystem`]) is sampled from whichever front
+/// covers them, so a storm can be watched rolling in from the coast.
+#[derive(Component)]
+pub struct WeatherFront {
+    pub weather: Weather,
+    pub radius: f32,
+    pub velocity: Vec2,
+    pub temperature: f32,
+    pub wind_speed: f32,
+}
+
+/// How long one casting of Stormstill holds the sky, in game hours.
+pub const WEATHER_CALM_HOURS: f32 = 6.0;
+
+/// A magical lull over the level: while it holds, standing fronts
+/// dissolve and no new ones blow in. The simulation picks back up on
+/// its own once the hours run out.
+#[derive(Resource, Default)]
+pub struct WeatherCalm {
+    pub remaining_hours: f32,
+}
+
+impl WeatherCalm {
+    pub fn invoke(&mut self, hours: f32) {
+        self.remaining_hours = self.remaining_hours.max(hours);
+    }
+
+    pub fn active(&self) -> bool {
+        self.remaining_hours > 0.0
+    }
+}
+
+/// Schedules new fronts blowing in off the sea.
+#[derive(Resource)]
+pub struct FrontSpawner {
+    timer: Timer,
+}
+
+impl Default for FrontSpawner {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(30.0, TimerMode::Repeating),
+        }
+    }
+}
+
+fn front_color(weather: Weather) -> Color {
+    match weather {
+        Weather::Storm => Color::srgba(0.15, 0.15, 0.25, 0.35),
+        Weather::Rain => Color::srgba(0.3, 0.35, 0.5, 0.25),
+        Weather::Snow => Color::srgba(0.9, 0.9, 0.95, 0.25),
+        Weather::Fog => Color::srgba(0.7, 0.7, 0.7, 0.3),
+        Weather::Cloudy => Color::srgba(0.5, 0.5, 0.55, 0.2),
+        Weather::Clear => Color::NONE,
+    }
+}
+
+/// Blow a new front in from below the coastline every so often.
+pub fn front_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    calm: Res<WeatherCalm>,
+    mut spawner: ResMut<FrontSpawner>,
+    current_level: Res<CurrentLevel>,
+) {
+    if !spawner.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    // A stilled sky admits no new fronts
+    if calm.active() {
+        return;
+    }
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let mut rng = rand::thread_rng();
+    let weather = match rng.gen_range(0..10) {
+        0..=2 => Weather::Cloudy,
+        3..=4 => Weather::Rain,
+        5..=6 => Weather::Snow,
+        7 => Weather::Storm,
+        8 => Weather::Fog,
+        _ => return, // clear spell: no front at all
+    };
+    let half_width = level.width as f32 * TILE_SIZE / 2.0;
+    let bottom = -(level.height as f32) * TILE_SIZE / 2.0;
+    let radius = rng.gen_range(8.0..24.0) * TILE_SIZE;
+    let position = Vec2::new(rng.gen_range(-half_width..half_width), bottom - radius);
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: front_color(weather),
+                custom_size: Some(Vec2::splat(radius * 2.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 4.0),
+            ..default()
+        },
+        WeatherFront {
+            weather,
+            radius,
+            // Fronts blow inland and up the mountain
+            velocity: Vec2::new(rng.gen_range(-8.0..8.0), rng.gen_range(10.0..28.0)),
+            temperature: match weather {
+                Weather::Snow | Weather::Storm => rng.gen_range(-18.0..-5.0),
+                Weather::Rain => rng.gen_range(-2.0..6.0),
+                _ => rng.gen_range(0.0..8.0),
+            },
+            wind_speed: match weather {
+                Weather::Storm => rng.gen_range(18.0..30.0),
+                Weather::Rain | Weather::Snow => rng.gen_range(8.0..18.0),
+                _ => rng.gen_range(2.0..10.0),
+            },
+        },
+    ));
+}
+
+/// Drift fronts along their track and retire the ones that leave the
+/// level.
+pub fn front_drift_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_level: Res<CurrentLevel>,
+    mut front_query: Query<(Entity, &mut Transform, &WeatherFront)>,
+) {
+    let Some(level) = &current_level.definition else {
+        return;
+    };
+    let half_width = level.width as f32 * TILE_SIZE / 2.0;
+    let half_height = level.height as f32 * TILE_SIZE / 2.0;
+    for (entity, mut transform, front) in front_query.iter_mut() {
+        transform.translation.x += front.velocity.x * time.delta_seconds();
+        transform.translation.y += front.velocity.y * time.delta_seconds();
+        if transform.translation.y > half_height + front.radius
+            || transform.translation.x.abs() > half_width + front.radius * 2.0
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// How fast a magical lull eats a front, world units of radius per
+/// second.
+const CALM_DISSOLVE_RATE: f32 = 40.0;
+
+/// Run down an invoked lull by the game clock and dissolve whatever
+/// fronts it caught in the open.
+pub fn calm_dissipation_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_time: Res<GameTime>,
+    mut calm: ResMut<WeatherCalm>,
+    mut front_query: Query<(Entity, &mut Sprite, &mut WeatherFront)>,
+) {
+    if !calm.active() {
+        return;
+    }
+    calm.remaining_hours =
+        (calm.remaining_hours - game_time.time_scale * time.delta_seconds()).max(0.0);
+    for (entity, mut sprite, mut front) in front_query.iter_mut() {
+        front.radius -= CALM_DISSOLVE_RATE * time.delta_seconds();
+        if front.radius < TILE_SIZE {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.custom_size = Some(Vec2::splat(front.radius * 2.0));
+    }
+}
+
+/// The calm between fronts.
+fn clear_conditions(weather: &mut WeatherSystem) {
+    weather.current_weather = Weather::Clear;
+    weather.temperature = 6.0;
+    weather.wind_speed = 4.0;
+    weather.wind_direction = Vec2::X;
+    weather.visibility = 1.0;
+}
+
+/// Degrees lost climbing from the coast to the summit row.
+const ALTITUDE_LAPSE: f32 = 12.0;
+
+/// What the local ground does to the conditions a front delivers.
+fn apply_terrain_conditions(
+    weather: &mut WeatherSystem,
+    altitude_fraction: f32,
+    biome: Option<Biome>,
+) {
+    weather.temperature -= ALTITUDE_LAPSE * altitude_fraction;
+    // Nothing breaks the wind up high
+    weather.wind_speed *= 1.0 + 0.6 * altitude_fraction;
+    match biome {
+        Some(Biome::Glacier) => weather.temperature -= 5.0,
+        Some(Biome::Coastal) => weather.wind_speed *= 1.4,
+        Some(Biome::Volcanic) => weather.temperature += 8.0,
+        Some(Biome::Forest) => weather.wind_speed *= 0.6,
+        _ => {}
+    }
+}
+
+/// Mirror the sampled weather into the `Weather` state machine so
+/// systems can gate on `in_state(Weather::Storm)` and the like.
+pub fn sync_weather_state(
+    weather: Res<WeatherSystem>,
+    state: Res<State<Weather>>,
+    mut next_state: ResMut<NextState<Weather>>,
+) {
+    if *state.get() != weather.current_weather {
+        next_state.set(weather.current_weather);
+    }
+}
+
+/// Sample the front covering the player into [`WeatherSystem`], then
+/// shade the result by altitude and biome, so the displayed conditions
+/// shift as you climb even under the same sky.
+pub fn local_weather_system(
+    mut weather: ResMut<WeatherSystem>,
+    index: Res<TerrainIndex>,
+    current_level: Res<CurrentLevel>,
+    tile_query: Query<&TerrainTile>,
+    front_query: Query<(&Transform, &WeatherFront)>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let position = player_transform.translation.truncate();
+    // The front whose center is nearest wins where several overlap
+    let covering = front_query
+        .iter()
+        .filter_map(|(transform, front)| {
+            let distance = position.distance(transform.translation.truncate());
+            (distance < front.radius).then_some((distance, front))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    match covering {
+        Some((_, front)) => {
+            weather.current_weather = front.weather;
+            weather.temperature = front.temperature;
+            weather.wind_speed = front.wind_speed;
+            weather.wind_direction = front.velocity.normalize_or(Vec2::X);
+            weather.visibility = match front.weather {
+                Weather::Fog => 0.3,
+                Weather::Storm => 0.5,
+                Weather::Snow => 0.7,
+                _ => 1.0,
+            };
+        }
+        None => clear_conditions(&mut weather),
+    }
+
+    if let Some(level) = &current_level.definition {
+        let (grid_x, grid_y) = levels::world_to_grid(position, level.width, level.height);
+        let altitude_fraction = (grid_y as f32 / level.height.max(1) as f32).clamp(0.0, 1.0);
+        let biome = index
+            .get(grid_x, grid_y)
+            .and_then(|entity| tile_query.get(entity).ok())
+            .map(|tile| tile.biome);
+        apply_terrain_conditions(&mut weather, altitude_fraction, biome);
+    }
+}
+
+/// One flake or rain streak.
+#[derive(Component)]
+pub struct WeatherParticle {
+    pub velocity: Vec2,
+}
+
+/// Full-screen haze whose density tracks `WeatherSystem.visibility`.
+#[derive(Component)]
+pub struct FogOverlay;
+
+/// Particles kept alive per falling-weather type.
+const PARTICLE_BUDGET: usize = 180;
+
+/// Sprinkle snow and rain sprites above the camera while the local
+/// weather calls for them.
+pub fn weather_particle_spawn_system(
+    mut commands: Commands,
+    weather: Res<WeatherSystem>,
+    camera_query: Query<&Transform, With<Camera>>,
+    particle_query: Query<(), With<WeatherParticle>>,
+) {
+    let (count, color, size, fall_speed) = match weather.current_weather {
+        Weather::Snow => (PARTICLE_BUDGET, Color::srgba(1.0, 1.0, 1.0, 0.9), Vec2::splat(3.0), 40.0),
+        Weather::Rain => (PARTICLE_BUDGET, Color::srgba(0.5, 0.6, 0.9, 0.8), Vec2::new(1.5, 9.0), 260.0),
+        Weather::Storm => (PARTICLE_BUDGET * 2, Color::srgba(0.5, 0.6, 0.9, 0.8), Vec2::new(1.5, 12.0), 340.0),
+        _ => return,
+    };
+    if particle_query.iter().count() >= count {
+        return;
+    }
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let mut rng = rand::thread_rng();
+    for _ in 0..6 {
+        let offset = Vec2::new(rng.gen_range(-450.0..450.0), rng.gen_range(250.0..350.0));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_xyz(
+                    camera_transform.translation.x + offset.x,
+                    camera_transform.translation.y + offset.y,
+                    6.0,
+                ),
+                ..default()
+            },
+            WeatherParticle {
+                velocity: Vec2::new(
+                    weather.wind_speed * rng.gen_range(0.5..1.5),
+                    -fall_speed * rng.gen_range(0.8..1.2),
+                ),
+            },
+        ));
+    }
+}
+
+/// Move particles with the wind and drop the ones that fall out of
+/// view — or all of them when the sky clears.
+pub fn weather_particle_move_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    weather: Res<WeatherSystem>,
+    camera_query: Query<&Transform, (With<Camera>, Without<WeatherParticle>)>,
+    mut particle_query: Query<(Entity, &mut Transform, &WeatherParticle)>,
+) {
+    let falling_weather = matches!(
+        weather.current_weather,
+        Weather::Snow | Weather::Rain | Weather::Storm
+    );
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    for (entity, mut transform, particle) in particle_query.iter_mut() {
+        if !falling_weather {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.x += particle.velocity.x * time.delta_seconds();
+        transform.translation.y += particle.velocity.y * time.delta_seconds();
+        if transform.translation.y < camera_transform.translation.y - 350.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Keep a camera-sized haze sprite whose opacity follows how little
+/// the player can see.
+pub fn fog_overlay_system(
+    mut commands: Commands,
+    weather: Res<WeatherSystem>,
+    camera_query: Query<&Transform, (With<Camera>, Without<FogOverlay>)>,
+    mut overlay_query: Query<(Entity, &mut Transform, &mut Sprite), With<FogOverlay>>,
+) {
+    let density = (1.0 - weather.visibility).clamp(0.0, 1.0) * 0.75;
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    match overlay_query.get_single_mut() {
+        Ok((entity, mut transform, mut sprite)) => {
+            if density <= 0.0 {
+                commands.entity(entity).despawn();
+                return;
+            }
+            transform.translation.x = camera_transform.translation.x;
+            transform.translation.y = camera_transform.translation.y;
+            sprite.color = Color::srgba(0.75, 0.77, 0.8, density);
+        }
+        Err(_) => {
+            if density <= 0.0 {
+                return;
+            }
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::srgba(0.75, 0.77, 0.8, density),
+                        custom_size: Some(Vec2::new(2000.0, 1400.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(
+                        camera_transform.translation.x,
+                        camera_transform.translation.y,
+                        7.0,
+                    ),
+                    ..default()
+                },
+                FogOverlay,
+            ));
+        }
+    }
+}